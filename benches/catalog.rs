@@ -0,0 +1,32 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use twine::Catalog;
+
+fn catalog_benchmark(c: &mut Criterion) {
+    let ini: String = (0..1000)
+        .map(|i| {
+            format!(
+                r#"
+                [band_tool_{}]
+                    en = Tool
+                    fr = Outil
+                "#,
+                i,
+            )
+        })
+        .collect();
+    let mut binary = Vec::new();
+    ini.parse::<Catalog>()
+        .unwrap()
+        .serialize_binary(&mut binary)
+        .unwrap();
+
+    let mut group = c.benchmark_group("catalog");
+    group.bench_function("parse_ini", |b| b.iter(|| ini.parse::<Catalog>().unwrap()));
+    group.bench_function("load_binary", |b| {
+        b.iter(|| Catalog::load_binary(&mut binary.as_slice()).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, catalog_benchmark);
+criterion_main!(benches);
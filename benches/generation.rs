@@ -25,11 +25,42 @@ fn generation_benchmark(c: &mut Criterion) {
         b.iter(|| {
             generation(
                 &strs.iter().map(|x| x.as_str()).collect::<Vec<_>>(),
-                &output_file,
+                output_file,
             )
         })
     });
 }
 
-criterion_group!(benches, generation_benchmark);
+fn placeholders_benchmark(c: &mut Criterion) {
+    let out_dir = tempfile::tempdir().unwrap();
+    std::env::set_var("OUT_DIR", out_dir.as_ref().as_os_str());
+    let output_file = Path::new("test");
+    let value = "%d %s %.2f %#x ".repeat(5);
+    let strs: Vec<_> = (0..500)
+        .map(|i| {
+            format!(
+                r#"
+                [format_mixed_{i}]
+                    en = {value}
+                    en-gb = {value}
+                    fr = {value}
+                    de = {value}
+                    nl = {value}
+                "#,
+                i = i,
+                value = value,
+            )
+        })
+        .collect();
+    c.bench_function("placeholders", |b| {
+        b.iter(|| {
+            generation(
+                &strs.iter().map(|x| x.as_str()).collect::<Vec<_>>(),
+                output_file,
+            )
+        })
+    });
+}
+
+criterion_group!(benches, generation_benchmark, placeholders_benchmark);
 criterion_main!(benches);
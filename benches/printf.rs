@@ -0,0 +1,53 @@
+// Compares the scanner of the values (src/printf.rs) with the regex it replaced, kept here as the
+// baseline.
+use criterion::{criterion_group, criterion_main, Criterion};
+use regex::Regex;
+
+#[path = "../src/printf.rs"]
+#[allow(dead_code)]
+mod printf;
+
+// the regex used to parse the values before the scanner
+// see: https://docs.microsoft.com/en-us/cpp/c-runtime-library/format-specification-syntax-printf-and-wprintf-functions?view=msvc-160
+const RE_PRINTF: &str = r#"%((?P<parameter>\d+)\$)?(?P<flags>[-+#])?(?P<width>\d+)?(?P<precision>\.\d+)?(?P<type>[dis@xXf])|\\u(?P<unicode>[0-9a-fA-F]{4})|\\.|%%|%|"|(?P<text>[^%"\\]+)"#;
+
+fn printf_benchmark(c: &mut Criterion) {
+    let re_printf = Regex::new(RE_PRINTF).unwrap();
+    let values: Vec<String> = (0..500)
+        .map(|i| {
+            format!(
+                r#"Value {} with %d %s %.2f %#x, %1$-5.2f, 50 %%, \"quotes\", é\n"#,
+                i,
+            )
+        })
+        .collect();
+    // both split the values into the same fragments
+    for value in values.iter() {
+        assert_eq!(
+            re_printf.find_iter(value).count(),
+            printf::fragments(value).count(),
+        );
+    }
+
+    let mut group = c.benchmark_group("printf");
+    group.bench_function("regex", |b| {
+        b.iter(|| {
+            values
+                .iter()
+                .map(|value| re_printf.captures_iter(value).count())
+                .sum::<usize>()
+        })
+    });
+    group.bench_function("scanner", |b| {
+        b.iter(|| {
+            values
+                .iter()
+                .map(|value| printf::fragments(value).count())
+                .sum::<usize>()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, printf_benchmark);
+criterion_main!(benches);
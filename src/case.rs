@@ -0,0 +1,71 @@
+// Case conversion of the keys: the same word boundaries as heck but the Unicode letters are kept
+// (heck drops everything that is not ASCII when its feature `unicode` is disabled).
+
+/// Splits a key into words: on the characters that are neither letters nor digits, before an
+/// uppercase letter that follows a lowercase letter and before the last uppercase letter of an
+/// acronym followed by a lowercase letter (`HTTPServer`).
+fn words(key: &str) -> Vec<&str> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum Mode {
+        Boundary,
+        Lowercase,
+        Uppercase,
+    }
+
+    let mut words = Vec::new();
+    for word in key.split(|c: char| !c.is_alphanumeric()) {
+        let mut chars = word.char_indices().peekable();
+        let mut init = 0;
+        let mut mode = Mode::Boundary;
+
+        while let Some((i, c)) = chars.next() {
+            if let Some(&(next_i, next)) = chars.peek() {
+                let next_mode = if c.is_lowercase() {
+                    Mode::Lowercase
+                } else if c.is_uppercase() {
+                    Mode::Uppercase
+                } else {
+                    mode
+                };
+
+                if next_mode == Mode::Lowercase && next.is_uppercase() {
+                    words.push(&word[init..next_i]);
+                    init = next_i;
+                    mode = Mode::Boundary;
+                } else if mode == Mode::Uppercase && c.is_uppercase() && next.is_lowercase() {
+                    words.push(&word[init..i]);
+                    init = i;
+                    mode = Mode::Boundary;
+                } else {
+                    mode = next_mode;
+                }
+            } else {
+                words.push(&word[init..]);
+                break;
+            }
+        }
+    }
+    words
+}
+
+/// `café_Button.Title` => `café_button_title`
+pub(crate) fn to_snake_case(key: &str) -> String {
+    words(key)
+        .iter()
+        .map(|x| x.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// `café_button` => `CaféButton`
+pub(crate) fn to_upper_camel_case(key: &str) -> String {
+    let mut out = String::with_capacity(key.len());
+    for word in words(key) {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            out.extend(first.to_uppercase());
+            out.push_str(&chars.as_str().to_lowercase());
+        }
+    }
+    out
+}
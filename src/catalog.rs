@@ -0,0 +1,959 @@
+use crate::printf;
+use crate::{
+    is_plural, parse_placeholders, placeholders, read_twine_ini, Section, TwineData,
+    TwineFormatter, DEFAULTS_SECTION, LANGUAGES_SECTION, SCHEMA_SECTION,
+};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::convert::TryFrom;
+use std::fs;
+use std::io;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+// the header of the binary format: a magic number and the version of the format
+const BINARY_MAGIC: &[u8; 4] = b"TWNC";
+const BINARY_VERSION: u8 = 1;
+
+// the tags of the metadata of a section in the binary format, a new metadata gets a new tag: the
+// readers skip the tags they do not know
+const TAG_MAXLEN: u32 = 1;
+const TAG_DEPRECATED: u32 = 2;
+const TAG_CONTEXT: u32 = 3;
+const TAG_ORDER: u32 = 4;
+const TAG_TM_ID: u32 = 5;
+const TAG_MARKDOWN: u32 = 6;
+const TAG_FUZZY: u32 = 7;
+
+// the keys used by the macros: `t!(key`, `t_raw!(key`, `t_static!(key`, `tl!(key`,
+// `t_default!(key`, `plural!(key`, `ordinal!(key`, `t_bytes!(key`, `t_into!(buf, key` and
+// `t_bytes!(&mut buf, key`
+static RE_MACRO_CALL: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"\b(?:(?:t|t_raw|t_static|tl|t_default|plural|ordinal|t_bytes)!\s*[(\[{]|(?:t_into!\s*[(\[{]|t_bytes!\s*[(\[{]\s*&\s*mut\b)[^,]*,)\s*(\w+)",
+    )
+    .unwrap()
+});
+
+/// Translations read from Twine INI files.
+///
+/// ```
+/// let catalog: twine::Catalog = r#"
+///     [band_tool]
+///         en = Tool
+///         fr = Outil
+/// "#
+/// .parse()
+/// .unwrap();
+///
+/// assert_eq!(catalog.get("band_tool", "fr"), Some("Outil"));
+/// ```
+#[derive(Debug, Default)]
+pub struct Catalog {
+    pub(crate) map: TwineData,
+    // the name of the file every translation comes from, by key and language
+    sources: HashMap<(String, String), String>,
+}
+
+impl Catalog {
+    /// Read the translations from a reader containing Twine INI translations.
+    pub fn from_reader<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Ok(Self {
+            map: read_twine_ini(reader, None)?,
+            sources: HashMap::new(),
+        })
+    }
+
+    /// Read the translations from a reader and record `name` (usually the path of the file) as
+    /// their source, returned by [`Catalog::source_of`].
+    ///
+    /// The sources are kept by [`Catalog::merge`]: every translation keeps the name of the
+    /// catalog its value comes from.
+    pub fn from_named_reader<R: Read>(name: &str, reader: &mut R) -> io::Result<Self> {
+        let map = read_twine_ini(reader, None)?;
+        let sources = map
+            .iter()
+            .flat_map(|(key, section)| {
+                section
+                    .translations
+                    .iter()
+                    .map(move |(lang, _)| ((key.clone(), lang.clone()), name.to_string()))
+            })
+            .collect();
+
+        Ok(Self { map, sources })
+    }
+
+    /// Returns the name of the source of the translation of a key for a language (see
+    /// [`Catalog::from_named_reader`]), `None` if the translation does not exist or was read
+    /// without a name.
+    pub fn source_of(&self, key: &str, lang: &str) -> Option<&str> {
+        self.sources
+            .get(&(key.to_string(), lang.to_string()))
+            .map(String::as_str)
+    }
+
+    /// Returns all the keys sorted alphabetically.
+    pub fn keys(&self) -> Vec<&str> {
+        let mut keys: Vec<_> = self
+            .map
+            .keys()
+            .map(String::as_str)
+            .filter(|key| ![LANGUAGES_SECTION, DEFAULTS_SECTION, SCHEMA_SECTION].contains(key))
+            .collect();
+        keys.sort_unstable();
+        keys
+    }
+
+    /// Returns the translation of a key for a language as written in the INI file (`en`,
+    /// `en-gb`, ...).
+    pub fn get(&self, key: &str, lang: &str) -> Option<&str> {
+        self.map.get(key).and_then(|section| {
+            section
+                .translations
+                .iter()
+                .find(|(x, _)| x == lang)
+                .map(|(_, text)| text.as_str())
+        })
+    }
+
+    /// Returns the languages of a key as written in the INI file (`en`, `en-gb`, ...).
+    pub fn languages(&self, key: &str) -> Vec<&str> {
+        self.map
+            .get(key)
+            .map(|section| {
+                section
+                    .translations
+                    .iter()
+                    .map(|(lang, _)| lang.as_str())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns the languages of all the keys grouped by their language without region:
+    /// `{"en": ["en", "en-gb"], "fr": ["fr"]}`, e.g. for a picker choosing the language then the
+    /// region.
+    ///
+    /// The codes are written as in the INI file and sorted, the plural categories are ignored. A
+    /// region without translation in its language still has its group (`{"de": ["de-ch"]}`).
+    pub fn group_by_language(&self) -> HashMap<String, Vec<String>> {
+        let codes: BTreeSet<&str> = self
+            .keys()
+            .into_iter()
+            .flat_map(|key| self.languages(key))
+            .map(|lang| lang.split('.').next().unwrap_or(lang))
+            .collect();
+        let mut groups: HashMap<String, Vec<String>> = HashMap::new();
+        for code in codes {
+            let language = code.split('-').next().unwrap_or(code);
+            groups
+                .entry(language.to_string())
+                .or_default()
+                .push(code.to_string());
+        }
+        groups
+    }
+
+    /// Returns the URL given by the metadata `context` of a key: a screenshot or a page showing
+    /// where the key is used.
+    pub fn context(&self, key: &str) -> Option<&str> {
+        self.map
+            .get(key)
+            .and_then(|section| section.context.as_deref())
+    }
+
+    /// Returns the ID given by the metadata `tm_id` of a key: the stable ID of the string in an
+    /// external translation memory (e.g. `CROWDIN-12345`).
+    pub fn external_id(&self, key: &str) -> Option<&str> {
+        self.map
+            .get(key)
+            .and_then(|section| section.tm_id.as_deref())
+    }
+
+    /// Returns `true` if the translations of a key are markdown (metadata `format = markdown`):
+    /// the UI renders them as markdown instead of plain text.
+    pub fn is_markdown(&self, key: &str) -> bool {
+        self.map.get(key).map_or(false, |section| section.markdown)
+    }
+
+    /// Returns `true` if the translation of a key for a language is marked with the metadata
+    /// `fuzzy`: it needs to be reviewed again.
+    pub fn is_fuzzy(&self, key: &str, lang: &str) -> bool {
+        self.map
+            .get(key)
+            .map_or(false, |section| section.fuzzy.iter().any(|x| x == lang))
+    }
+
+    /// Returns the kind of the printf's format placeholders of the translation of a key for a
+    /// language, one for every argument in the order of the arguments: `[Float, Str]` for
+    /// `%2$s: %1$.1f`.
+    ///
+    /// The language is written as in the INI file (`en`, `en-gb`, `en.one`, ...). The list is
+    /// empty if the key is not translated in this language.
+    pub fn placeholders(&self, key: &str, lang: &str) -> Vec<PlaceholderKind> {
+        let mut placeholders = self
+            .get(key, lang)
+            .map(parse_placeholders)
+            .unwrap_or_default();
+        placeholders.sort_by_key(|(position, _)| *position);
+        placeholders.dedup_by_key(|(position, _)| *position);
+        placeholders.into_iter().map(|(_, kind)| kind).collect()
+    }
+
+    /// Returns the ratio between the length (in characters) of every translation and the length of
+    /// the translation of `source_lang` (e.g. `en`) of its key, the longest first: the
+    /// translations likely to overflow the layout of the UI come first.
+    ///
+    /// A plural category is compared to the same category of the source language (`fr.one` to
+    /// `en.one`) or to its category `other`. The keys not translated in the source language or
+    /// with an empty source are skipped.
+    pub fn expansion_report(&self, source_lang: &str) -> Vec<(&str, &str, f64)> {
+        let mut report = Vec::new();
+        for key in self.keys() {
+            let section = &self.map[key];
+            let source = |lang: &str| {
+                section
+                    .translations
+                    .iter()
+                    .find(|(x, _)| x == lang)
+                    .map(|(_, text)| text.chars().count())
+            };
+
+            for (lang, text) in section.translations.iter() {
+                if lang.split('.').next() == Some(source_lang) {
+                    continue;
+                }
+                let length = match lang.find('.').map(|i| &lang[i..]) {
+                    Some(category) => source(&format!("{}{}", source_lang, category))
+                        .or_else(|| source(&format!("{}.other", source_lang))),
+                    None => source(source_lang),
+                };
+                if let Some(length) = length.filter(|x| *x > 0) {
+                    let ratio = text.chars().count() as f64 / length as f64;
+                    report.push((key, lang.as_str(), ratio));
+                }
+            }
+        }
+        report.sort_by(|(a_key, a_lang, a), (b_key, b_lang, b)| {
+            b.total_cmp(a)
+                .then_with(|| a_key.cmp(b_key))
+                .then_with(|| a_lang.cmp(b_lang))
+        });
+
+        report
+    }
+
+    /// Run all the checks on the translations and return every problem found, sorted by key and
+    /// language:
+    ///
+    ///  *  the languages missing in a key (warning);
+    ///  *  the translations that do not use the placeholders of the reference translation
+    ///     (warning), the plural keys are not checked;
+    ///  *  the empty translations (warning);
+    ///  *  the printf's format placeholders that are not valid and are kept as text: `%z`
+    ///     (warning);
+    ///  *  the keys that have the same name in the generated code: `band.tool` and `band_tool`
+    ///     (error).
+    pub fn validate(&self, opts: ValidateOpts) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        // the languages without region and plural category
+        let base_lang = |lang: &str| {
+            lang.split(&['-', '.'][..])
+                .next()
+                .unwrap_or(lang)
+                .to_string()
+        };
+        let all_langs: BTreeSet<_> = self
+            .keys()
+            .into_iter()
+            .flat_map(|key| self.map[key].translations.iter())
+            .map(|(lang, _)| base_lang(lang))
+            .collect();
+        let mut names: HashMap<String, &str> = HashMap::new();
+
+        for key in self.keys() {
+            let section = &self.map[key];
+
+            if let Some(other) = names.insert(TwineFormatter::normalize_key(key), key) {
+                diagnostics.push(Diagnostic {
+                    severity: Severity::Error,
+                    kind: DiagnosticKind::KeyCollision,
+                    key: key.to_string(),
+                    lang: None,
+                    message: format!("key {:?} has the same name as {:?}", key, other),
+                });
+            }
+
+            let langs: BTreeSet<_> = section
+                .translations
+                .iter()
+                .map(|(lang, _)| base_lang(lang))
+                .collect();
+            for lang in all_langs.difference(&langs) {
+                diagnostics.push(Diagnostic {
+                    severity: Severity::Warning,
+                    kind: DiagnosticKind::MissingLanguage,
+                    key: key.to_string(),
+                    lang: Some(lang.clone()),
+                    message: format!("key {:?} is not translated in {:?}", key, lang),
+                });
+            }
+
+            let reference = if is_plural(&section.translations) {
+                None
+            } else {
+                section
+                    .translations
+                    .iter()
+                    .find(|(lang, _)| Some(lang) == opts.source_lang.as_ref())
+                    .or_else(|| section.translations.first())
+            };
+            for (lang, text) in section.translations.iter() {
+                if text.trim().is_empty() {
+                    diagnostics.push(Diagnostic {
+                        severity: Severity::Warning,
+                        kind: DiagnosticKind::EmptyValue,
+                        key: key.to_string(),
+                        lang: Some(lang.clone()),
+                        message: format!("translation {:?} of key {:?} is empty", lang, key),
+                    });
+                }
+
+                if let Some(placeholder) = printf::invalid_placeholders(text).first() {
+                    diagnostics.push(Diagnostic {
+                        severity: Severity::Warning,
+                        kind: DiagnosticKind::InvalidPlaceholder,
+                        key: key.to_string(),
+                        lang: Some(lang.clone()),
+                        message: format!(
+                            "translation {:?} of key {:?} contains the invalid placeholder {:?}",
+                            lang, key, placeholder,
+                        ),
+                    });
+                }
+
+                if let Some((reference_lang, reference_text)) = reference {
+                    let expected = placeholders(reference_text);
+                    let actual = placeholders(text);
+                    if actual != expected {
+                        diagnostics.push(Diagnostic {
+                            severity: Severity::Warning,
+                            kind: DiagnosticKind::PlaceholderMismatch,
+                            key: key.to_string(),
+                            lang: Some(lang.clone()),
+                            message: format!(
+                                "translation {:?} of key {:?} uses the placeholders {:?} instead \
+                                of {:?} ({:?})",
+                                lang, key, actual, expected, reference_lang,
+                            ),
+                        });
+                    }
+                }
+            }
+        }
+
+        diagnostics.sort_by(|a, b| (&a.key, &a.lang).cmp(&(&b.key, &b.lang)));
+        diagnostics
+    }
+
+    /// Write the translations in the Twine INI format.
+    ///
+    /// The sections are written in the order they have been read and the comments (lines
+    /// starting with `;` or `#`) are written back before the section or the translation they
+    /// precede.
+    pub fn write_ini<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let mut sorted: Vec<_> = self.map.iter().collect();
+        sorted.sort_unstable_by_key(|(key, section)| (section.position, *key));
+
+        for (key, section) in sorted {
+            for comment in section.comments.iter() {
+                writeln!(writer, "{}", comment)?;
+            }
+            writeln!(writer, "[{}]", key.replace(']', r"\]"))?;
+            if let Some(maxlen) = section.maxlen {
+                writeln!(writer, "    maxlen = {}", maxlen)?;
+            }
+            if let Some(deprecated) = section.deprecated.as_ref() {
+                writeln!(writer, "    deprecated = {}", deprecated)?;
+            }
+            if let Some(context) = section.context.as_ref() {
+                writeln!(writer, "    context = {}", context)?;
+            }
+            if let Some(order) = section.order {
+                writeln!(writer, "    order = {}", order)?;
+            }
+            if let Some(id) = section.tm_id.as_ref() {
+                writeln!(writer, "    tm_id = {}", id)?;
+            }
+            if section.markdown {
+                writeln!(writer, "    format = markdown")?;
+            }
+            if !section.fuzzy.is_empty() {
+                writeln!(writer, "    fuzzy = {}", section.fuzzy.join(", "))?;
+            }
+            for (lang, text) in section.translations.iter() {
+                for comment in section.translation_comments.get(lang).into_iter().flatten() {
+                    writeln!(writer, "    {}", comment)?;
+                }
+                if key == LANGUAGES_SECTION {
+                    writeln!(writer, "    {}", lang)?;
+                } else {
+                    writeln!(writer, "    {} = {}", lang, text)?;
+                }
+            }
+            for comment in section.trailing_comments.iter() {
+                writeln!(writer, "{}", comment)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write the translations in a compact binary format that [`Catalog::load_binary`] reads
+    /// faster than the INI format.
+    ///
+    /// The format starts with the magic number `TWNC` and a version byte, then every section is
+    /// written with its key, its metadata and its translations. The integers are little-endian
+    /// `u32` and the strings are prefixed by their length in bytes. The metadata are tagged and
+    /// prefixed by their length: a catalog written with metadata unknown to the reader can still
+    /// be loaded. The comments are not written: the format is meant to be loaded by the
+    /// application, not edited.
+    pub fn serialize_binary<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let mut sorted: Vec<_> = self.map.iter().collect();
+        sorted.sort_unstable_by_key(|(key, section)| (section.position, *key));
+
+        writer.write_all(BINARY_MAGIC)?;
+        writer.write_all(&[BINARY_VERSION])?;
+        write_u32(writer, sorted.len())?;
+        for (key, section) in sorted {
+            write_str(writer, key)?;
+            write_u32(writer, section.position)?;
+
+            let mut fields: Vec<(u32, Vec<u8>)> = Vec::new();
+            if let Some(maxlen) = section.maxlen {
+                let mut payload = Vec::new();
+                write_u32(&mut payload, maxlen)?;
+                fields.push((TAG_MAXLEN, payload));
+            }
+            if let Some(deprecated) = section.deprecated.as_deref() {
+                fields.push((TAG_DEPRECATED, deprecated.as_bytes().to_vec()));
+            }
+            if let Some(context) = section.context.as_deref() {
+                fields.push((TAG_CONTEXT, context.as_bytes().to_vec()));
+            }
+            if let Some(order) = section.order {
+                fields.push((TAG_ORDER, order.to_string().into_bytes()));
+            }
+            if let Some(id) = section.tm_id.as_deref() {
+                fields.push((TAG_TM_ID, id.as_bytes().to_vec()));
+            }
+            if section.markdown {
+                fields.push((TAG_MARKDOWN, Vec::new()));
+            }
+            if !section.fuzzy.is_empty() {
+                let mut payload = Vec::new();
+                write_u32(&mut payload, section.fuzzy.len())?;
+                for lang in section.fuzzy.iter() {
+                    write_str(&mut payload, lang)?;
+                }
+                fields.push((TAG_FUZZY, payload));
+            }
+            write_u32(writer, fields.len())?;
+            for (tag, payload) in fields {
+                write_u32(writer, tag as usize)?;
+                write_u32(writer, payload.len())?;
+                writer.write_all(&payload)?;
+            }
+
+            write_u32(writer, section.translations.len())?;
+            for (lang, text) in section.translations.iter() {
+                write_str(writer, lang)?;
+                write_str(writer, text)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Read the translations written by [`Catalog::serialize_binary`].
+    ///
+    /// An error of kind [`io::ErrorKind::InvalidData`] is returned if the data is not a catalog
+    /// or was written by another version of the format.
+    pub fn load_binary<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut header = [0; 5];
+        reader.read_exact(&mut header)?;
+        if &header[..4] != BINARY_MAGIC {
+            return Err(invalid_data("not a binary catalog".to_string()));
+        }
+        let version = header[4];
+        if version != BINARY_VERSION {
+            return Err(invalid_data(format!(
+                "unsupported binary catalog version {} (supported: {}), write it again with \
+                Catalog::serialize_binary() from the INI files",
+                version, BINARY_VERSION,
+            )));
+        }
+
+        let len = read_u32(reader)?;
+        let mut map = TwineData::with_capacity(len.min(1 << 16));
+        for _ in 0..len {
+            let key = read_str(reader)?;
+            let mut section = Section {
+                position: read_u32(reader)?,
+                ..Section::default()
+            };
+            read_tagged_metadata(reader, &mut section)?;
+            let count = read_u32(reader)?;
+            let mut translations = Vec::with_capacity(count.min(1 << 8));
+            for _ in 0..count {
+                translations.push((read_str(reader)?, read_str(reader)?));
+            }
+            section.translations = translations;
+            map.insert(key, section);
+        }
+
+        Ok(Self {
+            map,
+            sources: HashMap::new(),
+        })
+    }
+
+    /// Returns the translations by language then by key, the shape expected by many frontend
+    /// libraries (e.g. i18next): `{"en": {"band_tool": "Tool"}, "en_gb": {...}, "fr": {...}}`.
+    ///
+    /// The regions are separate languages named like the `Lang` displayed (`en_gb`) and the plural
+    /// categories are suffixes of the keys (`n_files_one`, `n_files_other`). The translations are
+    /// written as in the INI file, the printf's placeholders are not converted.
+    #[cfg(feature = "catalog-serde")]
+    pub fn to_json_by_lang(&self) -> serde_json::Value {
+        let mut langs = serde_json::Map::new();
+        for key in self.keys() {
+            for (lang, text) in self.map[key].translations.iter() {
+                let (lang, category) = match lang.split_once('.') {
+                    Some((lang, category)) => (lang, Some(category)),
+                    None => (lang.as_str(), None),
+                };
+                let name = match category {
+                    Some(category) => format!("{}_{}", key, category),
+                    None => key.to_string(),
+                };
+                langs
+                    .entry(lang.replace('-', "_").to_lowercase())
+                    .or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()))
+                    .as_object_mut()
+                    .expect("the languages are objects")
+                    .insert(name, serde_json::Value::String(text.clone()));
+            }
+        }
+        serde_json::Value::Object(langs)
+    }
+
+    /// Compare this catalog (the old one) to another catalog (the new one).
+    pub fn diff(&self, other: &Catalog) -> CatalogDiff {
+        let old_keys: BTreeSet<_> = self.map.keys().collect();
+        let new_keys: BTreeSet<_> = other.map.keys().collect();
+        let mut diff = CatalogDiff {
+            added_keys: new_keys
+                .difference(&old_keys)
+                .map(|x| x.to_string())
+                .collect(),
+            removed_keys: old_keys
+                .difference(&new_keys)
+                .map(|x| x.to_string())
+                .collect(),
+            changes: Vec::new(),
+        };
+
+        for key in old_keys.intersection(&new_keys) {
+            let langs: BTreeSet<_> = self
+                .languages(key)
+                .into_iter()
+                .chain(other.languages(key))
+                .collect();
+            for lang in langs {
+                let old = self.get(key, lang);
+                let new = other.get(key, lang);
+                if old != new {
+                    diff.changes.push(TranslationChange {
+                        key: key.to_string(),
+                        lang: lang.to_string(),
+                        old: old.map(ToString::to_string),
+                        new: new.map(ToString::to_string),
+                    });
+                }
+            }
+        }
+
+        diff
+    }
+
+    /// Returns the keys that are never used by the macros in the Rust files of the source
+    /// directories (searched recursively), sorted alphabetically.
+    ///
+    /// This is a heuristic based on the text of the files: the calls `t!(key`, `t_into!(buf, key`,
+    /// `t_raw!(key`, `t_static!(key`, `plural!(key` and `ordinal!(key` are searched, including in
+    /// the comments.
+    /// The keys used through a macro renamed by an import, a macro wrapping these macros or the
+    /// `Key` enum are reported as unused.
+    pub fn find_unused<P: AsRef<Path>>(&self, src_dirs: &[P]) -> io::Result<Vec<String>> {
+        let mut used = HashSet::new();
+        for src_dir in src_dirs {
+            find_used_keys(src_dir.as_ref(), &mut used)?;
+        }
+
+        Ok(self
+            .keys()
+            .into_iter()
+            .filter(|key| !used.contains(&TwineFormatter::normalize_key(key)))
+            .map(ToString::to_string)
+            .collect())
+    }
+
+    /// Merge the translations of another catalog into this one.
+    ///
+    /// The keys and the languages missing in this catalog are added. A conflict is reported for
+    /// every translation that exists in both catalogs with a different value, the strategy
+    /// decides which value is kept. With [`MergeStrategy::Error`], nothing is merged when there
+    /// is a conflict.
+    ///
+    /// The conflicts are sorted by key and language.
+    pub fn merge(&mut self, other: Catalog, strategy: MergeStrategy) -> Vec<MergeConflict> {
+        let mut conflicts = Vec::new();
+        let mut sorted: Vec<_> = other.map.iter().collect();
+        sorted.sort_unstable_by_key(|(key, _)| *key);
+        for (key, section) in sorted {
+            let mut langs: Vec<_> = section.translations.iter().collect();
+            langs.sort_unstable_by_key(|(lang, _)| lang);
+            for (lang, text) in langs {
+                match self.get(key, lang) {
+                    Some(current) if current != text => conflicts.push(MergeConflict {
+                        key: key.to_string(),
+                        lang: lang.to_string(),
+                        current: current.to_string(),
+                        incoming: text.to_string(),
+                    }),
+                    _ => {}
+                }
+            }
+        }
+
+        if strategy == MergeStrategy::Error && !conflicts.is_empty() {
+            return conflicts;
+        }
+
+        // the sources follow the values
+        let mut other_sources = other.sources;
+        let sources = &mut self.sources;
+        let mut take_source = |key: &str, lang: &str| {
+            let id = (key.to_string(), lang.to_string());
+            match other_sources.remove(&id) {
+                Some(source) => sources.insert(id, source),
+                None => sources.remove(&id),
+            };
+        };
+
+        // the new sections are placed after the existing ones
+        let offset = self
+            .map
+            .values()
+            .map(|section| section.position + 1)
+            .max()
+            .unwrap_or(0);
+        for (key, mut other_section) in other.map {
+            let section = match self.map.get_mut(&key) {
+                Some(section) => section,
+                None => {
+                    for (lang, _) in other_section.translations.iter() {
+                        take_source(&key, lang);
+                    }
+                    other_section.position += offset;
+                    self.map.insert(key, other_section);
+                    continue;
+                }
+            };
+            if section.maxlen.is_none() {
+                section.maxlen = other_section.maxlen;
+            }
+            if section.deprecated.is_none() {
+                section.deprecated = other_section.deprecated;
+            }
+            if section.context.is_none() {
+                section.context = other_section.context;
+            }
+            if section.order.is_none() {
+                section.order = other_section.order;
+            }
+            if section.tm_id.is_none() {
+                section.tm_id = other_section.tm_id;
+            }
+            section.markdown |= other_section.markdown;
+            for (lang, text) in other_section.translations {
+                match section.translations.iter_mut().find(|(x, _)| *x == lang) {
+                    Some((_, current)) => {
+                        if strategy == MergeStrategy::PreferOther && *current != text {
+                            take_source(&key, &lang);
+                            take_fuzzy(&mut section.fuzzy, &other_section.fuzzy, &lang);
+                            *current = text;
+                        }
+                    }
+                    None => {
+                        take_source(&key, &lang);
+                        take_fuzzy(&mut section.fuzzy, &other_section.fuzzy, &lang);
+                        if let Some(comments) = other_section.translation_comments.remove(&lang) {
+                            section.translation_comments.insert(lang.clone(), comments);
+                        }
+                        section.translations.push((lang, text));
+                    }
+                }
+            }
+        }
+
+        conflicts
+    }
+}
+
+impl FromStr for Catalog {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> io::Result<Self> {
+        Self::from_reader(&mut io::Cursor::new(s))
+    }
+}
+
+// the fuzzy markers follow the values: the translation of `lang` is fuzzy if it is in the catalog
+// its value comes from
+fn take_fuzzy(fuzzy: &mut Vec<String>, other_fuzzy: &[String], lang: &str) {
+    fuzzy.retain(|x| x != lang);
+    if other_fuzzy.iter().any(|x| x == lang) {
+        fuzzy.push(lang.to_string());
+    }
+}
+
+// the metadata of a section: their number then every metadata with its tag and its length
+fn read_tagged_metadata<R: Read>(reader: &mut R, section: &mut Section) -> io::Result<()> {
+    let count = read_u32(reader)?;
+    for _ in 0..count {
+        let tag = read_u32(reader)? as u32;
+        let payload = read_bytes(reader)?;
+        let text =
+            || String::from_utf8(payload.clone()).map_err(|err| invalid_data(err.to_string()));
+        match tag {
+            TAG_MAXLEN => section.maxlen = Some(read_u32(&mut payload.as_slice())?),
+            TAG_DEPRECATED => section.deprecated = Some(text()?),
+            TAG_CONTEXT => section.context = Some(text()?),
+            TAG_ORDER => {
+                let order = text()?;
+                section.order = Some(
+                    order
+                        .parse()
+                        .map_err(|_| invalid_data(format!("invalid order: {}", order)))?,
+                );
+            }
+            TAG_TM_ID => section.tm_id = Some(text()?),
+            TAG_MARKDOWN => section.markdown = true,
+            TAG_FUZZY => {
+                let payload = &mut payload.as_slice();
+                let count = read_u32(payload)?;
+                for _ in 0..count {
+                    section.fuzzy.push(read_str(payload)?);
+                }
+            }
+            // a metadata added by a later version
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn write_u32<W: Write>(writer: &mut W, value: usize) -> io::Result<()> {
+    let value =
+        u32::try_from(value).map_err(|_| invalid_data(format!("{} is too large", value)))?;
+    writer.write_all(&value.to_le_bytes())
+}
+
+fn write_str<W: Write>(writer: &mut W, value: &str) -> io::Result<()> {
+    write_u32(writer, value.len())?;
+    writer.write_all(value.as_bytes())
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<usize> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes) as usize)
+}
+
+fn read_str<R: Read>(reader: &mut R) -> io::Result<String> {
+    String::from_utf8(read_bytes(reader)?).map_err(|err| invalid_data(err.to_string()))
+}
+
+fn read_bytes<R: Read>(reader: &mut R) -> io::Result<Vec<u8>> {
+    let len = read_u32(reader)?;
+    let mut bytes = Vec::new();
+    // the length is not trusted: the bytes are read in one allocation only if the data is there
+    reader.by_ref().take(len as u64).read_to_end(&mut bytes)?;
+    if bytes.len() != len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(bytes)
+}
+
+fn find_used_keys(path: &Path, used: &mut HashSet<String>) -> io::Result<()> {
+    if path.is_dir() {
+        for entry in fs::read_dir(path)? {
+            find_used_keys(&entry?.path(), used)?;
+        }
+    } else if path.extension().map(|x| x == "rs").unwrap_or(false) {
+        let content = fs::read_to_string(path)?;
+        used.extend(
+            RE_MACRO_CALL
+                .captures_iter(&content)
+                .map(|caps| caps[1].to_string()),
+        );
+    }
+
+    Ok(())
+}
+
+/// Differences between two catalogs returned by [`Catalog::diff`].
+///
+/// All the lists are sorted alphabetically.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "catalog-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct CatalogDiff {
+    /// Keys that exist only in the new catalog.
+    pub added_keys: Vec<String>,
+    /// Keys that exist only in the old catalog.
+    pub removed_keys: Vec<String>,
+    /// Translations added, removed or modified in the keys of both catalogs.
+    pub changes: Vec<TranslationChange>,
+}
+
+/// Translation of a key that differs between two catalogs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "catalog-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct TranslationChange {
+    pub key: String,
+    pub lang: String,
+    /// Translation in the old catalog (`None` if it has been added).
+    pub old: Option<String>,
+    /// Translation in the new catalog (`None` if it has been removed).
+    pub new: Option<String>,
+}
+
+/// How [`Catalog::merge`] resolves the translations that exist in both catalogs with a different
+/// value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Keep the translation of the catalog merged into.
+    PreferSelf,
+    /// Keep the translation of the catalog being merged.
+    PreferOther,
+    /// Do not merge anything if there is a conflict.
+    Error,
+}
+
+/// Translation of a key that exists in both catalogs merged by [`Catalog::merge`] with a
+/// different value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "catalog-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct MergeConflict {
+    pub key: String,
+    pub lang: String,
+    /// Translation in the catalog merged into.
+    pub current: String,
+    /// Translation in the catalog being merged.
+    pub incoming: String,
+}
+
+/// Options of [`Catalog::validate`].
+#[derive(Debug, Clone, Default)]
+pub struct ValidateOpts {
+    /// The language whose placeholders are expected in the other languages (`en`). The first
+    /// translation of every key is used when it is not defined or when the key is not translated
+    /// in this language.
+    pub source_lang: Option<String>,
+}
+
+/// A problem found by [`Catalog::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "catalog-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub kind: DiagnosticKind,
+    pub key: String,
+    /// The language as written in the INI file, `None` if the problem concerns the whole key.
+    pub lang: Option<String>,
+    /// A description of the problem for humans.
+    pub message: String,
+}
+
+/// Severity of a [`Diagnostic`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "catalog-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum Severity {
+    /// The translations can be generated but are probably wrong.
+    Warning,
+    /// The generated code does not compile.
+    Error,
+}
+
+/// Kind of problem of a [`Diagnostic`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "catalog-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum DiagnosticKind {
+    /// The key is not translated in a language used by other keys.
+    MissingLanguage,
+    /// The translation does not use the placeholders of the reference translation.
+    PlaceholderMismatch,
+    /// The translation is empty or contains only whitespace.
+    EmptyValue,
+    /// The key has the same name as another key in the generated code.
+    KeyCollision,
+    /// The translation contains a `%` followed by a letter, a digit or a dot that is not a valid
+    /// placeholder (`%z`, `%.f`) or `%@` followed by a letter (`%@s`).
+    InvalidPlaceholder,
+}
+
+/// Kind of a printf's format placeholder returned by [`Catalog::placeholders`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "catalog-serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum PlaceholderKind {
+    /// `%d` or `%i`.
+    Int,
+    /// `%f`.
+    Float,
+    /// `%s` or `%@`.
+    Str,
+    /// `%x`.
+    Hex,
+    /// `%X`.
+    UpperHex,
+}
@@ -10,7 +10,8 @@
 //!
 //! # Usage
 //!
-//! 1.  You need to add twine to your `[build-dependencies]` in `Cargo.toml`.
+//! 1.  You need to add twine to your `[build-dependencies]` in `Cargo.toml` (Rust 1.65 or
+//!     newer).
 //!
 //! 2.  Create (or edit) your `build.rs` file:
 //!
@@ -83,6 +84,11 @@
 //! #![allow(macro_expanded_macro_exports_accessed_by_absolute_paths)]
 //! ```
 //!
+//! Alternatively, the option `local_macros` of the `Builder` generates the macros without
+//! `#[macro_export]` for a file included in a module: `.local_macros("ui::i18n")` declares them
+//! with `macro_rules!` followed by `pub(crate) use t;`, they are used by path anywhere in the crate
+//! (`crate::ui::i18n::t!(band_tool => lang)`) and the lint does not need to be disabled.
+//!
 //! # Implementation Notes
 //!
 //! All translation keys must have all the languages of all the keys. For example, if all your keys
@@ -90,14 +96,341 @@
 //!
 //! Localized translation can be provided and will be used if available. Otherwise it will
 //! fallback to the default translation for that language.
+//! Several regions can share a translation: `en-gb,au,nz = colour`.
+//! The option `uppercase_regions` of the `Builder` displays the regions in uppercase (`en_GB`).
+//! The option `ignore_regions` merges the regions into their language (`en-gb` into `en`, the
+//! last translation wins and the differences are reported) and generates a `Lang` without
+//! region: `Lang::En` instead of `Lang::En("")`. The region of a parsed code is dropped.
+//! When a key is not translated in a language at all, its first translation is used instead. The
+//! option `fallback_order` of the `Builder` changes this: `.fallback_order(&["de", "fr", "en"])`.
+//! The option `default_lang` adds a last language to try and reports the keys that are not
+//! translated in it.
+//!
+//! The section `[__defaults__]` gives the value of the keys missing in a language, before their
+//! first translation is used: `band_tool = Unknown tool` for a key or `band_* = Unknown band` for
+//! all the keys matching a pattern (the key itself comes first, then the patterns in their order).
+//! It does not replace the languages of `fallback_order` and `default_lang` and the plural keys
+//! are not concerned.
+//!
+//! The keys are converted to snake case to be used in the macros (`NameWith.DotInIt` becomes
+//! `name_with_dot_in_it`), the Unicode letters are kept (`café_button`). A key that does not start
+//! with a letter once converted (`1st_place`) is an error.
+//! Only the letters, the digits and `_` are kept: `[band=>tool]` is `t!(band_tool => lang)`. Two
+//! keys converted to the same identifier (`band=>tool` and `band, tool`) are an error, as well as
+//! the keys `crate`, `self` and `super`. The other keywords are written as is in the macros
+//! (`t!(type => lang)`), their typed functions use raw identifiers (`r#type(&lang)`).
+//! A `]` in a key is escaped with a backslash: the section `[fits\]_in]` is the key `fits]_in`.
+//!
+//! Any typo in the key will make the compilation fail. A wrong number of format arguments will also
+//! make the compilation fail: `t!(format_string) expects 2 arguments, got 1`.
+//! To roll out a new key gradually, a literal can be given after the language: it is used as
+//! long as the key does not exist (a typo in the key is not detected then):
+//! `t!(new_key => lang; "New feature")`.
+//! A language that might be missing is given with `?`: `t!(band_tool => ?lang)` takes an
+//! `Option<Lang>` and uses `Lang::default()` when it is `None` (the default language of the thread
+//! with the feature `current-lang`, the first language of `Lang::all_languages()` otherwise). It
+//! works with all the macros taking a language except `t_static!`.
+//!
+//! A `%` that does not start a placeholder is a literal `%`, like `%%`: `en = %.0f%` and
+//! `en = %.0f%%` both give `73%`, `fr = %.0f %` gives `73 %`.
+//! The placeholder `%{lang}` is replaced by the code of the language requested (as displayed, e.g.
+//! `en_gb`) without using an argument: `en = <html lang="%{lang}">`.
+//! The option `strict_placeholders` of the `Builder` makes the typos like `%z`, `%.f` or `%@s` an
+//! error instead.
+//!
+//! The option `typed_functions` of the `Builder` generates a function for every key with the types
+//! of its arguments inferred from the placeholders: `format_percentage(&lang, 73.02)`.
+//! The option `doctests` documents every variant of `Key` with an example calling `t!` so that
+//! `cargo test --doc` checks the translations of a library.
+//! The option `ffi` generates `extern "C"` functions giving the raw translations to the programs
+//! written in another language by the indices of the key and of the language:
+//! `i18n_translate(i18n_key_index("band_tool", 9), i18n_lang_index("fr", 2), &len)` (see the
+//! documentation of `Builder::ffi` for the ABI).
+//! The option `const_table` generates the raw translations in a constant array indexed by key and
+//! language, without hashing nor matching: `TABLE[key as usize][lang_index(&lang)]` (the missing
+//! translations are filled with the fallback of `t!`).
+//!
+//! The option `html_escape` escapes `<`, `>` and `&` in the translations for the web (`a &lt; b`):
+//! the arguments of the placeholders are not escaped.
+//!
+//! A key can define the maximum length (in characters) of its translations with the metadata
+//! `maxlen = 20`. A warning is emitted for every translation that is longer, this becomes an error
+//! when the strict mode of the `Builder` is enabled. The same goes for the translations containing
+//! control characters other than the tabulation (e.g. `NUL` or a lone carriage return).
+//!
+//! The languages can be declared in a section `[__languages__]` listing their codes (`en, fr`)
+//! to catch the typos: the translations in another language are reported like the translations
+//! that are too long. A language declared without region allows all its regions.
+//!
+//! A key replaced by another one can be marked with the metadata `deprecated = band_tool`: a
+//! warning lists the deprecated keys at build time and their typed functions are `#[deprecated]`.
+//!
+//! The metadata `context = https://...` gives the URL of a screenshot or a page showing where the
+//! key is used: it is added to the documentation of the key in the generated code and returned by
+//! `Catalog::context()`.
+//!
+//! The translations that need to be reviewed again, usually because the source changed, can be
+//! listed in the metadata `fuzzy = fr, en-gb`: a warning lists them at build time and the option
+//! `fail_on_fuzzy` of the `Builder` makes them errors in strict mode. `Catalog::is_fuzzy()` tells
+//! if a translation is marked.
+//!
+//! The keys are generated in alphabetical order. The metadata `order = 10` moves a key: the keys
+//! are sorted by their order (`0` when missing, lower first) then alphabetically. This only changes
+//! the order of the generated code (macro arms, variants of `Key`, typed functions), `Key::ALL`
+//! stays sorted by variant.
+//!
+//! The metadata `tm_id = CROWDIN-12345` maps a key to the ID of its string in an external
+//! translation memory (Crowdin, Lokalise, ...): it is kept when the catalog is written back and
+//! returned by `Catalog::external_id()`, it is not used by the generated code. It is not named
+//! `id` as `id = ...` is a translation in Indonesian.
+//!
+//! The metadata `format = markdown` marks the translations of a key as markdown (`plain` by
+//! default): the generated function `is_markdown(key: Key) -> bool` and `Catalog::is_markdown()`
+//! tell the UI to render them as markdown instead of plain text. The markdown is not rendered.
+//!
+//! The option `summary` of the `Builder` prints the size of the catalog after the generation to
+//! follow its growth: `warning: twine: 342 keys, 5 languages, 1710 strings generated`.
+//!
+//! The option `source_lang` of the `Builder` designates the reference language (usually `en`): the
+//! keys missing in a language and the translations that do not use the placeholders of the source
+//! language are reported the same way.
+//! The section `[__schema__]` pins the placeholders of a key in all its languages:
+//! `order_confirmed = %s, %d` reports the translations that do not use exactly these placeholders
+//! (`%1$s` and `%s` are the same), `order_cancelled = none` the ones that use any. The plural keys
+//! are not checked and a key of the schema that does not exist is also reported.
+//! A translation `@source` is replaced by the translation of the source language (or the default
+//! language) to leave a key untranslated on purpose: `fr = @source`.
+//! The option `check_identical` warns about the keys that have the same value in several
+//! languages (`fr = The Doors`), often a copy of the source language that was never translated.
+//! The option `check_whitespace` warns about the keys whose translations do not have the same
+//! whitespace at the start and at the end (`en = Save\u0020` but `fr = Enregistrer`). The spaces
+//! around a value are trimmed: only the escape sequences (`\t`, `\u0020`, ...) keep whitespace.
+//!
+//! A translation can include the translation of another key in the same language with `$t(key)`:
+//! `en = Welcome to $t(app_name)!`. The references are resolved when the code is generated, the
+//! placeholders of the key referenced become placeholders of the translation (their positions are
+//! not renumbered). A reference to a key that is not translated in the language or a cycle of
+//! references make the generation fail.
+//!
+//! The generated file contains a constant `TWINE_GEN_VERSION` with the version of twine that
+//! generated it. You can assert it in your tests to detect a stale generated file:
+//!
+//! ```ignore
+//! #[test]
+//! fn i18n_is_up_to_date() {
+//!     assert_eq!(TWINE_GEN_VERSION, "0.7.1");
+//! }
+//! ```
+//!
+//! The macro `t_into!` works like `t!` but writes the translation into a buffer implementing
+//! `std::fmt::Write` instead of allocating a new `String`. It returns a `std::fmt::Result`:
+//! `t_into!(buf, format_percentage, 73.02f32 => lang)?`.
+//! The macro `t_bytes!` returns the translation as UTF-8 bytes (`Vec<u8>`) for a response body:
+//! `t_bytes!(band_tool => lang)`. Given `&mut` a `Vec<u8>` first, it appends the translation to it
+//! without allocating a `String` and returns a `std::fmt::Result` like `t_into!`:
+//! `t_bytes!(&mut body, format_percentage, 73.02f32 => lang)?`.
+//!
+//! The macro `t_raw!` returns the translation (`&'static str`) without formatting it: the escape
+//! sequences are decoded like `t!` (`\n`, `\u00e9`) but the placeholders are kept:
+//! `t_raw!(format_string => lang)` returns `%s, %@!`.
+//!
+//! The macro `t_static!` returns the translation of a key without placeholders as a `&'static str`
+//! literal, it can be used in const contexts:
+//! `const TOOL: &str = t_static!(band_tool => Lang::En(""));`. The keys with placeholders are
+//! rejected at compile time.
+//!
+//! A language variant that does not exist (`Lang::Xx("")`) does not compile but the regions are
+//! not checked: `Lang::En("us")` falls back to `Lang::En("")`. The macro `const_lang!` checks a
+//! constant language at compile time with the `const fn` `Lang::is_known()`:
+//! `t!(band_tool => const_lang!(Lang::En("gb")))`. A dynamic `Lang` cannot be checked before
+//! runtime, `is_known()` can be called on it.
+//!
+//! `Lang::sort_key(&priority)` sorts the languages of a picker by a list of preferred codes then
+//! alphabetically: `langs.sort_by_key(|x| x.sort_key(&["en", "fr"]))`.
+//! `Lang::nearest("en_us")` gives the closest language translated for a tag: `Lang::En("")` when
+//! `en` exists but not `en_us`, `None` when the language is not translated at all.
+//! `Lang::try_from("en_us")` parses a code like `Lang::parse()` but tells what is unknown:
+//! `LangParseError::UnknownLanguage("de")` for `de_ch` or `LangParseError::UnknownRegion("us")`.
+//! `Lang::complete_languages()` lists the languages translated in every key, computed at build
+//! time: the languages to expose in production. A region counts as translated when its language
+//! is (`en_gb` by `en`).
+//! `Catalog::group_by_language()` groups the codes of a catalog by language for a picker choosing
+//! the language then the region: `{"en": ["en", "en-gb"], "fr": ["fr"]}`.
+//!
+//! The generated file also contains an enum `Key` with a variant for every translation key and a
+//! function `preview(key: Key) -> &'static [(Lang, &'static str)]` returning the raw translations
+//! of a key (the escape sequences are decoded, printf's placeholders are not converted).
+//! All the keys are listed in `Key::ALL` and `Key::iter()` iterates over them:
+//! `Key::iter().map(preview)`.
+//! The keys can also be looked up at runtime by their name in the INI file with `Key::from_name()`
+//! and `translate(name, lang) -> Option<&'static str>` returns the raw translation of a key.
+//! The translations loaded at runtime (e.g. an override file) can be checked with
+//! `validate_overrides(&map)` which returns the names that are not keys, sorted alphabetically.
+//! `describe(key, &lang) -> String` returns the raw translation of a `Key` like `translate()` but
+//! falls back to the translation used by `t!()` for the other languages. It gives a localized
+//! message to every variant of an error with one key by variant (`[error.not_found]`):
+//!
+//! ```ignore
+//! impl fmt::Display for Localized<MyError> {
+//!     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+//!         let key = match self.error {
+//!             MyError::NotFound => Key::ErrorNotFound,
+//!             MyError::PermissionDenied => Key::ErrorPermissionDenied,
+//!         };
+//!         f.write_str(&describe(key, &self.lang))
+//!     }
+//! }
+//! ```
+//!
+//! The option `pseudolocale` of the `Builder` generates an extra language `Lang::Pseudo("")` with
+//! the translations of the first language of every key accented and padded (`[Ĥéļļö Ŵöŕļð!!!!]`)
+//! to check that the user interface copes with longer texts. The placeholders are kept untouched.
+//! `Catalog::expansion_report("en")` lists the ratio between the length of every translation and
+//! its source, the longest first, to find the real translations that might overflow.
+//!
+//! The translations can also be split in one module per INI file with
+//! `build_translations_split()`, the `Lang` enum being generated in a separate file shared by the
+//! modules which must be included at the root of the crate.
+//!
+//! `build_translations_to_writer()` writes the code to any `Write` (e.g. a `Vec<u8>`) instead of a
+//! file of `OUT_DIR`, for the generators that keep it in memory or write it elsewhere.
+//!
+//! `build_translations_from_toml()` (feature `toml`) reads the translations from TOML files
+//! instead, every table being a key: `[band_tool]` then `en = "Tool"`. The escape sequences and
+//! the multi-line strings of TOML are decoded and the plural categories are dotted keys
+//! (`fr.one = "%d fichier"`) or inline tables (`en = { one = "%d file", other = "%d files" }`).
+//!
+//! The code is written to the output file while it is generated: the memory used depends on the
+//! size of the translations, not on the size of the generated code.
+//!
+//! The option `no_std` of the `Builder` generates code for a `#![no_std]` crate using `alloc`
+//! (the crate must declare `extern crate alloc;`). `Lang::from_locale_env()` and the items of the
+//! features `current-lang` and `gettext` are not generated then.
+//!
+//! # Plurals
+//!
+//! A key becomes plural when its translations are suffixed by a plural category (`zero`, `one`,
+//! `two`, `few`, `many` or `other`). A translation without category is used as `other`:
+//!
+//! ```text
+//! [n_files]
+//!     en.one = %d file
+//!     en.other = %d files
+//!     fr.one = %d fichier
+//!     fr.other = %d fichiers
+//! ```
+//!
+//! Plural keys are translated with the macro `plural!` instead of `t!`. The count selects the
+//! category following the [CLDR rules](https://cldr.unicode.org/index/cldr-spec/plural-rules)
+//! of the language and is also the first argument of the format string. `#` can be used as a
+//! shorthand for it:
+//!
+//! ```ignore
+//! // will output "2 fichiers"
+//! plural!(n_files, 2 => Lang::Fr(""));
+//! ```
+//!
+//! The translations of the category `zero` override the rules of the language when the count is 0,
+//! even if the language does not have this category: `en.zero = No files`.
+//!
+//! The macro `ordinal!` works the same way but uses the ordinal rules of the language instead
+//! (1st, 2nd, 3rd, 4th, ...):
+//!
+//! ```text
+//! [place]
+//!     en.one = %dst
+//!     en.two = %dnd
+//!     en.few = %drd
+//!     en.other = %dth
+//! ```
+//!
+//! # Named Arguments
+//!
+//! Keys can also use named arguments with Rust's format syntax when the option `named_args` of the
+//! `Builder` is enabled:
+//!
+//! ```text
+//! [greeting]
+//!     en = Hello {name}!
+//!     fr = Bonjour {name} !
+//! ```
+//!
+//! ```ignore
+//! // will output "Bonjour Sam !"
+//! t!(greeting, name = "Sam" => Lang::Fr(""));
+//! ```
+//!
+//! The plural keys take the count as `count = ` followed by the named arguments, `#` being the
+//! count:
+//!
+//! ```text
+//! [cart_items]
+//!     en.one = {name} has # item
+//!     en.other = {name} has # items
+//! ```
+//!
+//! ```ignore
+//! // will output "Sam has 3 items"
+//! plural!(cart_items, count = 3, name = "Sam" => Lang::En(""));
+//! ```
 //!
-//! Any typo in the key will make the compilation fail. Missing format arguments will also make
-//! the compilation fail.
+//! The named arguments can also be given at runtime in a map (e.g. a template filled by the user)
+//! with `format_dynamic(key, &lang, &args) -> Result<String, MissingArg>`. It replaces the named
+//! arguments of the translation given by `describe()` and returns the names missing from the map in
+//! the error `MissingArg` (only available with `std`):
+//!
+//! ```ignore
+//! let mut args = HashMap::new();
+//! args.insert("name", "Sam");
+//! // will output "Bonjour Sam !"
+//! format_dynamic(Key::Greeting, &Lang::Fr(""), &args)?;
+//! // MissingArg(["name"])
+//! format_dynamic(Key::Greeting, &Lang::Fr(""), &HashMap::new()).unwrap_err();
+//! ```
 //!
 //! # Features
 //!
 //!  *  `serde`: when this feature is activated you will need to add `serde` to your dependencies
-//!     and the `Lang` enum generated implements `Serialize` and `Deserialize`.
+//!     and the `Lang` enum generated implements `Serialize` and `Deserialize`. The option
+//!     `serde(false)` of the `Builder` does not implement them for `Lang` when the feature is enabled
+//!     by another crate.
+//!
+//!  *  `catalog-serde`: the `CatalogDiff` returned by `Catalog::diff()`, the `MergeConflict`
+//!     returned by `Catalog::merge()`, the `PlaceholderKind` returned by `Catalog::placeholders()`
+//!     and the `Diagnostic` returned by `Catalog::validate()` implement `Serialize` and
+//!     `Deserialize`. `Catalog::to_json_by_lang()` exports the translations by language for the
+//!     frontend libraries like i18next. This feature makes twine depend on `serde` and `serde_json`.
+//!
+//!  *  `current-lang`: generates `set_lang(lang)` and `get_lang()` to store a current language per
+//!     thread and the macro `tl!` which translates in this language: `tl!(format_string, "Hello",
+//!     "World")`. The language is stored in a thread-local: it is not seen by the other threads
+//!     (including the worker threads of an async runtime) and it stays set after a test, so every
+//!     test should set the language it expects. `tl!` reads the language once, before evaluating
+//!     the arguments: a call to `set_lang()` inside an argument only affects the following calls.
+//!     The default language is stored separately, also per thread: `Lang::set_default(lang)` sets
+//!     the language used by the macro `t_default!` and returned by `Lang::default()` (the first
+//!     language of `Lang::all_languages()` until it is set), without changing the current language.
+//!
+//!  *  `gettext`: generates gettext-like shims for code ported from gettext: the thread-local
+//!     current language is set with `set_current_lang(lang)`, `gettext(key)` returns the raw
+//!     translation of a key in the current language and `ngettext(singular_key, plural_key, n)`
+//!     selects one of both keys using the plural rule of the current language. The key itself is
+//!     returned when the key is unknown or when no current language has been set.
+//!
+//!  *  `encoding_rs`: adds the option `encoding` to the `Builder` to read INI files in another
+//!     encoding than UTF-8 (e.g. `twine::encoding_rs::WINDOWS_1252`).
+//!
+//!  *  `locale-numbers`: adds the option `locale_numbers` to the `Builder` to display the numbers
+//!     of the placeholders `%d` and `%f` with the separators of the language of the translation:
+//!     `1,234.50` in English, `1.234,50` in German. The option `locale_count` does the same for
+//!     the count `#` of the plural translations: `1,000,000 files`.
+//!
+//!  *  `phf`: when this feature is activated you will need to add `phf = "0.11"` to your
+//!     dependencies and the generated file contains `KEYS`, a `phf::Map` of the keys by their name
+//!     used by `translate()` to find the keys in constant time.
+//!
+//!  *  `toml`: adds `build_translations_from_toml()` to read the translations from TOML files
+//!     instead of Twine INI files. This feature makes twine depend on `toml`.
 //!
 //! # License
 //!
@@ -107,8 +440,9 @@
 use heck::{ToSnakeCase, ToUpperCamelCase};
 use indenter::CodeFormatter;
 use once_cell::sync::Lazy;
+use printf::Fragment;
 use regex::Regex;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt;
 use std::fmt::Write as FmtWrite;
 use std::fs;
@@ -116,34 +450,360 @@ use std::io;
 use std::io::{Read, Write};
 use std::path::Path;
 
-// regex that tries to parse printf's format placeholders
-// see: https://docs.microsoft.com/en-us/cpp/c-runtime-library/format-specification-syntax-printf-and-wprintf-functions?view=msvc-160
-static RE_PRINTF: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r#"%((?P<parameter>\d+)\$)?(?P<flags>[-+#])?(?P<width>\d+)?(?P<precision>\.\d+)?(?P<type>[dis@xXf])|\\u(?P<unicode>[0-9a-fA-F]{4})|\\.|%%|%$|"|[^%"\\]+"#)
-        .unwrap()
-});
-static RE_LANG: Lazy<Regex> = Lazy::new(|| Regex::new(r"(\w+)(-(\w+))?").unwrap());
-static RE_SECTION: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\s*\[([^\]]+)\]").unwrap());
+mod case;
+mod catalog;
+#[cfg(feature = "locale-numbers")]
+mod numbers;
+mod plural;
+mod printf;
+mod pseudo;
+#[cfg(feature = "toml")]
+mod toml;
+
+#[cfg(feature = "encoding_rs")]
+pub use encoding_rs;
+
+pub use catalog::{
+    Catalog, CatalogDiff, Diagnostic, DiagnosticKind, MergeConflict, MergeStrategy,
+    PlaceholderKind, Severity, TranslationChange, ValidateOpts,
+};
+
+static RE_NAMED_ARGUMENT: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{\w+\}").unwrap());
+static RE_LANG: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\w+)(-(\w+))?(\.(\w+))?$").unwrap());
+// a `]` is part of the section name when it is escaped: `[foo\]bar]` is the key `foo]bar`
+static RE_SECTION: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\s*\[((?:[^\]\\]|\\.)+)\]").unwrap());
+static RE_REFERENCE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\$t\(\s*([^)\s]+)\s*\)").unwrap());
 static RE_KEY_VALUE: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^\s*([^\s=;#]+)\s*=\s*(.+?)\s*$").unwrap());
 
-type TwineData = HashMap<String, Vec<(String, String)>>;
+// translation replaced by the translation of the source language
+const SOURCE_MARKER: &str = "@source";
+// section declaring the languages allowed in the file
+const LANGUAGES_SECTION: &str = "__languages__";
+// section giving the values of the keys (or of the patterns of keys) missing in a language
+const DEFAULTS_SECTION: &str = "__defaults__";
+// section giving the placeholders required by the keys: `order_confirmed = %s, %d`
+const SCHEMA_SECTION: &str = "__schema__";
+// placeholder replaced by the code of the language requested: `<html lang="%{lang}">`
+const LANG_PLACEHOLDER: &str = "%{lang}";
+// the highest explicit position of a placeholder (`%255$s`), the translations are read as
+// arguments of `format!()`
+const MAX_POSITION: usize = 255;
+// the keywords of Rust: the typed functions of these keys use a raw identifier (`r#type`)
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let",
+    "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+    "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+    "virtual", "where", "while", "yield",
+];
+// the keywords that cannot be raw identifiers (`self` is also the variant `Key::Self`)
+const RESERVED_KEYS: &[&str] = &["crate", "self", "super"];
+
+type TwineData = HashMap<String, Section>;
+type ValueTransform<'a> = Box<dyn Fn(&str, &str, &str) -> String + 'a>;
+
+#[derive(Debug, Default)]
+struct Section {
+    translations: Vec<(String, String)>,
+    // maximum length (in characters) of the translations
+    maxlen: Option<usize>,
+    // the key replacing this one
+    deprecated: Option<String>,
+    // URL of a screenshot or of a page showing where the key is used
+    context: Option<String>,
+    // emission order of the key in the generated code (lower first, `0` by default)
+    order: Option<i64>,
+    // ID of the key in an external translation memory (e.g. `CROWDIN-12345`)
+    tm_id: Option<String>,
+    // the translations are markdown to render instead of plain text
+    markdown: bool,
+    // the languages whose translation needs to be reviewed again
+    fuzzy: Vec<String>,
+    // position of the section in its file
+    position: usize,
+    // comments preceding the section
+    comments: Vec<String>,
+    // comments preceding the translations (by language)
+    translation_comments: HashMap<String, Vec<String>>,
+    // comments at the end of the file if it is the last section
+    trailing_comments: Vec<String>,
+}
+
+impl Section {
+    // merge the translations and the metadata of another section (other wins)
+    fn merge(&mut self, other: Section) {
+        self.translations.extend(other.translations);
+        self.maxlen = other.maxlen.or(self.maxlen);
+        self.deprecated = other.deprecated.or(self.deprecated.take());
+        self.context = other.context.or(self.context.take());
+        self.order = other.order.or(self.order);
+        self.tm_id = other.tm_id.or(self.tm_id.take());
+        self.markdown |= other.markdown;
+        for lang in other.fuzzy {
+            if !self.fuzzy.contains(&lang) {
+                self.fuzzy.push(lang);
+            }
+        }
+        self.comments.extend(other.comments);
+        for (lang, comments) in other.translation_comments {
+            self.translation_comments
+                .entry(lang)
+                .or_default()
+                .extend(comments);
+        }
+        self.trailing_comments.extend(other.trailing_comments);
+    }
+}
+
+/// Error returned when the translations could not be generated.
+#[derive(Debug)]
+pub enum TwineError {
+    /// An I/O error occurred while reading the translations or writing the generated file.
+    Io(io::Error),
+    /// A translation is longer than the `maxlen` of its key (strict mode only).
+    MaxLengthExceeded {
+        key: String,
+        lang: String,
+        max: usize,
+        actual: usize,
+    },
+    /// A translation contains a control character other than the tabulation (strict mode only).
+    InvalidCharacter {
+        key: String,
+        lang: String,
+        codepoint: u32,
+    },
+    /// A key is not translated in a language while the source language is defined (strict mode
+    /// only).
+    MissingTranslation { key: String, lang: String },
+    /// A translation does not use the same placeholders as the source language or as the
+    /// section `[__schema__]` (strict mode only).
+    PlaceholderMismatch {
+        key: String,
+        lang: String,
+        expected: Vec<String>,
+        actual: Vec<String>,
+    },
+    /// A key is not translated in the default language (strict mode only).
+    MissingDefault { key: String },
+    /// A translation references with `$t(key)` a key that does not exist or is not translated in
+    /// its language.
+    UnresolvedReference {
+        key: String,
+        lang: String,
+        reference: String,
+    },
+    /// The references `$t(key)` of a language form a cycle: `["a", "b", "a"]`.
+    ReferenceCycle { lang: String, keys: Vec<String> },
+    /// A key is translated in a language that is not declared in the section `[__languages__]`
+    /// (strict mode only).
+    UnknownLanguage { key: String, lang: String },
+    /// A translation contains a `%`-sequence that is not a valid placeholder: `%z`, `%.f`, `%@s`
+    /// (see [`Builder::strict_placeholders`]).
+    InvalidPlaceholder {
+        key: String,
+        lang: String,
+        placeholder: String,
+    },
+    /// A key cannot be turned into a Rust identifier: it does not start with a letter once
+    /// converted to snake case (`1st_place`, `!!!`) or it is `crate`, `self` or `super`.
+    InvalidKey { key: String, normalized: String },
+    /// Two keys are turned into the same Rust identifier (`band=>tool` and `band, tool` both
+    /// become `band_tool`).
+    KeyCollision {
+        key: String,
+        other: String,
+        normalized: String,
+    },
+    /// A key of the section `[__schema__]` does not exist (strict mode only).
+    UnknownSchemaKey { key: String },
+    /// A translation is marked with the metadata `fuzzy` (see [`Builder::fail_on_fuzzy`]).
+    FuzzyTranslation { key: String, lang: String },
+    /// The regions of a language have different translations of a key while the regions are
+    /// ignored (strict mode only, see [`Builder::ignore_regions`]).
+    RegionConflict { key: String, lang: String },
+    /// A Twine INI file cannot be read: a metadata value, a language code or a plural category is
+    /// invalid, or a key-value is outside a section. `file` is the path or the name of the file
+    /// when it is known, the line is in the error.
+    Parse {
+        file: Option<String>,
+        error: io::Error,
+    },
+    /// A TOML file cannot be read: its syntax, a metadata value, a language code or a plural
+    /// category is invalid, or a key-value is outside a table (see
+    /// `Builder::build_translations_from_toml`).
+    ParseToml { file: String, error: io::Error },
+}
+
+impl fmt::Display for TwineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TwineError::Io(err) => write!(f, "{}", err),
+            TwineError::MaxLengthExceeded {
+                key,
+                lang,
+                max,
+                actual,
+            } => write!(
+                f,
+                "translation {:?} of key {:?} is too long: {} characters (max {})",
+                lang, key, actual, max,
+            ),
+            TwineError::InvalidCharacter {
+                key,
+                lang,
+                codepoint,
+            } => write!(
+                f,
+                "translation {:?} of key {:?} contains the invalid character U+{:04X}",
+                lang, key, codepoint,
+            ),
+            TwineError::MissingTranslation { key, lang } => {
+                write!(f, "key {:?} is not translated in {:?}", key, lang)
+            }
+            TwineError::PlaceholderMismatch {
+                key,
+                lang,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "translation {:?} of key {:?} uses the placeholders {:?} instead of {:?}",
+                lang, key, actual, expected,
+            ),
+            TwineError::MissingDefault { key } => {
+                write!(f, "key {:?} is not translated in the default language", key)
+            }
+            TwineError::UnresolvedReference {
+                key,
+                lang,
+                reference,
+            } => write!(
+                f,
+                "translation {:?} of key {:?} references the key {:?} which is not translated in \
+                this language",
+                lang, key, reference,
+            ),
+            TwineError::ReferenceCycle { lang, keys } => write!(
+                f,
+                "the references of the translations {:?} form a cycle: {}",
+                lang,
+                keys.join(" -> "),
+            ),
+            TwineError::UnknownLanguage { key, lang } => write!(
+                f,
+                "translation {:?} of key {:?} uses a language that is not declared",
+                lang, key,
+            ),
+            TwineError::InvalidPlaceholder {
+                key,
+                lang,
+                placeholder,
+            } => write!(
+                f,
+                "translation {:?} of key {:?} contains the invalid placeholder {:?}",
+                lang, key, placeholder,
+            ),
+            TwineError::InvalidKey { key, normalized } => write!(
+                f,
+                "key {:?} cannot be turned into a Rust identifier (got {:?})",
+                key, normalized,
+            ),
+            TwineError::KeyCollision {
+                key,
+                other,
+                normalized,
+            } => write!(
+                f,
+                "keys {:?} and {:?} are both turned into the Rust identifier {:?}",
+                key, other, normalized,
+            ),
+            TwineError::UnknownSchemaKey { key } => write!(
+                f,
+                "key {:?} of the section [{}] does not exist",
+                key, SCHEMA_SECTION,
+            ),
+            TwineError::FuzzyTranslation { key, lang } => write!(
+                f,
+                "translation {:?} of key {:?} is fuzzy and needs to be reviewed",
+                lang, key,
+            ),
+            TwineError::RegionConflict { key, lang } => write!(
+                f,
+                "the regions of {:?} have different translations of key {:?}, the last one is used",
+                lang, key,
+            ),
+            TwineError::Parse {
+                file: Some(file),
+                error,
+            } => write!(f, "could not read Twine INI file {}: {}", file, error),
+            TwineError::Parse { file: None, error } => {
+                write!(f, "could not read Twine INI file: {}", error)
+            }
+            TwineError::ParseToml { file, error } => {
+                write!(f, "could not read TOML file {}: {}", file, error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TwineError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TwineError::Io(err) => Some(err),
+            TwineError::Parse { error, .. } | TwineError::ParseToml { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for TwineError {
+    fn from(err: io::Error) -> Self {
+        TwineError::Io(err)
+    }
+}
+
+impl From<TwineError> for io::Error {
+    fn from(err: TwineError) -> Self {
+        match err {
+            TwineError::Io(err) => err,
+            err => io::Error::new(io::ErrorKind::InvalidData, err),
+        }
+    }
+}
 
 /// Generate the `t!()` macro based on the provided list of paths to Twine INI translation files.
 pub fn build_translations<P: AsRef<Path>, O: AsRef<Path>>(
     ini_files: &[P],
     output_file: O,
 ) -> io::Result<()> {
-    let mut readers = ini_files
-        .iter()
-        .map(|file_path| {
-            let file_path = file_path.as_ref();
-            println!("cargo:rerun-if-changed={}", file_path.display());
-            fs::File::open(file_path)
-        })
-        .collect::<io::Result<Vec<_>>>()?;
+    Ok(Builder::new().build_translations(ini_files, output_file)?)
+}
+
+/// Generate the `t!()` macro based on the provided list of paths to Twine INI translation files.
+///
+/// The instructions for cargo (`cargo:rerun-if-changed=...` for every file read) are written to
+/// `cargo_output` instead of the standard output.
+pub fn build_translations_with_cargo_output<P: AsRef<Path>, O: AsRef<Path>, W: Write>(
+    ini_files: &[P],
+    output_file: O,
+    cargo_output: &mut W,
+) -> io::Result<()> {
+    Ok(Builder::new()
+        .cargo_output(cargo_output)
+        .build_translations(ini_files, output_file)?)
+}
 
-    build_translations_from_readers(readers.as_mut_slice(), output_file)
+/// Generate the `t!()` macro based on the provided list of paths to TOML translation files.
+///
+/// See [`Builder::build_translations_from_toml`].
+#[cfg(feature = "toml")]
+pub fn build_translations_from_toml<P: AsRef<Path>, O: AsRef<Path>>(
+    toml_files: &[P],
+    output_file: O,
+) -> io::Result<()> {
+    Ok(Builder::new().build_translations_from_toml(toml_files, output_file)?)
 }
 
 /// Generate the `t!()` macro based on the provided list of `&str` containing Twine INI
@@ -152,9 +812,18 @@ pub fn build_translations_from_str<P: AsRef<Path>>(
     strs: &[&str],
     output_file: P,
 ) -> io::Result<()> {
-    let mut readers = strs.iter().map(io::Cursor::new).collect::<Vec<_>>();
+    Ok(Builder::new().build_translations_from_str(strs, output_file)?)
+}
 
-    build_translations_from_readers(readers.as_mut_slice(), output_file)
+/// Generate the `t!()` macro based on the provided list of `(name, content)` containing Twine INI
+/// translations.
+///
+/// See [`Builder::build_translations_from_named_str`].
+pub fn build_translations_from_named_str<P: AsRef<Path>>(
+    strs: &[(&str, &str)],
+    output_file: P,
+) -> io::Result<()> {
+    Ok(Builder::new().build_translations_from_named_str(strs, output_file)?)
 }
 
 /// Generate the `t!()` macro based on the provided list of readers containing Twine INI
@@ -163,165 +832,5049 @@ pub fn build_translations_from_readers<R: Read, P: AsRef<Path>>(
     readers: &mut [R],
     output_file: P,
 ) -> io::Result<()> {
-    let mut map = HashMap::new();
-
-    // read all the INI files (might override existing keys)
-    for reader in readers {
-        match read_twine_ini(reader) {
-            Err(err) => panic!("could not read Twine INI file: {}", err),
-            Ok(other_map) => map.extend(other_map),
-        }
-    }
+    Ok(Builder::new().build_translations_from_readers(readers, output_file)?)
+}
 
-    let out_dir = std::env::var_os("OUT_DIR").unwrap();
-    let dest_path = Path::new(&out_dir).join(output_file);
-    let _ = fs::create_dir_all(dest_path.parent().unwrap());
-    let mut f = io::BufWriter::new(
-        fs::OpenOptions::new()
-            .create(true)
-            .write(true)
-            .truncate(true)
-            .open(dest_path)?,
-    );
-    write!(f, "{}", TwineFormatter { map })?;
+/// Generate the `t!()` macro based on the provided list of readers containing Twine INI
+/// translations and write the code to `out` instead of a file of `OUT_DIR`.
+///
+/// See [`Builder::build_translations_to_writer`].
+pub fn build_translations_to_writer<R: Read, W: Write>(
+    readers: &mut [R],
+    out: &mut W,
+) -> io::Result<()> {
+    Ok(Builder::new().build_translations_to_writer(readers, out)?)
+}
 
-    Ok(())
+/// Generate the `t!()` macro based on the provided list of readers, each one containing the
+/// translations of a single language.
+///
+/// The sections of these files contain only the translated text, without the `lang =` prefix:
+///
+/// ```text
+/// [band_tool]
+///     Outil
+/// ```
+///
+/// The translations of all the readers are merged together.
+pub fn build_translations_from_readers_with_lang<R: Read, P: AsRef<Path>>(
+    readers: &mut [(R, &str)],
+    output_file: P,
+) -> io::Result<()> {
+    Ok(Builder::new().build_translations_from_readers_with_lang(readers, output_file)?)
 }
 
-fn read_twine_ini<R: Read>(reader: &mut R) -> io::Result<TwineData> {
-    use std::io::BufRead;
+/// Generate one module per Twine INI translation file and a shared file with the `Lang` enum.
+///
+/// See [`Builder::build_translations_split`].
+pub fn build_translations_split<P: AsRef<Path>, O: AsRef<Path>, L: AsRef<Path>>(
+    ini_files: &[(P, O)],
+    shared_lang_file: L,
+) -> io::Result<()> {
+    Ok(Builder::new().build_translations_split(ini_files, shared_lang_file)?)
+}
 
-    let mut map: TwineData = HashMap::new();
-    let mut section = None;
+/// Generate the translations with options.
+///
+/// ```no_run
+/// fn main() {
+///     twine::Builder::new()
+///         .strict(true)
+///         .build_translations(&["translations.ini"], "i18n.rs")
+///         .unwrap();
+/// }
+/// ```
+pub struct Builder<'a> {
+    strict: bool,
+    lang_code: bool,
+    named_args: bool,
+    typed_functions: bool,
+    doctests: bool,
+    no_std: bool,
+    uppercase_regions: bool,
+    ignore_regions: bool,
+    html_escape: bool,
+    pseudolocale: bool,
+    check_identical: bool,
+    check_whitespace: bool,
+    strict_placeholders: bool,
+    fail_on_fuzzy: bool,
+    value_transform: Option<ValueTransform<'a>>,
+    fallback_order: Vec<String>,
+    default_lang: Option<String>,
+    local_macros: Option<String>,
+    source_lang: Option<String>,
+    ffi: Option<String>,
+    const_table: bool,
+    summary: bool,
+    indentation: String,
+    #[cfg(feature = "encoding_rs")]
+    encoding: Option<&'static encoding_rs::Encoding>,
+    #[cfg(feature = "locale-numbers")]
+    locale_numbers: bool,
+    #[cfg(feature = "locale-numbers")]
+    locale_count: bool,
+    #[cfg(feature = "serde")]
+    serde: bool,
+    cargo_output: Box<dyn Write + 'a>,
+}
 
-    let reader = io::BufReader::new(reader);
-    for (i, line) in reader.lines().enumerate() {
-        let line = line?;
-        if let Some(caps) = RE_SECTION.captures(line.as_str()) {
-            section = Some(
-                map.entry(caps.get(1).unwrap().as_str().to_owned())
-                    .or_default(),
-            );
-        }
-        if let Some(caps) = RE_KEY_VALUE.captures(line.as_str()) {
-            if let Some(section) = section.as_mut() {
-                section.push((
-                    caps.get(1).unwrap().as_str().to_owned(),
-                    caps.get(2).unwrap().as_str().to_owned(),
-                ));
-            } else {
-                panic!("key-value outside section at line {}", i + 1);
-            }
+impl Default for Builder<'_> {
+    fn default() -> Self {
+        Self {
+            strict: false,
+            lang_code: false,
+            named_args: false,
+            typed_functions: false,
+            doctests: false,
+            no_std: false,
+            uppercase_regions: false,
+            ignore_regions: false,
+            html_escape: false,
+            pseudolocale: false,
+            check_identical: false,
+            check_whitespace: false,
+            strict_placeholders: false,
+            fail_on_fuzzy: false,
+            value_transform: None,
+            fallback_order: Vec::new(),
+            default_lang: None,
+            local_macros: None,
+            source_lang: None,
+            ffi: None,
+            const_table: false,
+            summary: false,
+            indentation: "    ".to_string(),
+            #[cfg(feature = "encoding_rs")]
+            encoding: None,
+            #[cfg(feature = "locale-numbers")]
+            locale_numbers: false,
+            #[cfg(feature = "locale-numbers")]
+            locale_count: false,
+            #[cfg(feature = "serde")]
+            serde: true,
+            cargo_output: Box::new(io::stdout()),
         }
     }
-
-    Ok(map)
 }
 
-struct TwineFormatter {
-    map: TwineData,
-}
+impl<'a> Builder<'a> {
+    /// Create a builder with the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-impl fmt::Display for TwineFormatter {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let mut f = CodeFormatter::new(f, "    ");
-        let mut all_languages = HashSet::new();
+    /// Fail when the translations have a problem instead of emitting a warning.
+    ///
+    /// The problems checked are:
+    ///
+    ///  *  a translation longer than the `maxlen` of its key;
+    ///  *  a translation containing a control character other than the tabulation (e.g. `NUL` or
+    ///     a lone carriage return);
+    ///  *  the problems of the translations compared to the source language (see
+    ///     [`Builder::source_lang`]);
+    ///  *  a key not translated in the default language (see [`Builder::default_lang`]);
+    ///  *  a translation in a language not declared in the section `[__languages__]`;
+    ///  *  a translation not using the placeholders of the section `[__schema__]`;
+    ///  *  a translation marked with the metadata `fuzzy` (see [`Builder::fail_on_fuzzy`]).
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
 
-        write!(
-            f,
-            r#"
-            #[macro_export]
-            macro_rules! t {{
-            "#,
-        )?;
-        f.indent(1);
+    /// Generate the enum `LangCode`: a `#[repr(u16)]` version of `Lang` with a variant for every
+    /// language and region.
+    ///
+    /// The codes are assigned in the alphabetical order of the languages and regions. It
+    /// implements `From<Lang>`, `TryFrom<u16>` and `Lang` implements `From<LangCode>`.
+    pub fn lang_code(mut self, lang_code: bool) -> Self {
+        self.lang_code = lang_code;
+        self
+    }
 
-        let mut sorted: Vec<_> = self.map.iter().collect();
-        sorted.sort_unstable_by(|(a_key, _), (b_key, _)| a_key.cmp(b_key));
+    /// Allow passing named arguments to `t!()`: `t!(greeting, name = "Sam" => lang)`.
+    ///
+    /// The translations refer to them using Rust's format syntax: `en = Hello {name}!`.
+    pub fn named_args(mut self, named_args: bool) -> Self {
+        self.named_args = named_args;
+        self
+    }
 
-        for (key, translations) in sorted {
-            let key = Self::normalize_key(key.as_str());
-            write!(
-                f,
-                r#"
-                ({key} $(, $fmt_args:expr)* => $lang:expr) => {{{{
-                    #[allow(unreachable_patterns)]
-                    match $lang {{
-                "#,
-            )?;
-            f.indent(2);
+    /// Generate a function for every key with the types of its arguments inferred from the
+    /// printf's format placeholders of its translations, e.g. for `en = %.0f%`:
+    /// `format_percentage(lang: &Lang, arg1: f64) -> String`.
+    ///
+    /// | Placeholders  | Type                       |
+    /// |---------------|----------------------------|
+    /// | `%d`, `%i`    | `i64`                      |
+    /// | `%f`          | `f64`                      |
+    /// | `%s`, `%@`    | `impl std::fmt::Display`   |
+    /// | `%x`          | `impl std::fmt::LowerHex`  |
+    /// | `%X`          | `impl std::fmt::UpperHex`  |
+    ///
+    /// When the translations use different placeholders for the same argument, the argument
+    /// implements all their traits (`i64` and `f64` are replaced by `std::fmt::Display`). The
+    /// functions of the plural keys take the count (`i64`) as first argument and use the
+    /// cardinal numbers. The keys using named arguments do not have a function.
+    pub fn typed_functions(mut self, typed_functions: bool) -> Self {
+        self.typed_functions = typed_functions;
+        self
+    }
 
-            self.generate_match_arms(&mut f, translations, &mut all_languages)?;
+    /// Document every variant of the enum `Key` with an example calling `t!()` in the first
+    /// language of the key (default: `false`): `cargo test --doc` checks the translations.
+    ///
+    /// The examples assert the output of the keys without arguments and call the others with
+    /// sample arguments. The generated file must be included at the root of a library crate
+    /// named like its package (`CARGO_PKG_NAME`) and the macros must be exported: nothing is
+    /// generated for the local macros, the plural keys and the keys using named arguments. This
+    /// makes the generated file bigger.
+    pub fn doctests(mut self, doctests: bool) -> Self {
+        self.doctests = doctests;
+        self
+    }
 
-            f.dedent(2);
-            write!(
-                f,
-                r#"
-                    }}
-                }}}};
-                "#,
-            )?;
-        }
-        f.dedent(1);
+    /// Generate `extern "C"` functions giving the raw translations to the callers written in
+    /// another language, their names start with `prefix` (e.g. `i18n_translate`).
+    ///
+    /// The keys are indexed in their alphabetical order and the languages in the order of
+    /// `Lang::all_languages()`, starting at `0`: the indices only change when a key or a language
+    /// is added or removed. The ABI is:
+    ///
+    /// ```c
+    /// uint32_t i18n_key_count(void);
+    /// uint32_t i18n_lang_count(void);
+    /// // -1 if the key (`band_tool`) or the language (`en`, `en_gb` or `en-GB`) is unknown
+    /// int32_t i18n_key_index(const uint8_t *name, size_t len);
+    /// int32_t i18n_lang_index(const uint8_t *code, size_t len);
+    /// // NULL if the key is not translated in the language nor in the language without region,
+    /// // otherwise a static UTF-8 string terminated by NUL, its length (without NUL) is written in
+    /// // `len` if it is not NULL
+    /// const uint8_t *i18n_translate(uint32_t key, uint32_t lang, size_t *len);
+    /// ```
+    ///
+    /// The translations are returned with their escape sequences decoded, the placeholders are
+    /// not replaced. The functions are only generated by `build_translations()` and
+    /// `build_translations_from_str()`, the statics `FFI_KEYS` and `FFI_LANGS` give the names
+    /// by index on the Rust side.
+    pub fn ffi(mut self, prefix: &str) -> Self {
+        self.ffi = Some(prefix.to_string());
+        self
+    }
 
-        write!(
-            f,
-            r#"
-            }}
-            "#,
-        )?;
+    /// Generate the raw translations in a constant array indexed by key and language (default:
+    /// `false`): `TABLE[key as usize][lang_index(&lang)]` is a direct access, without hashing nor
+    /// matching the key.
+    ///
+    /// The rows follow the variants of `Key` and the columns the languages of
+    /// `Lang::all_languages()` (`NUM_KEYS` and `NUM_LANGS`). The cells of the languages not
+    /// translated contain the translation of the language without region, then the one used by
+    /// `t!()` for the other languages. The plural keys only have their category `other`. The table
+    /// is not generated by `build_translations_split()`.
+    pub fn const_table(mut self, const_table: bool) -> Self {
+        self.const_table = const_table;
+        self
+    }
 
-        // generate the `Lang` enum and its variants
-        write!(
-            f,
-            r#"
-            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-            #[allow(dead_code)]
-            pub enum Lang {{
-            "#,
-        )?;
-        f.indent(1);
+    /// Generate code for a `#![no_std]` crate using `alloc` (default: `false`).
+    ///
+    /// The crate must declare `extern crate alloc;` at its root: the macros return an
+    /// `alloc::string::String`. `Lang::from_locale_env()` and the items of the features
+    /// `current-lang` and `gettext` need `std` and are not generated.
+    pub fn no_std(mut self, no_std: bool) -> Self {
+        self.no_std = no_std;
+        self
+    }
 
-        let lang_variants: HashSet<_> = all_languages
-            .iter()
-            .map(|(lang, _)| lang.as_str())
-            .collect();
-        let mut lang_variants: Vec<_> = lang_variants.into_iter().collect();
-        lang_variants.sort_unstable();
+    /// Display the regions in uppercase as in ISO 3166 (default: `false`): `Lang::En("gb")` is
+    /// displayed `en_GB` instead of `en_gb`.
+    ///
+    /// This applies to everything based on `Display`: `Lang::code()`, `Lang::all_codes()` and the
+    /// serialization with serde. The regions are still written in lowercase in the INI file and in
+    /// `Lang` (`Lang::En("gb")`), the parsing and the deserialization accept any case.
+    pub fn uppercase_regions(mut self, uppercase_regions: bool) -> Self {
+        self.uppercase_regions = uppercase_regions;
+        self
+    }
 
-        for lang in lang_variants.iter() {
-            write!(
-                f,
-                r#"
-                {lang}(&'static str),
-                "#,
-            )?;
-        }
+    /// Ignore the regions (default: `false`): the translations of `en-gb` are merged into `en`
+    /// and `Lang` has unit variants (`Lang::En`, `Lang::Fr`) instead of carrying a region.
+    ///
+    /// When the regions of a language have different translations of a key, the last one in the
+    /// INI file is used and a warning is emitted, this becomes an error in strict mode. The
+    /// parsing and the deserialization accept a region but drop it: `Lang::parse("en_gb")` is
+    /// `Some(Lang::En)`, `Lang::region()` is always empty.
+    pub fn ignore_regions(mut self, ignore_regions: bool) -> Self {
+        self.ignore_regions = ignore_regions;
+        self
+    }
 
-        f.dedent(1);
-        write!(
-            f,
-            r#"
-            }}
+    /// Escape the characters `<`, `>` and `&` of the translations with the HTML entities `&lt;`,
+    /// `&gt;` and `&amp;` (default: `false`), for the translations inserted into HTML: `a < b`
+    /// becomes `a &lt; b`.
+    ///
+    /// Only the text written in the translations is escaped, including the escape sequences
+    /// (`\u003C`). **The arguments given at runtime to the placeholders (`%s`, `{name}`) are not
+    /// escaped**: they must be escaped by the caller. The entities already written in the
+    /// translations are escaped again (`&amp;` becomes `&amp;amp;`).
+    pub fn html_escape(mut self, html_escape: bool) -> Self {
+        self.html_escape = html_escape;
+        self
+    }
 
-            impl Lang {{
-                pub fn all_languages() -> &'static [&'static Lang] {{
-                    &[
-            "#,
+    /// Generate the language `Lang::Pseudo("")` by accenting and padding the translations of the
+    /// default language (the first language of every key): `[Ĥéļļö Ŵöŕļð!!!!]`.
+    ///
+    /// The placeholders are kept untouched. This is useful to check the layout of the user
+    /// interface before the real translations are available.
+    pub fn pseudolocale(mut self, pseudolocale: bool) -> Self {
+        self.pseudolocale = pseudolocale;
+        self
+    }
+
+    /// Emit a warning for the keys translated with the same value in several languages, often a
+    /// copy of the source language that was never translated (default: `false`).
+    ///
+    /// The regions of a language are not compared to each other and the plural categories are
+    /// only compared to the same category. The values with less than 4 letters outside of the
+    /// placeholders (`OK`, `%d`, `%s: %s`) are ignored. This is not an error in strict mode.
+    pub fn check_identical(mut self, check_identical: bool) -> Self {
+        self.check_identical = check_identical;
+        self
+    }
+
+    /// Emit a warning for the keys whose translations do not have the same whitespace at the
+    /// start and at the end (default: `false`): `en = Save\u0020` but `fr = Enregistrer` misaligns
+    /// the UIs concatenating the translations.
+    ///
+    /// The spaces around a value are trimmed by the parser, only the escape sequences (`\t`,
+    /// `\n`, `\u0020`, `\u00a0`, ...) keep whitespace. Every translation is compared to the first
+    /// one of its key. This is not an error in strict mode.
+    pub fn check_whitespace(mut self, check_whitespace: bool) -> Self {
+        self.check_whitespace = check_whitespace;
+        self
+    }
+
+    /// Fail on the `%`-sequences that look like placeholders but are not valid instead of
+    /// writing them as they are (default: `false`): a `%` followed by a letter, a digit or a dot
+    /// (`%z`, `%.f`) and `%@` followed by a letter (`%@s`).
+    ///
+    /// The other `%` are still literal (`50 %`, `%.0f%`) so the files using them keep working.
+    pub fn strict_placeholders(mut self, strict_placeholders: bool) -> Self {
+        self.strict_placeholders = strict_placeholders;
+        self
+    }
+
+    /// Report the translations marked with the metadata `fuzzy` like the other problems of the
+    /// translations: they fail in strict mode (default: `false`).
+    ///
+    /// When disabled, a warning lists them even in strict mode.
+    pub fn fail_on_fuzzy(mut self, fail_on_fuzzy: bool) -> Self {
+        self.fail_on_fuzzy = fail_on_fuzzy;
+        self
+    }
+
+    /// Transform every translation before generating the code. The function receives the key, the
+    /// language (with its region and plural category, e.g. `en-gb.one`) and the translation.
+    ///
+    /// The transform is applied on the raw translation, as written in the INI file: the escape
+    /// sequences (`\"`, `\u00e9`, ...) are not decoded yet and the printf's placeholders are not
+    /// converted yet. It runs before the checks of the translations and the pseudolocalization.
+    ///
+    /// ```no_run
+    /// twine::Builder::new()
+    ///     .value_transform(Box::new(|_key, _lang, value| value.replace('\'', "’")))
+    ///     .build_translations(&["translations.ini"], "i18n.rs")
+    ///     .unwrap();
+    /// ```
+    pub fn value_transform(mut self, value_transform: ValueTransform<'a>) -> Self {
+        self.value_transform = Some(value_transform);
+        self
+    }
+
+    /// Choose the translation used when a key is not translated in the language requested: the
+    /// first language of the list the key has a translation for, e.g. `&["de", "fr", "en"]`.
+    ///
+    /// The languages can have a region (`de-ch`). By default, and when none of the languages of
+    /// the list is available, the first translation of the key is used.
+    pub fn fallback_order(mut self, fallback_order: &[&str]) -> Self {
+        self.fallback_order = fallback_order.iter().map(|x| x.to_string()).collect();
+        self
+    }
+
+    /// Use the translation of `default_lang` (e.g. `en`) when a key is not translated in the
+    /// language requested nor in the languages of [`Builder::fallback_order`]. Every key must be
+    /// translated in this language.
+    pub fn default_lang(mut self, default_lang: &str) -> Self {
+        self.default_lang = Some(default_lang.to_string());
+        self
+    }
+
+    /// Generate the macros without `#[macro_export]` for a file included in a module instead of
+    /// the root of the crate. The path of the module is relative to the root of the crate:
+    ///
+    /// ```ignore
+    /// // build.rs
+    /// twine::Builder::new()
+    ///     .local_macros("ui::i18n")
+    ///     .build_translations(&["translations.ini"], "i18n.rs")
+    ///     .unwrap();
+    ///
+    /// // ui/i18n.rs
+    /// include!(concat!(env!("OUT_DIR"), "/i18n.rs"));
+    /// ```
+    ///
+    /// The macros can then be used after the `include!()` in the module and its submodules, or by
+    /// path anywhere in the crate (`crate::ui::i18n::t!()`).
+    pub fn local_macros(mut self, module: &str) -> Self {
+        self.local_macros = Some(module.to_string());
+        self
+    }
+
+    /// Check the translations against a source language (usually `en`):
+    ///
+    ///  *  every key must be translated in the source language;
+    ///  *  every key must be translated in all the other languages (the regions are optional);
+    ///  *  the translations must use the same placeholders as the source language, the plural keys
+    ///     are not checked.
+    pub fn source_lang(mut self, source_lang: &str) -> Self {
+        self.source_lang = Some(source_lang.to_string());
+        self
+    }
+
+    /// Indent the generated code with `indentation` for every level instead of 4 spaces, e.g.
+    /// `"\t"` or `"  "`.
+    pub fn indentation(mut self, indentation: &str) -> Self {
+        self.indentation = indentation.to_string();
+        self
+    }
+
+    /// Read the INI files in another encoding than UTF-8, e.g. Windows-1252 for legacy files:
+    ///
+    /// ```no_run
+    /// twine::Builder::new()
+    ///     .encoding(twine::encoding_rs::WINDOWS_1252)
+    ///     .build_translations(&["translations.ini"], "i18n.rs")
+    ///     .unwrap();
+    /// ```
+    ///
+    /// The files and readers are transcoded to UTF-8 before being parsed, a byte order mark
+    /// overrides the encoding. The `&str` given to `build_translations_from_str()` are already
+    /// UTF-8: they are not transcoded.
+    #[cfg(feature = "encoding_rs")]
+    pub fn encoding(mut self, encoding: &'static encoding_rs::Encoding) -> Self {
+        self.encoding = Some(encoding);
+        self
+    }
+
+    /// Display the numbers of the placeholders `%d` and `%f` of `t!()` and `t_into!()` with the
+    /// separators of the thousands and of the decimals of the language of the translation:
+    /// `%.2f` gives `1,234.50` in English, `1.234,50` in German and `1 234,50` in French.
+    ///
+    /// The arguments are wrapped in the generated type `LocaleNumber` which formats them like
+    /// Rust does before changing the separators. The languages missing in the built-in table are
+    /// not changed.
+    #[cfg(feature = "locale-numbers")]
+    pub fn locale_numbers(mut self, locale_numbers: bool) -> Self {
+        self.locale_numbers = locale_numbers;
+        self
+    }
+
+    /// Display the count of the placeholder `#` of `plural!()` and `ordinal!()` with the
+    /// separator of the thousands of the language of the translation: `# files` gives
+    /// `1,000,000 files` in English and `1.000.000 Dateien` in German.
+    ///
+    /// The count is wrapped in `LocaleNumber` like the numbers of `locale_numbers`, the other
+    /// placeholders of the plural translations are not changed. The languages missing in the
+    /// built-in table are not changed.
+    #[cfg(feature = "locale-numbers")]
+    pub fn locale_count(mut self, locale_count: bool) -> Self {
+        self.locale_count = locale_count;
+        self
+    }
+
+    /// Generate the implementations of `Serialize` and `Deserialize` for `Lang` (default:
+    /// `true`). Disable it when the feature `serde` is enabled by another crate and `Lang` must
+    /// not implement them, e.g. to implement them differently. Without the feature `serde` the
+    /// option has no effect.
+    #[cfg_attr(not(feature = "serde"), allow(unused_mut))]
+    pub fn serde(mut self, serde: bool) -> Self {
+        #[cfg(feature = "serde")]
+        {
+            self.serde = serde;
+        }
+        #[cfg(not(feature = "serde"))]
+        let _ = serde;
+        self
+    }
+
+    /// Print the size of the generated catalog after the generation (default: `false`):
+    /// `cargo:warning=twine: 342 keys, 5 languages, 1710 strings generated`.
+    ///
+    /// The strings are the translations of all the keys, every plural category counting as one.
+    /// `build_translations_split()` prints a summary for every module.
+    pub fn summary(mut self, summary: bool) -> Self {
+        self.summary = summary;
+        self
+    }
+
+    /// Write the instructions for cargo (`cargo:rerun-if-changed=...`, `cargo:warning=...`) to
+    /// `cargo_output` instead of the standard output.
+    pub fn cargo_output<W: Write + 'a>(mut self, cargo_output: W) -> Self {
+        self.cargo_output = Box::new(cargo_output);
+        self
+    }
+
+    /// Generate the `t!()` macro based on the provided list of paths to Twine INI translation
+    /// files.
+    pub fn build_translations<P: AsRef<Path>, O: AsRef<Path>>(
+        &mut self,
+        ini_files: &[P],
+        output_file: O,
+    ) -> Result<(), TwineError> {
+        let mut map = HashMap::new();
+
+        // read all the INI files (might override existing keys)
+        for file_path in ini_files {
+            let file_path = file_path.as_ref();
+            writeln!(
+                self.cargo_output,
+                "cargo:rerun-if-changed={}",
+                file_path.display()
+            )?;
+            let other_map = self
+                .read_ini(&mut fs::File::open(file_path)?, None)
+                .map_err(|error| TwineError::Parse {
+                    file: Some(file_path.display().to_string()),
+                    error,
+                })?;
+            map.extend(other_map);
+        }
+
+        self.write_translations(map, output_file)
+    }
+
+    /// Generate the `t!()` macro based on the provided list of paths to TOML translation files:
+    /// every table is a key and its strings are the translations, like the sections of the Twine
+    /// INI format.
+    ///
+    /// ```toml
+    /// [band_tool]
+    /// en = "Tool"
+    /// fr = "Outil"
+    ///
+    /// [n_files]
+    /// maxlen = 20
+    /// en = { one = "%d file", other = "%d files" }
+    /// fr.one = "%d fichier"
+    /// fr.other = "%d fichiers"
+    ///
+    /// [errors.not_found]
+    /// en = """
+    /// Not found,
+    /// check the path."""
+    /// ```
+    ///
+    /// The escape sequences and the multi-line strings of TOML are decoded, the whitespace
+    /// around a string is kept. A nested table (`[errors.not_found]`) is the key `errors.not_found`
+    /// and the plural categories are dotted keys (`fr.one`) or inline tables. The metadata are
+    /// written as in the INI format (`maxlen = 20`, `fuzzy = ["fr"]`). A table containing only
+    /// plural categories is a plural translation, the other nested tables are keys.
+    #[cfg(feature = "toml")]
+    pub fn build_translations_from_toml<P: AsRef<Path>, O: AsRef<Path>>(
+        &mut self,
+        toml_files: &[P],
+        output_file: O,
+    ) -> Result<(), TwineError> {
+        let mut map = HashMap::new();
+
+        // read all the TOML files (might override existing keys)
+        for file_path in toml_files {
+            let file_path = file_path.as_ref();
+            writeln!(
+                self.cargo_output,
+                "cargo:rerun-if-changed={}",
+                file_path.display()
+            )?;
+            let other_map = toml::read_toml(&fs::read_to_string(file_path)?).map_err(|error| {
+                TwineError::ParseToml {
+                    file: file_path.display().to_string(),
+                    error,
+                }
+            })?;
+            map.extend(other_map);
+        }
+
+        self.write_translations(map, output_file)
+    }
+
+    /// Generate the `t!()` macro based on the provided list of `&str` containing Twine INI
+    /// translations.
+    pub fn build_translations_from_str<P: AsRef<Path>>(
+        &mut self,
+        strs: &[&str],
+        output_file: P,
+    ) -> Result<(), TwineError> {
+        let mut readers = strs.iter().map(io::Cursor::new).collect::<Vec<_>>();
+
+        self.build_translations_from_readers(readers.as_mut_slice(), output_file)
+    }
+
+    /// Generate the `t!()` macro based on the provided list of `(name, content)` containing
+    /// Twine INI translations, the content usually coming from `include_str!()`:
+    ///
+    /// ```ignore
+    /// fn main() {
+    ///     twine::Builder::new()
+    ///         .build_translations_from_named_str(
+    ///             &[("translations.ini", include_str!("translations.ini"))],
+    ///             "i18n.rs",
+    ///         )
+    ///         .unwrap();
+    /// }
+    /// ```
+    ///
+    /// The name is used in the error messages and in `cargo:rerun-if-changed=...`: it should be
+    /// the path of the file relative to the package. The generated code only depends on the
+    /// content, not on the name.
+    pub fn build_translations_from_named_str<P: AsRef<Path>>(
+        &mut self,
+        strs: &[(&str, &str)],
+        output_file: P,
+    ) -> Result<(), TwineError> {
+        let mut map = HashMap::new();
+
+        // read all the INI files (might override existing keys)
+        for (name, content) in strs {
+            writeln!(self.cargo_output, "cargo:rerun-if-changed={}", name)?;
+            let other_map =
+                read_twine_ini(&mut io::Cursor::new(content), None).map_err(|error| {
+                    TwineError::Parse {
+                        file: Some(name.to_string()),
+                        error,
+                    }
+                })?;
+            map.extend(other_map);
+        }
+
+        self.write_translations(map, output_file)
+    }
+
+    /// Generate the `t!()` macro based on the provided list of readers containing Twine INI
+    /// translations.
+    pub fn build_translations_from_readers<R: Read, P: AsRef<Path>>(
+        &mut self,
+        readers: &mut [R],
+        output_file: P,
+    ) -> Result<(), TwineError> {
+        let map = self.read_readers(readers)?;
+
+        self.write_translations(map, output_file)
+    }
+
+    /// Generate the `t!()` macro based on the provided list of readers containing Twine INI
+    /// translations and write the code to `out` instead of a file of `OUT_DIR`, e.g. to keep
+    /// it in memory:
+    ///
+    /// ```
+    /// let ini = "[band_tool]\n    en = Tool\n";
+    /// let mut code = Vec::new();
+    /// twine::Builder::new()
+    ///     .build_translations_to_writer(&mut [ini.as_bytes()], &mut code)
+    ///     .unwrap();
+    /// assert!(String::from_utf8(code).unwrap().contains("macro_rules! t"));
+    /// ```
+    pub fn build_translations_to_writer<R: Read, W: Write>(
+        &mut self,
+        readers: &mut [R],
+        out: &mut W,
+    ) -> Result<(), TwineError> {
+        let map = self.read_readers(readers)?;
+        let map = self.prepare_translations(map)?;
+
+        self.write_code(map, Output::All, out)
+    }
+
+    /// Generate the `t!()` macro based on the provided list of readers, each one containing the
+    /// translations of a single language.
+    ///
+    /// See [`build_translations_from_readers_with_lang`].
+    pub fn build_translations_from_readers_with_lang<R: Read, P: AsRef<Path>>(
+        &mut self,
+        readers: &mut [(R, &str)],
+        output_file: P,
+    ) -> Result<(), TwineError> {
+        let mut map: TwineData = HashMap::new();
+
+        // read all the INI files (merging the translations of the existing keys)
+        for (reader, lang) in readers {
+            let other_map = self
+                .read_ini(reader, Some(*lang))
+                .map_err(|error| TwineError::Parse { file: None, error })?;
+            for (key, section) in other_map {
+                map.entry(key).or_default().merge(section);
+            }
+        }
+
+        self.write_translations(map, output_file)
+    }
+
+    /// Generate one module per Twine INI translation file and a shared file with the `Lang` enum.
+    ///
+    /// The shared file must be included at the root of the crate. Every module file contains a
+    /// `t!()` macro (and the plural macros) restricted to the keys of its INI file, which is not
+    /// exported at the root of the crate but can be used in its module or by path
+    /// (`crate::my_module::t!()`):
+    ///
+    /// ```ignore
+    /// // build.rs
+    /// twine::Builder::new()
+    ///     .build_translations_split(
+    ///         &[("billing.ini", "billing.rs"), ("shipping.ini", "shipping.rs")],
+    ///         "lang.rs",
+    ///     )
+    ///     .unwrap();
+    ///
+    /// // main.rs
+    /// include!(concat!(env!("OUT_DIR"), "/lang.rs"));
+    ///
+    /// mod billing {
+    ///     include!(concat!(env!("OUT_DIR"), "/billing.rs"));
+    /// }
+    /// ```
+    pub fn build_translations_split<P: AsRef<Path>, O: AsRef<Path>, L: AsRef<Path>>(
+        &mut self,
+        ini_files: &[(P, O)],
+        shared_lang_file: L,
+    ) -> Result<(), TwineError> {
+        let mut shared = TwineData::new();
+        let mut modules = Vec::new();
+
+        for (file_path, output_file) in ini_files {
+            let file_path = file_path.as_ref();
+            writeln!(
+                self.cargo_output,
+                "cargo:rerun-if-changed={}",
+                file_path.display()
+            )?;
+            let map = self
+                .read_ini(&mut fs::File::open(file_path)?, None)
+                .map_err(|error| TwineError::Parse {
+                    file: Some(file_path.display().to_string()),
+                    error,
+                })?;
+            let map = self.prepare_translations(map)?;
+            // the shared file only needs the languages
+            for (key, section) in map.iter() {
+                shared
+                    .entry(key.clone())
+                    .or_default()
+                    .translations
+                    .extend(section.translations.iter().cloned());
+            }
+            modules.push((map, output_file));
+        }
+
+        self.write_output(shared, Output::Shared, shared_lang_file)?;
+        for (map, output_file) in modules {
+            self.write_output(map, Output::Module, output_file)?;
+        }
+
+        Ok(())
+    }
+
+    // read all the INI files (might override existing keys)
+    fn read_readers<R: Read>(&self, readers: &mut [R]) -> Result<TwineData, TwineError> {
+        let mut map = HashMap::new();
+        for reader in readers {
+            let other_map = self
+                .read_ini(reader, None)
+                .map_err(|error| TwineError::Parse { file: None, error })?;
+            map.extend(other_map);
+        }
+        Ok(map)
+    }
+
+    // read the translations, transcoded to UTF-8 first if an encoding is given
+    fn read_ini<R: Read>(&self, reader: &mut R, lang: Option<&str>) -> io::Result<TwineData> {
+        #[cfg(feature = "encoding_rs")]
+        {
+            if let Some(encoding) = self.encoding {
+                let mut bytes = Vec::new();
+                reader.read_to_end(&mut bytes)?;
+                let (text, _) = encoding.decode_with_bom_removal(&bytes);
+                return read_twine_ini(&mut io::Cursor::new(text.as_bytes()), lang);
+            }
+        }
+
+        read_twine_ini(reader, lang)
+    }
+
+    fn write_translations<P: AsRef<Path>>(
+        &mut self,
+        map: TwineData,
+        output_file: P,
+    ) -> Result<(), TwineError> {
+        let map = self.prepare_translations(map)?;
+        self.write_output(map, Output::All, output_file)
+    }
+
+    // transform and check the translations
+    fn prepare_translations(&mut self, mut map: TwineData) -> Result<TwineData, TwineError> {
+        if let Some(languages) = map.remove(LANGUAGES_SECTION) {
+            self.check_languages(&map, &languages)?;
+        }
+        let defaults = map.remove(DEFAULTS_SECTION);
+        let schema = map.remove(SCHEMA_SECTION);
+        check_keys(&map)?;
+        self.resolve_source_markers(&mut map);
+        resolve_references(&mut map)?;
+        if let Some(value_transform) = self.value_transform.as_ref() {
+            for (key, section) in map.iter_mut() {
+                for (lang, text) in section.translations.iter_mut() {
+                    *text = value_transform(key, lang, text);
+                }
+            }
+        }
+
+        self.check_max_length(&map)?;
+        self.check_characters(&map)?;
+        self.check_source_lang(&map)?;
+        if let Some(schema) = schema {
+            self.check_schema(&map, &schema)?;
+        }
+        self.check_default_lang(&map)?;
+        self.check_deprecated(&map)?;
+        self.check_fuzzy(&map)?;
+        if self.strict_placeholders {
+            check_placeholders(&map)?;
+        }
+        if self.check_identical {
+            self.check_identical_values(&map)?;
+        }
+        if self.check_whitespace {
+            self.check_whitespace_values(&map)?;
+        }
+        if let Some(defaults) = defaults {
+            self.apply_defaults(&mut map, &defaults);
+        }
+        if self.ignore_regions {
+            self.collapse_regions(&mut map)?;
+        }
+
+        if self.pseudolocale {
+            add_pseudolocale(&mut map);
+        }
+        if self.html_escape {
+            for section in map.values_mut() {
+                for (_, text) in section.translations.iter_mut() {
+                    *text = html_escape(text);
+                }
+            }
+        }
+
+        Ok(map)
+    }
+
+    fn write_output<P: AsRef<Path>>(
+        &mut self,
+        map: TwineData,
+        output: Output,
+        output_file: P,
+    ) -> Result<(), TwineError> {
+        let out_dir = std::env::var_os("OUT_DIR").unwrap();
+        let dest_path = Path::new(&out_dir).join(output_file);
+        let _ = fs::create_dir_all(dest_path.parent().unwrap());
+        let f = io::BufWriter::new(
+            fs::OpenOptions::new()
+                .create(true)
+                .write(true)
+                .truncate(true)
+                .open(dest_path)?,
+        );
+
+        self.write_code(map, output, f)
+    }
+
+    fn write_code<W: Write>(
+        &mut self,
+        map: TwineData,
+        output: Output,
+        out: W,
+    ) -> Result<(), TwineError> {
+        // the shared file only has the languages of the modules
+        let summary = (self.summary && output != Output::Shared).then(|| {
+            let languages: HashSet<_> = map
+                .values()
+                .flat_map(|section| section.translations.iter())
+                .map(|(lang, _)| {
+                    let (lang, region, _) = parse_lang(lang.as_str());
+                    (lang, region)
+                })
+                .collect();
+            let strings: usize = map.values().map(|x| x.translations.len()).sum();
+            (map.len(), languages.len(), strings)
+        });
+
+        let code = TwineFormatter {
+            map,
+            lang_code: self.lang_code,
+            named_args: self.named_args,
+            typed_functions: self.typed_functions,
+            // the examples refer to the items by the name of the crate
+            doctests: std::env::var("CARGO_PKG_NAME")
+                .ok()
+                .filter(|_| self.doctests)
+                .map(|x| x.replace('-', "_")),
+            no_std: self.no_std,
+            uppercase_regions: self.uppercase_regions,
+            ignore_regions: self.ignore_regions,
+            fallback_order: &self.fallback_order,
+            default_lang: self.default_lang.as_deref(),
+            local_macros: self.local_macros.as_deref(),
+            ffi: self.ffi.as_deref(),
+            const_table: self.const_table,
+            #[cfg(feature = "locale-numbers")]
+            locale_numbers: self.locale_numbers,
+            #[cfg(feature = "locale-numbers")]
+            locale_count: self.locale_count,
+            #[cfg(feature = "serde")]
+            serde: self.serde,
+            output,
+        };
+
+        // the code is written line by line: only the line being generated is kept in memory
+        let mut writer = IndentWriter {
+            inner: out,
+            indentation: &self.indentation,
+            line: String::new(),
+            error: None,
+        };
+        if write!(writer, "{}", code).is_err() {
+            return Err(writer
+                .error
+                .unwrap_or_else(|| io::Error::new(io::ErrorKind::Other, "formatter error"))
+                .into());
+        }
+        writer.finish()?;
+
+        if let Some((keys, languages, strings)) = summary {
+            writeln!(
+                self.cargo_output,
+                "cargo:warning=twine: {} keys, {} languages, {} strings generated",
+                keys, languages, strings,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    // replace the translations `@source` by the translation of the source language (or the
+    // default language, or the first translation) with the same plural category
+    fn resolve_source_markers(&self, map: &mut TwineData) {
+        let source_lang = self.source_lang.as_deref().or(self.default_lang.as_deref());
+        for section in map.values_mut() {
+            let resolved: Vec<_> = section
+                .translations
+                .iter()
+                .map(|(lang, text)| {
+                    if text != SOURCE_MARKER {
+                        return None;
+                    }
+                    let category = parse_lang(lang).2;
+                    let candidates = section
+                        .translations
+                        .iter()
+                        .filter(|(x, text)| text != SOURCE_MARKER && parse_lang(x).2 == category);
+                    candidates
+                        .clone()
+                        .find(|(x, _)| x.split('.').next() == source_lang)
+                        .or_else(|| candidates.clone().next())
+                        .map(|(_, text)| text.clone())
+                })
+                .collect();
+            for ((_, text), resolved) in section.translations.iter_mut().zip(resolved) {
+                if let Some(resolved) = resolved {
+                    *text = resolved;
+                }
+            }
+        }
+    }
+
+    // merge the translations of the regions into their language (`en-gb.one` into `en.one`), the
+    // last one wins when they are different
+    fn collapse_regions(&mut self, map: &mut TwineData) -> Result<(), TwineError> {
+        let mut sorted: Vec<_> = map.iter_mut().collect();
+        sorted.sort_unstable_by_key(|(key, _)| *key);
+
+        for (key, section) in sorted {
+            let mut translations: Vec<(String, String)> = Vec::new();
+            for (lang, text) in section.translations.drain(..) {
+                let (code, category) = match lang.split_once('.') {
+                    Some((code, category)) => (code, Some(category)),
+                    None => (lang.as_str(), None),
+                };
+                let language = code.split('-').next().unwrap_or(code);
+                let lang = match category {
+                    Some(category) => format!("{}.{}", language, category),
+                    None => language.to_string(),
+                };
+                match translations.iter_mut().find(|(x, _)| *x == lang) {
+                    Some((_, existing)) => {
+                        if *existing != text {
+                            self.report(TwineError::RegionConflict {
+                                key: key.to_owned(),
+                                lang: lang.clone(),
+                            })?;
+                            *existing = text;
+                        }
+                    }
+                    None => translations.push((lang, text)),
+                }
+            }
+            section.translations = translations;
+        }
+
+        Ok(())
+    }
+
+    // report the translations in a language that is not declared, the regions of a language
+    // declared without region are allowed
+    fn check_languages(&mut self, map: &TwineData, languages: &Section) -> Result<(), TwineError> {
+        let declared: HashSet<_> = languages
+            .translations
+            .iter()
+            .map(|(lang, _)| lang.as_str())
+            .collect();
+        let mut sorted: Vec<_> = map.iter().collect();
+        sorted.sort_unstable_by_key(|(key, _)| *key);
+
+        for (key, section) in sorted {
+            for (lang, _) in section.translations.iter() {
+                let without_category = lang.split('.').next().unwrap_or(lang);
+                let without_region = without_category.split('-').next().unwrap_or(lang);
+                if !declared.contains(without_category) && !declared.contains(without_region) {
+                    self.report(TwineError::UnknownLanguage {
+                        key: key.to_owned(),
+                        lang: lang.to_owned(),
+                    })?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // add the value of the first entry of the section `[__defaults__]` matching a key (the key
+    // itself, then the patterns in their order) to the languages it is missing in, unless the key
+    // is translated in a language of the fallback order: the default value comes before the
+    // first language of the key only. The plural keys are left untouched.
+    fn apply_defaults(&self, map: &mut TwineData, defaults: &Section) {
+        let base_lang = |lang: &str| {
+            lang.split(&['-', '.'][..])
+                .next()
+                .unwrap_or(lang)
+                .to_owned()
+        };
+        let languages: BTreeSet<_> = map
+            .values()
+            .flat_map(|section| section.translations.iter())
+            .map(|(lang, _)| base_lang(lang))
+            .collect();
+
+        for (key, section) in map.iter_mut() {
+            if is_plural(&section.translations) {
+                continue;
+            }
+            let default = defaults
+                .translations
+                .iter()
+                .find(|(pattern, _)| pattern == key)
+                .or_else(|| {
+                    defaults
+                        .translations
+                        .iter()
+                        .find(|(pattern, _)| matches_pattern(pattern, key))
+                });
+            let default = match default {
+                Some((_, default)) => default,
+                None => continue,
+            };
+            let has_fallback = self
+                .fallback_order
+                .iter()
+                .chain(self.default_lang.as_ref())
+                .any(|fallback| {
+                    section
+                        .translations
+                        .iter()
+                        .any(|(lang, _)| lang == fallback)
+                });
+            if has_fallback {
+                continue;
+            }
+
+            let translated: HashSet<_> = section
+                .translations
+                .iter()
+                .map(|(lang, _)| base_lang(lang))
+                .collect();
+            for lang in languages.iter().filter(|lang| !translated.contains(*lang)) {
+                let text = match self.value_transform.as_ref() {
+                    Some(value_transform) => value_transform(key, lang, default),
+                    None => default.clone(),
+                };
+                section.translations.push((lang.clone(), text));
+            }
+        }
+    }
+
+    // report the translations longer than the `maxlen` of their key
+    fn check_max_length(&mut self, map: &TwineData) -> Result<(), TwineError> {
+        let mut sorted: Vec<_> = map.iter().collect();
+        sorted.sort_unstable_by_key(|(key, _)| *key);
+
+        for (key, section) in sorted {
+            let max = match section.maxlen {
+                Some(max) => max,
+                None => continue,
+            };
+            for (lang, text) in section.translations.iter() {
+                let actual = text.chars().count();
+                if actual <= max {
+                    continue;
+                }
+                self.report(TwineError::MaxLengthExceeded {
+                    key: key.to_owned(),
+                    lang: lang.to_owned(),
+                    max,
+                    actual,
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // report the translations containing control characters: they would end up in the generated
+    // string literals
+    fn check_characters(&mut self, map: &TwineData) -> Result<(), TwineError> {
+        let mut sorted: Vec<_> = map.iter().collect();
+        sorted.sort_unstable_by_key(|(key, _)| *key);
+
+        for (key, section) in sorted {
+            for (lang, text) in section.translations.iter() {
+                if let Some(c) = text.chars().find(|c| c.is_control() && *c != '\t') {
+                    self.report(TwineError::InvalidCharacter {
+                        key: key.to_owned(),
+                        lang: lang.to_owned(),
+                        codepoint: c as u32,
+                    })?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // report the keys missing in the source language or in another language and the
+    // translations that do not use the placeholders of the source language
+    fn check_source_lang(&mut self, map: &TwineData) -> Result<(), TwineError> {
+        let source_lang = match self.source_lang.clone() {
+            Some(source_lang) => source_lang,
+            None => return Ok(()),
+        };
+        let mut sorted: Vec<_> = map.iter().collect();
+        sorted.sort_unstable_by_key(|(key, _)| *key);
+        // the languages without region and plural category
+        let base_lang = |lang: &str| {
+            lang.split(&['-', '.'][..])
+                .next()
+                .unwrap_or(lang)
+                .to_string()
+        };
+        let all_langs: BTreeSet<_> = map
+            .values()
+            .flat_map(|section| section.translations.iter())
+            .map(|(lang, _)| base_lang(lang))
+            .filter(|lang| *lang != source_lang)
+            .collect();
+
+        for (key, section) in sorted {
+            let langs: BTreeSet<_> = section
+                .translations
+                .iter()
+                .map(|(lang, _)| base_lang(lang))
+                .collect();
+            for lang in std::iter::once(&source_lang).chain(all_langs.iter()) {
+                if !langs.contains(lang) {
+                    self.report(TwineError::MissingTranslation {
+                        key: key.to_owned(),
+                        lang: lang.to_owned(),
+                    })?;
+                }
+            }
+
+            if is_plural(&section.translations) {
+                continue;
+            }
+            let expected = match section
+                .translations
+                .iter()
+                .find(|(lang, _)| *lang == source_lang)
+            {
+                Some((_, text)) => placeholders(text),
+                None => continue,
+            };
+            for (lang, text) in section.translations.iter() {
+                let actual = placeholders(text);
+                if actual != expected {
+                    self.report(TwineError::PlaceholderMismatch {
+                        key: key.to_owned(),
+                        lang: lang.to_owned(),
+                        expected: expected.clone(),
+                        actual,
+                    })?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // the translations that do not use the placeholders required by the section `[__schema__]`,
+    // whatever the language: `order_confirmed = %s, %d` is the same as `%1$s %2$d`. The plural
+    // keys are not checked.
+    fn check_schema(&mut self, map: &TwineData, schema: &Section) -> Result<(), TwineError> {
+        for (key, signature) in schema.translations.iter() {
+            let section = match map.get(key) {
+                Some(section) => section,
+                None => {
+                    self.report(TwineError::UnknownSchemaKey {
+                        key: key.to_owned(),
+                    })?;
+                    continue;
+                }
+            };
+            if is_plural(&section.translations) {
+                continue;
+            }
+
+            let expected = placeholders(&signature.split(',').collect::<Vec<_>>().join(" "));
+            for (lang, text) in section.translations.iter() {
+                let actual = placeholders(text);
+                if actual != expected {
+                    self.report(TwineError::PlaceholderMismatch {
+                        key: key.to_owned(),
+                        lang: lang.to_owned(),
+                        expected: expected.clone(),
+                        actual,
+                    })?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // keys without a translation in the default language
+    fn check_default_lang(&mut self, map: &TwineData) -> Result<(), TwineError> {
+        let default_lang = match self.default_lang.clone() {
+            Some(default_lang) => default_lang,
+            None => return Ok(()),
+        };
+        let mut sorted: Vec<_> = map.iter().collect();
+        sorted.sort_unstable_by_key(|(key, _)| *key);
+
+        for (key, section) in sorted {
+            if !section
+                .translations
+                .iter()
+                .any(|(lang, _)| lang.split('.').next() == Some(default_lang.as_str()))
+            {
+                self.report(TwineError::MissingDefault {
+                    key: key.to_owned(),
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // list the deprecated keys still present, they are not errors even in strict mode
+    fn check_deprecated(&mut self, map: &TwineData) -> Result<(), TwineError> {
+        let mut sorted: Vec<_> = map.iter().collect();
+        sorted.sort_unstable_by_key(|(key, _)| *key);
+
+        for (key, section) in sorted {
+            if let Some(replacement) = section.deprecated.as_ref() {
+                writeln!(
+                    self.cargo_output,
+                    "cargo:warning=key {:?} is deprecated, use {:?} instead",
+                    key, replacement,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // list the translations that need to be reviewed again, they are errors in strict mode only
+    // when `fail_on_fuzzy` is enabled
+    fn check_fuzzy(&mut self, map: &TwineData) -> Result<(), TwineError> {
+        let mut sorted: Vec<_> = map.iter().collect();
+        sorted.sort_unstable_by_key(|(key, _)| *key);
+
+        for (key, section) in sorted {
+            for lang in section.fuzzy.iter() {
+                let err = TwineError::FuzzyTranslation {
+                    key: key.to_owned(),
+                    lang: lang.to_owned(),
+                };
+                if self.fail_on_fuzzy {
+                    self.report(err)?;
+                } else {
+                    writeln!(self.cargo_output, "cargo:warning={}", err)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // list the values shared by several languages of a key (possible copy-paste), they are not
+    // errors even in strict mode
+    fn check_identical_values(&mut self, map: &TwineData) -> Result<(), TwineError> {
+        let mut sorted: Vec<_> = map.iter().collect();
+        sorted.sort_unstable_by_key(|(key, _)| *key);
+        let is_trivial = |text: &str| {
+            printf::fragments(text)
+                .filter_map(|x| match x {
+                    Fragment::Text(text) => {
+                        Some(text.chars().filter(|c| c.is_alphabetic()).count())
+                    }
+                    _ => None,
+                })
+                .sum::<usize>()
+                < 4
+        };
+
+        for (key, section) in sorted {
+            let translations = &section.translations;
+            for (i, (lang, text)) in translations.iter().enumerate() {
+                let category = parse_lang(lang).2;
+                let same =
+                    |(x, other): &&(String, String)| other == text && parse_lang(x).2 == category;
+                // the value is reported with its first language
+                if is_trivial(text) || translations[..i].iter().any(|x| same(&x)) {
+                    continue;
+                }
+                let mut langs: Vec<&str> = Vec::new();
+                for (x, _) in translations[i..].iter().filter(same) {
+                    if !langs
+                        .iter()
+                        .any(|lang| parse_lang(lang).0 == parse_lang(x).0)
+                    {
+                        langs.push(x);
+                    }
+                }
+                if langs.len() > 1 {
+                    writeln!(
+                        self.cargo_output,
+                        "cargo:warning=key {:?} has the same translation in {:?}: {:?}",
+                        key, langs, text,
+                    )?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // list the translations whose whitespace at the start or at the end differs from the first
+    // translation of their key, they are not errors even in strict mode
+    fn check_whitespace_values(&mut self, map: &TwineData) -> Result<(), TwineError> {
+        let mut sorted: Vec<_> = map.iter().collect();
+        sorted.sort_unstable_by_key(|(key, _)| *key);
+
+        for (key, section) in sorted {
+            let (first_lang, first_text) = match section.translations.first() {
+                Some(first) => first,
+                None => continue,
+            };
+            let expected = edge_whitespace(first_text);
+            let langs: Vec<&str> = section.translations[1..]
+                .iter()
+                .filter(|(_, text)| edge_whitespace(text) != expected)
+                .map(|(lang, _)| lang.as_str())
+                .collect();
+            if !langs.is_empty() {
+                writeln!(
+                    self.cargo_output,
+                    "cargo:warning=key {:?} has whitespace at the start or the end that differs \
+                    from {:?} in {:?}",
+                    key, first_lang, langs,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // fail in strict mode, emit a warning otherwise
+    fn report(&mut self, err: TwineError) -> Result<(), TwineError> {
+        if self.strict {
+            return Err(err);
+        }
+        writeln!(self.cargo_output, "cargo:warning={}", err)?;
+
+        Ok(())
+    }
+}
+
+fn read_twine_ini<R: Read>(reader: &mut R, lang: Option<&str>) -> io::Result<TwineData> {
+    use std::io::BufRead;
+
+    let mut map: TwineData = HashMap::new();
+    let mut section: Option<&mut Section> = None;
+    // the section `[__languages__]` contains language codes instead of translations
+    let mut in_languages = false;
+    // the sections `[__defaults__]` and `[__schema__]` contain keys instead of languages
+    let mut in_defaults = false;
+    // comments are attached to the section or translation that follows them
+    let mut comments = Vec::new();
+
+    let reader = io::BufReader::new(reader);
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        if let Some(caps) = RE_SECTION.captures(line.as_str()) {
+            let position = map.len();
+            in_languages = &caps[1] == LANGUAGES_SECTION;
+            in_defaults = &caps[1] == DEFAULTS_SECTION || &caps[1] == SCHEMA_SECTION;
+            let new_section = map
+                .entry(caps[1].replace(r"\]", "]"))
+                .or_insert_with(|| Section {
+                    position,
+                    ..Section::default()
+                });
+            new_section.comments.append(&mut comments);
+            section = Some(new_section);
+            continue;
+        }
+
+        let trimmed = line.trim();
+        if trimmed.starts_with(';') || trimmed.starts_with('#') {
+            comments.push(trimmed.to_owned());
+            continue;
+        }
+
+        if in_languages {
+            if let Some(section) = section.as_mut() {
+                for lang in trimmed.split(|c: char| c == ',' || c.is_whitespace()) {
+                    if !lang.is_empty() {
+                        check_lang(lang, i + 1)?;
+                        section.translations.push((lang.to_owned(), String::new()));
+                    }
+                }
+            }
+            continue;
+        }
+
+        // metadata of the section, the keys of `[__defaults__]` and `[__schema__]` and the lines of
+        // a file of a single language are never metadata
+        let metadata = if in_defaults || lang.is_some() {
+            None
+        } else {
+            RE_KEY_VALUE.captures(line.as_str())
+        };
+        if let Some(caps) = metadata {
+            let value = caps.get(2).unwrap().as_str();
+            if caps.get(1).unwrap().as_str() == "maxlen" {
+                let maxlen = value.parse().map_err(|_| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("invalid maxlen at line {}: {}", i + 1, value),
+                    )
+                })?;
+                if let Some(section) = section.as_mut() {
+                    section.maxlen = Some(maxlen);
+                } else {
+                    return Err(outside_section(i + 1));
+                }
+                continue;
+            }
+            if caps.get(1).unwrap().as_str() == "order" {
+                let order = value.parse().map_err(|_| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("invalid order at line {}: {}", i + 1, value),
+                    )
+                })?;
+                if let Some(section) = section.as_mut() {
+                    section.order = Some(order);
+                } else {
+                    return Err(outside_section(i + 1));
+                }
+                continue;
+            }
+            if caps.get(1).unwrap().as_str() == "deprecated" {
+                if let Some(section) = section.as_mut() {
+                    section.deprecated = Some(value.to_owned());
+                } else {
+                    return Err(outside_section(i + 1));
+                }
+                continue;
+            }
+            if caps.get(1).unwrap().as_str() == "context" {
+                if let Some(section) = section.as_mut() {
+                    section.context = Some(value.to_owned());
+                } else {
+                    return Err(outside_section(i + 1));
+                }
+                continue;
+            }
+            if caps.get(1).unwrap().as_str() == "format" {
+                let markdown = match value {
+                    "markdown" => true,
+                    "plain" => false,
+                    _ => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("invalid format at line {}: {}", i + 1, value),
+                        ))
+                    }
+                };
+                if let Some(section) = section.as_mut() {
+                    section.markdown = markdown;
+                } else {
+                    return Err(outside_section(i + 1));
+                }
+                continue;
+            }
+            if caps.get(1).unwrap().as_str() == "tm_id" {
+                if let Some(section) = section.as_mut() {
+                    section.tm_id = Some(value.to_owned());
+                } else {
+                    return Err(outside_section(i + 1));
+                }
+                continue;
+            }
+            if caps.get(1).unwrap().as_str() == "fuzzy" {
+                if let Some(section) = section.as_mut() {
+                    for lang in value.split(|c: char| c == ',' || c.is_whitespace()) {
+                        if !lang.is_empty() {
+                            check_lang(lang, i + 1)?;
+                            section.fuzzy.extend(expand_regions(lang));
+                        }
+                    }
+                } else {
+                    return Err(outside_section(i + 1));
+                }
+                continue;
+            }
+        }
+
+        let translation = if let Some(lang) = lang {
+            // the whole line is the value when the language is given for the whole file
+            if trimmed.is_empty() {
+                continue;
+            }
+            (lang.to_owned(), trimmed.to_owned())
+        } else if let Some(caps) = RE_KEY_VALUE.captures(line.as_str()) {
+            (
+                caps.get(1).unwrap().as_str().to_owned(),
+                caps.get(2).unwrap().as_str().to_owned(),
+            )
+        } else {
+            continue;
+        };
+
+        if let Some(section) = section.as_mut() {
+            let (lang, text) = translation;
+            if let Some(placeholder) = position_error(&text) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "invalid placeholder position at line {}: {}",
+                        i + 1,
+                        placeholder
+                    ),
+                ));
+            }
+            let langs = if in_defaults {
+                vec![lang]
+            } else {
+                expand_regions(&lang)
+            };
+            for lang in langs {
+                if !in_defaults {
+                    check_lang(&lang, i + 1)?;
+                }
+                if !comments.is_empty() {
+                    section
+                        .translation_comments
+                        .entry(lang.clone())
+                        .or_default()
+                        .append(&mut comments);
+                }
+                section.translations.push((lang, text.clone()));
+            }
+        } else {
+            return Err(outside_section(i + 1));
+        }
+    }
+
+    if let Some(section) = section {
+        section.trailing_comments = comments;
+    }
+
+    Ok(map)
+}
+
+// the language of a translation must be a code (`en`, `en-gb`) followed by an optional plural
+// category (`en.one`)
+fn check_lang(lang: &str, line: usize) -> io::Result<()> {
+    match lang_error(lang) {
+        Some(error) => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{} at line {}: {}", error, line, lang),
+        )),
+        None => Ok(()),
+    }
+}
+
+fn lang_error(lang: &str) -> Option<&'static str> {
+    match RE_LANG.captures(lang) {
+        Some(caps) => match caps.get(5) {
+            Some(category) if !plural::is_category(category.as_str()) => {
+                Some("invalid plural category")
+            }
+            _ => None,
+        },
+        None => Some("invalid language"),
+    }
+}
+
+// the first placeholder whose explicit position is higher than `MAX_POSITION`
+fn position_error(text: &str) -> Option<&str> {
+    printf::fragments(text).find_map(|fragment| match fragment {
+        Fragment::Placeholder(source, placeholder) => placeholder
+            .parameter
+            .filter(|x| x.parse::<usize>().map_or(true, |x| x > MAX_POSITION))
+            .map(|_| source),
+        _ => None,
+    })
+}
+
+fn outside_section(line: usize) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("key-value outside section at line {}", line),
+    )
+}
+
+// a value with its escape sequences decoded like the literals of `t!()` (`\n`, `\u00e9`, `\"`),
+// the placeholders are kept: the raw translations of `t_raw!()`, `preview()` and the FFI
+fn decode_escapes(text: &str) -> String {
+    printf::fragments(text)
+        .map(|x| match x {
+            Fragment::Escape(source) => match &source[1..] {
+                "n" => "\n".to_owned(),
+                "r" => "\r".to_owned(),
+                "t" => "\t".to_owned(),
+                c => c.to_owned(),
+            },
+            Fragment::Unicode(_, hex) => u32::from_str_radix(hex, 16)
+                .ok()
+                .and_then(char::from_u32)
+                .map_or_else(String::new, String::from),
+            x => x.as_str().to_owned(),
+        })
+        .collect()
+}
+
+// the whitespace at the start and at the end of a value once its escape sequences are decoded
+fn edge_whitespace(text: &str) -> (String, String) {
+    let decoded = decode_escapes(text);
+    let start = decoded.len() - decoded.trim_start().len();
+    let end = decoded.trim_end().len();
+    (decoded[..start].to_owned(), decoded[end..].to_owned())
+}
+
+// expand a list of regions sharing a translation: `en-gb,au.one` is `en-gb.one` and `en-au.one`
+fn expand_regions(lang: &str) -> Vec<String> {
+    let (lang, category) = match lang.find('.') {
+        Some(i) => lang.split_at(i),
+        None => (lang, ""),
+    };
+    match lang.split_once('-') {
+        Some((language, regions)) if regions.contains(',') => regions
+            .split(',')
+            .map(|region| format!("{}-{}{}", language, region, category))
+            .collect(),
+        _ => vec![format!("{}{}", lang, category)],
+    }
+}
+
+// add the language `pseudo` to every key, derived from the translations of its first language
+fn add_pseudolocale(map: &mut TwineData) {
+    for section in map.values_mut() {
+        let (default_lang, default_text) = match section.translations.first() {
+            Some((lang, text)) => (parse_lang(lang).0, text),
+            None => continue,
+        };
+        let mut pseudo = section
+            .translations
+            .iter()
+            .filter_map(|(lang, text)| match parse_lang(lang) {
+                (lang, None, category) if lang == default_lang => Some((
+                    match category {
+                        Some(category) => format!("pseudo.{}", category),
+                        None => "pseudo".to_string(),
+                    },
+                    pseudo::pseudolocalize(text),
+                )),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        // the key might only have localized translations for this language
+        if pseudo.is_empty() {
+            pseudo.push(("pseudo".to_string(), pseudo::pseudolocalize(default_text)));
+        }
+        section.translations.extend(pseudo);
+    }
+}
+
+// escape the HTML special characters of the text of a value, the placeholders are untouched
+fn html_escape(text: &str) -> String {
+    let entity = |c: char| match c {
+        '<' => Some("&lt;"),
+        '>' => Some("&gt;"),
+        '&' => Some("&amp;"),
+        _ => None,
+    };
+    let mut out = String::with_capacity(text.len());
+    for fragment in printf::fragments(text) {
+        match fragment {
+            Fragment::Text(text) => {
+                for c in text.chars() {
+                    match entity(c) {
+                        Some(entity) => out.push_str(entity),
+                        None => out.push(c),
+                    }
+                }
+            }
+            Fragment::Unicode(source, unicode) => {
+                match u32::from_str_radix(unicode, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .and_then(entity)
+                {
+                    Some(entity) => out.push_str(entity),
+                    None => out.push_str(source),
+                }
+            }
+            fragment => out.push_str(fragment.as_str()),
+        }
+    }
+    out
+}
+
+// the placeholders of a value, sorted, with the position of the printf's placeholders made
+// explicit (`%1$s`, `%2$d`, `{name}`) to compare the translations of a key
+fn placeholders(text: &str) -> Vec<String> {
+    let mut placeholders: Vec<_> = parse_placeholders(text)
+        .into_iter()
+        .map(|(position, kind)| {
+            let type_ = match kind {
+                PlaceholderKind::Int => "d",
+                PlaceholderKind::Float => "f",
+                PlaceholderKind::Str => "s",
+                PlaceholderKind::Hex | PlaceholderKind::UpperHex => "x",
+            };
+            format!("%{}${}", position, type_)
+        })
+        .collect();
+    for fragment in printf::fragments(text) {
+        if let Fragment::Text(text) = fragment {
+            placeholders.extend(
+                RE_NAMED_ARGUMENT
+                    .find_iter(text)
+                    .map(|x| x.as_str().to_string()),
+            );
+        }
+    }
+    placeholders.sort_unstable();
+    placeholders.dedup();
+
+    placeholders
+}
+
+// the position (starting at 1) and the kind of the printf's format placeholders of a value, in
+// the order they appear
+fn parse_placeholders(text: &str) -> Vec<(usize, PlaceholderKind)> {
+    let mut implicit = 0;
+    let mut placeholders = Vec::new();
+    for fragment in printf::fragments(text) {
+        if let Fragment::Placeholder(_, placeholder) = fragment {
+            let position = match placeholder.parameter {
+                Some(parameter) => parameter.parse().unwrap_or(0),
+                None => {
+                    implicit += 1;
+                    implicit
+                }
+            };
+            let kind = match placeholder.type_ {
+                "d" | "i" => PlaceholderKind::Int,
+                "f" => PlaceholderKind::Float,
+                "x" => PlaceholderKind::Hex,
+                "X" => PlaceholderKind::UpperHex,
+                _ => PlaceholderKind::Str,
+            };
+            placeholders.push((position, kind));
+        }
+    }
+
+    placeholders
+}
+
+// count the arguments used by the printf's format placeholders of a value
+fn count_arguments(text: &str) -> usize {
+    parse_placeholders(text)
+        .into_iter()
+        .map(|(position, _)| position)
+        .max()
+        .unwrap_or(0)
+}
+
+// transform all printf's format placeholders of a value to Rust's format
+//
+// In the values of plural keys, the count is the first argument and `#` is a shorthand for it.
+// When the count is not used by the value it is not part of the arguments, the explicit positions
+// of the other arguments are then shifted accordingly. The boolean returned indicates if the count
+// is used.
+fn convert_printf(text: &str, plural: bool) -> (String, bool) {
+    convert_printf_with(text, plural, false, false, false)
+}
+
+// same as `convert_printf()`, the numbers of `%d` and `%f` (or the count `#`) use the trait
+// `LowerExp` of `LocaleNumber` when `locale_numbers` (or `locale_count`) is enabled and the braces
+// of the text are escaped when `escape_braces` is enabled (the named arguments are disabled)
+#[allow(clippy::single_char_add_str)]
+fn convert_printf_with(
+    text: &str,
+    plural: bool,
+    locale_numbers: bool,
+    locale_count: bool,
+    escape_braces: bool,
+) -> (String, bool) {
+    let count = if locale_count { "{0:e}" } else { "{0}" };
+    // the positions are checked when the translations are read
+    let parse_parameter = |parameter: &str| -> usize { parameter.parse().unwrap_or(0) };
+    let uses_count = plural
+        && printf::fragments(text).any(|fragment| match fragment {
+            Fragment::Placeholder(_, placeholder) => placeholder
+                .parameter
+                .map(|x| parse_parameter(x) == 1)
+                .unwrap_or(true),
+            Fragment::Text(text) => text.contains('#'),
+            _ => false,
+        });
+    let shift = if plural && !uses_count { 2 } else { 1 };
+
+    let mut out = String::new();
+    for fragment in printf::fragments(text) {
+        match fragment {
+            Fragment::Placeholder(_, placeholder) => {
+                out.push_str("{");
+                if let Some(parameter) = placeholder.parameter {
+                    let parameter = parse_parameter(parameter);
+                    write!(out, "{}", parameter.saturating_sub(shift)).unwrap();
+                }
+                out.push_str(":");
+                if let Some(flag) = placeholder.flags {
+                    out.push_str(flag);
+                }
+                if let Some(width) = placeholder.width {
+                    out.push_str(width);
+                }
+                if let Some(precision) = placeholder.precision {
+                    out.push_str(precision);
+                }
+                match placeholder.type_ {
+                    x @ "x" | x @ "X" => out.push_str(x),
+                    "d" | "i" | "u" | "f" | "F" if locale_numbers => out.push_str("e"),
+                    _ => {}
+                }
+                out.push_str("}");
+            }
+            Fragment::PercentPercent => out.push_str("%"),
+            Fragment::Quote => out.push_str("\\\""),
+            Fragment::Unicode(_, unicode) => {
+                out.push_str(r"\u{");
+                out.push_str(unicode);
+                out.push_str(r"}");
+            }
+            fragment => {
+                let text = if escape_braces {
+                    fragment.as_str().replace('{', "{{").replace('}', "}}")
+                } else {
+                    fragment.as_str().to_owned()
+                };
+                if plural {
+                    out.push_str(&text.replace('#', count));
+                } else {
+                    out.push_str(&text);
+                }
+            }
+        }
+    }
+    // the placeholder of the language is not a named argument
+    if escape_braces {
+        out = out.replace("%{{lang}}", LANG_PLACEHOLDER);
+    }
+
+    (out, uses_count)
+}
+
+// the first invalid placeholder in the keys sorted alphabetically
+fn check_placeholders(map: &TwineData) -> Result<(), TwineError> {
+    let mut sorted: Vec<_> = map.iter().collect();
+    sorted.sort_unstable_by_key(|(key, _)| *key);
+
+    for (key, section) in sorted {
+        for (lang, text) in section.translations.iter() {
+            if let Some(placeholder) = printf::invalid_placeholders(text).into_iter().next() {
+                return Err(TwineError::InvalidPlaceholder {
+                    key: key.to_owned(),
+                    lang: lang.to_owned(),
+                    placeholder,
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// the keys are used as identifiers: they must start with a letter once normalized, the Unicode
+// letters are kept (`café_button`). Only letters, digits and `_` are left: nothing can be mistaken
+// for the tokens of the macros (`=>`, `,`, `;`). Two keys must not give the same identifier in
+// snake case (macros, functions) nor in camel case (`Key`): `a_1` and `a1` are both `Key::A1`.
+fn check_keys(map: &TwineData) -> Result<(), TwineError> {
+    let mut sorted: Vec<_> = map.keys().collect();
+    sorted.sort_unstable();
+
+    // the identifiers in snake case and in camel case are checked separately
+    let mut identifiers: [HashMap<String, &String>; 2] = Default::default();
+    for key in sorted {
+        let normalized = TwineFormatter::normalize_key(key);
+        let mut chars = normalized.chars();
+        let valid = chars.next().map_or(false, char::is_alphabetic)
+            && chars.all(|c| c.is_alphabetic() || c.is_ascii_digit() || c == '_')
+            && !RESERVED_KEYS.contains(&normalized.as_str());
+        if !valid {
+            return Err(TwineError::InvalidKey {
+                key: key.to_owned(),
+                normalized,
+            });
+        }
+
+        let camel = case::to_upper_camel_case(&normalized);
+        for (identifiers, identifier) in identifiers.iter_mut().zip([normalized, camel]) {
+            if let Some(other) = identifiers.get(&identifier) {
+                return Err(TwineError::KeyCollision {
+                    key: (*other).to_owned(),
+                    other: key.to_owned(),
+                    normalized: identifier,
+                });
+            }
+            identifiers.insert(identifier, key);
+        }
+    }
+
+    Ok(())
+}
+
+// parse the language, the region and the plural category of a translation (`en-gb.one`)
+fn parse_lang(lang: &str) -> (String, Option<String>, Option<String>) {
+    let caps = RE_LANG.captures(lang).expect("lang can be parsed");
+    let lang = caps
+        .get(1)
+        .expect("the language is always there")
+        .as_str()
+        .to_upper_camel_case();
+    let region = caps.get(3).map(|x| x.as_str().to_string());
+    let category = caps.get(5).map(|x| x.as_str().to_string());
+    if let Some(category) = category.as_deref() {
+        assert!(
+            plural::is_category(category),
+            "invalid plural category: {}",
+            category,
+        );
+    }
+
+    (lang, region, category)
+}
+
+// writes the generated code into a file, the code is generated with 4 spaces per level which are
+// replaced by the indentation of the `Builder`
+struct IndentWriter<'a, W: Write> {
+    inner: W,
+    indentation: &'a str,
+    // the current line, until its end is written
+    line: String,
+    // the error of the file, `fmt::Error` does not carry it
+    error: Option<io::Error>,
+}
+
+impl<W: Write> IndentWriter<'_, W> {
+    fn write_line(&mut self) -> io::Result<()> {
+        let line = self.line.strip_suffix('\r').unwrap_or(&self.line);
+        let level = (line.len() - line.trim_start_matches(' ').len()) / 4;
+        for _ in 0..level {
+            self.inner.write_all(self.indentation.as_bytes())?;
+        }
+        writeln!(self.inner, "{}", &line[level * 4..])?;
+        self.line.clear();
+        Ok(())
+    }
+
+    fn finish(mut self) -> io::Result<()> {
+        if !self.line.is_empty() {
+            self.write_line()?;
+        }
+        self.inner.flush()
+    }
+}
+
+impl<W: Write> fmt::Write for IndentWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut lines = s.split('\n');
+        // the first part continues the current line, the other parts start a new line
+        self.line.push_str(lines.next().unwrap_or_default());
+        for part in lines {
+            if let Err(err) = self.write_line() {
+                self.error = Some(err);
+                return Err(fmt::Error);
+            }
+            self.line.push_str(part);
+        }
+        Ok(())
+    }
+}
+
+// replace the references `$t(key)` by the translation of the key in the same language
+fn resolve_references(map: &mut TwineData) -> Result<(), TwineError> {
+    let mut resolved = Vec::new();
+    let mut sorted: Vec<_> = map.iter().collect();
+    sorted.sort_unstable_by_key(|(key, _)| *key);
+    for (key, section) in sorted {
+        for (i, (lang, text)) in section.translations.iter().enumerate() {
+            if RE_REFERENCE.is_match(text) {
+                let mut stack = vec![key.as_str()];
+                let text = resolve_reference(map, text, lang, &mut stack)?;
+                resolved.push((key.clone(), i, text));
+            }
+        }
+    }
+
+    for (key, i, text) in resolved {
+        map.get_mut(&key).unwrap().translations[i].1 = text;
+    }
+
+    Ok(())
+}
+
+// `stack` contains the keys being resolved, the last one being the key of `text`
+fn resolve_reference<'a>(
+    map: &'a TwineData,
+    text: &str,
+    lang: &str,
+    stack: &mut Vec<&'a str>,
+) -> Result<String, TwineError> {
+    let mut out = String::new();
+    let mut last = 0;
+    for caps in RE_REFERENCE.captures_iter(text) {
+        let whole = caps.get(0).unwrap();
+        let (reference, section) = match map.get_key_value(&caps[1]) {
+            Some((reference, section)) => (reference.as_str(), section),
+            None => {
+                return Err(TwineError::UnresolvedReference {
+                    key: stack.last().unwrap().to_string(),
+                    lang: lang.to_owned(),
+                    reference: caps[1].to_owned(),
+                })
+            }
+        };
+        if stack.contains(&reference) {
+            let mut keys: Vec<_> = stack.iter().map(ToString::to_string).collect();
+            keys.push(reference.to_owned());
+            return Err(TwineError::ReferenceCycle {
+                lang: lang.to_owned(),
+                keys,
+            });
+        }
+
+        // the same language, then without plural category, then without region
+        let get = |lang: &str| {
+            section
+                .translations
+                .iter()
+                .find(|(x, _)| x == lang)
+                .map(|(_, text)| text)
+        };
+        let without_category = lang.split('.').next().unwrap_or(lang);
+        let without_region = without_category.split('-').next().unwrap_or(lang);
+        let referenced = match get(lang)
+            .or_else(|| get(without_category))
+            .or_else(|| get(without_region))
+        {
+            Some(referenced) => referenced,
+            None => {
+                return Err(TwineError::UnresolvedReference {
+                    key: stack.last().unwrap().to_string(),
+                    lang: lang.to_owned(),
+                    reference: reference.to_owned(),
+                })
+            }
+        };
+
+        out.push_str(&text[last..whole.start()]);
+        stack.push(reference);
+        out.push_str(&resolve_reference(map, referenced, lang, stack)?);
+        stack.pop();
+        last = whole.end();
+    }
+    out.push_str(&text[last..]);
+
+    Ok(out)
+}
+
+// a plural key has at least one translation with a plural category
+// match a key against a pattern of the section `[__defaults__]` where `*` is any sequence of
+// characters: `band_*`
+fn matches_pattern(pattern: &str, key: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let mut rest = match key.strip_prefix(first) {
+        Some(rest) => rest,
+        None => return false,
+    };
+    let mut parts: Vec<_> = parts.collect();
+    let last = match parts.pop() {
+        Some(last) => last,
+        // no `*`: the pattern is the key itself
+        None => return rest.is_empty(),
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+fn is_plural(translations: &[(String, String)]) -> bool {
+    translations
+        .iter()
+        .any(|(lang, _)| parse_lang(lang.as_str()).2.is_some())
+}
+
+struct TwineFormatter<'a> {
+    map: TwineData,
+    lang_code: bool,
+    named_args: bool,
+    typed_functions: bool,
+    // the name of the crate used by the examples of the keys
+    doctests: Option<String>,
+    no_std: bool,
+    uppercase_regions: bool,
+    ignore_regions: bool,
+    fallback_order: &'a [String],
+    default_lang: Option<&'a str>,
+    local_macros: Option<&'a str>,
+    // the prefix of the `extern "C"` functions
+    ffi: Option<&'a str>,
+    const_table: bool,
+    #[cfg(feature = "locale-numbers")]
+    locale_numbers: bool,
+    #[cfg(feature = "locale-numbers")]
+    locale_count: bool,
+    #[cfg(feature = "serde")]
+    serde: bool,
+    output: Output,
+}
+
+// the macros generated for the keys that are not plural
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TranslateMacro {
+    // `t!()`: returns a `String`
+    Format,
+    // `t_into!()`: writes into a `std::fmt::Write`
+    Write,
+}
+
+impl TranslateMacro {
+    fn name(self) -> &'static str {
+        match self {
+            TranslateMacro::Format => "t",
+            TranslateMacro::Write => "t_into",
+        }
+    }
+
+    // the parameters before the key
+    fn buffer(self) -> &'static str {
+        match self {
+            TranslateMacro::Format => "",
+            TranslateMacro::Write => "$buf:expr, ",
+        }
+    }
+
+    // the parameters before the key passed to another call of the macro
+    fn forward_buffer(self) -> &'static str {
+        match self {
+            TranslateMacro::Format => "",
+            TranslateMacro::Write => "$buf, ",
+        }
+    }
+
+    // the call made by every match arm, the format string and the arguments are appended
+    fn call(self, no_std: bool) -> &'static str {
+        match self {
+            TranslateMacro::Format if no_std => "::alloc::format!(",
+            TranslateMacro::Format => "format!(",
+            TranslateMacro::Write => "write!($buf, ",
+        }
+    }
+}
+
+// the part of the generated code written by `TwineFormatter`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Output {
+    // everything in a single file
+    All,
+    // the `Lang` enum shared by the modules
+    Shared,
+    // the macros and the `Key` enum of a module
+    Module,
+}
+
+impl fmt::Display for TwineFormatter<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut f = CodeFormatter::new(f, "    ");
+        let all_languages: HashSet<_> = self
+            .map
+            .values()
+            .flat_map(|section| section.translations.iter())
+            .map(|(lang, _)| {
+                let (lang, region, _) = parse_lang(lang.as_str());
+                (lang, region)
+            })
+            .collect();
+
+        if self.output != Output::Module {
+            write!(
+                f,
+                r#"
+                #[allow(dead_code)]
+                pub const TWINE_GEN_VERSION: &str = {:?};
+                "#,
+                env!("CARGO_PKG_VERSION"),
+            )?;
+        }
+
+        if self.output != Output::Shared {
+            self.generate_macros(&mut f)?;
+        }
+
+        if self.output == Output::Module {
+            if self.typed_functions {
+                self.generate_typed_functions(&mut f)?;
+            }
+            return self.generate_keys(&mut f);
+        }
+
+        // generate the `Lang` enum and its variants
+        write!(
+            f,
+            r#"
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            #[allow(dead_code)]
+            pub enum Lang {{
+            "#,
+        )?;
+        f.indent(1);
+
+        let lang_variants: HashSet<_> = all_languages
+            .iter()
+            .map(|(lang, _)| lang.as_str())
+            .collect();
+        let mut lang_variants: Vec<_> = lang_variants.into_iter().collect();
+        lang_variants.sort_unstable();
+
+        let field = if self.ignore_regions {
+            ""
+        } else {
+            "(&'static str)"
+        };
+        for lang in lang_variants.iter() {
+            write!(
+                f,
+                r#"
+                {lang}{field},
+                "#,
+            )?;
+        }
+
+        f.dedent(1);
+        write!(
+            f,
+            r#"
+            }}
+
+            #[allow(dead_code)]
+            impl Lang {{
+                pub fn all_languages() -> &'static [&'static Lang] {{
+                    &[
+            "#,
+        )?;
+        f.indent(3);
+
+        let mut sorted_languages: Vec<_> = all_languages.iter().collect();
+        sorted_languages.sort_unstable();
+
+        for (lang, region) in &sorted_languages {
+            write!(
+                f,
+                r#"
+                &{},
+                "#,
+                self.lang_value(lang, region.as_deref()),
+            )?;
+        }
+
+        f.dedent(3);
+        write!(
+            f,
+            r#"
+                    ]
+                }}
+
+                /// The codes of all the languages as displayed: `["en", "en_gb", "fr"]`.
+                pub fn all_codes() -> {vec}<{string}> {{
+                    Self::all_languages().iter().map(|x| x.code()).collect()
+                }}
+
+                /// The languages translated in every key, a region being translated by its
+                /// language: `[Lang::En(""), Lang::En("gb")]` if `fr` misses a translation.
+                pub fn complete_languages() -> {vec}<Lang> {{
+                    const COMPLETE_LANGUAGES: &[Lang] = &[{complete}];
+                    COMPLETE_LANGUAGES.to_vec()
+                }}
+            }}
+            "#,
+            vec = if self.no_std {
+                "::alloc::vec::Vec"
+            } else {
+                "Vec"
+            },
+            string = self.string_type(),
+            complete = sorted_languages
+                .iter()
+                .filter(|(lang, region)| self.is_complete(lang, region.as_deref()))
+                .map(|(lang, region)| self.lang_value(lang, region.as_deref()))
+                .collect::<Vec<_>>()
+                .join(", "),
+        )?;
+
+        f.dedent(3);
+        write!(
+            f,
+            r#"
+
+            impl {core}::fmt::Display for Lang {{
+                fn fmt(&self, f: &mut {core}::fmt::Formatter) -> {core}::fmt::Result {{
+                    write!(f, "{{}}", self.language())?;
+                    let region = self.region();
+                    if !region.is_empty() {{
+                        {write_region}
+                    }}
+                    Ok(())
+                }}
+            }}
+            "#,
+            core = self.core(),
+            write_region = if self.uppercase_regions {
+                "f.write_str(\"_\")?;\n                        \
+                for c in region.chars() {\n                            \
+                    write!(f, \"{}\", c.to_ascii_uppercase())?;\n                        \
+                }"
+            } else {
+                "write!(f, \"_{}\", region)?;"
+            },
+        )?;
+
+        write!(
+            f,
+            r#"
+
+            #[allow(dead_code)]
+            impl Lang {{
+                pub fn language(&self) -> &'static str {{
+                    match self {{
+            "#,
+        )?;
+
+        f.indent(3);
+        for lang in &lang_variants {
+            write!(
+                f,
+                r#"
+                {} => {:?},
+                "#,
+                self.lang_pattern(lang, None),
+                lang.to_snake_case(),
+            )?;
+        }
+
+        f.dedent(3);
+        write!(
+            f,
+            r#"
+                    }}
+                }}
+
+                /// The code of the language and its region, if any, as displayed: `en_gb`.
+                pub fn code(&self) -> {string} {{
+                    {to_string}
+                }}
+
+                /// The code of the language without its region: `en`.
+                pub fn code_static(&self) -> &'static str {{
+                    self.language()
+                }}
+
+                pub fn region(&self) -> &str {{
+                    match self {{
+            "#,
+            string = self.string_type(),
+            to_string = if self.no_std {
+                "::alloc::string::ToString::to_string(self)"
+            } else {
+                "self.to_string()"
+            },
+        )?;
+
+        f.indent(3);
+        for lang in &lang_variants {
+            if self.ignore_regions {
+                write!(
+                    f,
+                    r#"
+                    Lang::{lang} => "",
+                    "#,
+                )?;
+            } else {
+                write!(
+                    f,
+                    r#"
+                    Lang::{lang}(region) => region,
+                    "#,
+                )?;
+            }
+        }
+
+        f.dedent(3);
+        write!(
+            f,
+            r#"
+                    }}
+                }}
+
+                /// The language without its region: `Lang::En("gb")` gives `Lang::En("")`.
+                pub fn base(&self) -> Lang {{
+                    match self {{
+            "#,
+        )?;
+
+        f.indent(3);
+        for lang in &lang_variants {
+            write!(
+                f,
+                r#"
+                {} => {},
+                "#,
+                self.lang_pattern(lang, None),
+                self.lang_value(lang, None),
+            )?;
+        }
+
+        f.dedent(3);
+        write!(
+            f,
+            r#"
+                    }}
+                }}
+
+                /// Returns `true` if both are the same language, whatever their regions are.
+                pub fn same_language(&self, other: &Lang) -> bool {{
+                    self.language() == other.language()
+                }}
+
+                /// The key sorting the languages by their position in `priority` then by code:
+                /// `langs.sort_by_key(|x| x.sort_key(&["en", "fr"]))`. The codes of `priority` are
+                /// compared like `code()`, a language without region matches all its regions.
+                pub fn sort_key(&self, priority: &[&str]) -> (usize, {string}) {{
+                    let code = self.code();
+                    let position = priority
+                        .iter()
+                        .position(|x| x.eq_ignore_ascii_case(&code))
+                        .or_else(|| priority.iter().position(|x| *x == self.language()))
+                        .unwrap_or(priority.len());
+                    (position, code)
+                }}
+
+                /// The closest language of `all_languages()` to a tag (`en_us`, `en-US` or `en`):
+                /// the same language and region, then the language without region, then its first
+                /// region. Returns `None` if the language is not translated.
+                pub fn nearest(tag: &str) -> Option<Lang> {{
+                    let (language, region) = match tag.find(&['_', '-'][..]) {{
+                        Some(i) => (&tag[..i], &tag[i + 1..]),
+                        None => (tag, ""),
+                    }};
+                    let candidates = Self::all_languages()
+                        .iter()
+                        .filter(|x| x.language().eq_ignore_ascii_case(language));
+                    candidates
+                        .clone()
+                        .find(|x| x.region().eq_ignore_ascii_case(region))
+                        .or_else(|| candidates.clone().find(|x| x.region().is_empty()))
+                        .or_else(|| candidates.clone().next())
+                        .map(|x| **x)
+                }}
+
+                /// Returns `true` if the language and its region are in `all_languages()`. This is
+                /// a `const fn` used by `const_lang!()` to check the languages at compile time.
+                pub const fn is_known(&self) -> bool {{
+                    match self {{
+            "#,
+            string = self.string_type(),
+        )?;
+
+        f.indent(3);
+        for lang in &lang_variants {
+            if self.ignore_regions {
+                write!(
+                    f,
+                    r#"
+                    Lang::{lang} => true,
+                    "#,
+                )?;
+                continue;
+            }
+            let regions: Vec<_> = sorted_languages
+                .iter()
+                .filter(|(x, _)| x.as_str() == *lang)
+                .map(|(_, region)| format!("b{:?}", region.as_deref().unwrap_or("")))
+                .collect();
+            write!(
+                f,
+                r#"
+                Lang::{}(region) => matches!(region.as_bytes(), {}),
+                "#,
+                lang,
+                regions.join(" | "),
+            )?;
+        }
+
+        f.dedent(3);
+        write!(
+            f,
+            r#"
+                    }}
+                }}
+            "#,
+        )?;
+
+        // the environment variables are only available with `std`
+        if !self.no_std {
+            self.generate_from_locale_env(&mut f, &lang_variants)?;
+        }
+
+        write!(
+            f,
+            r#"
+            }}
+            "#,
+        )?;
+
+        let mut all_regions: Vec<_> = all_languages
+            .iter()
+            .filter_map(|(_, region)| region.as_deref())
+            .collect();
+        all_regions.sort_unstable_by(|a, b| a.cmp(b).reverse());
+        self.generate_lang_parse(&mut f, &lang_variants, &all_regions)?;
+        self.generate_lang_try_from(&mut f)?;
+
+        self.generate_plural_rules(&mut f, &lang_variants)?;
+        #[cfg(feature = "locale-numbers")]
+        if self.locale_numbers || self.locale_count {
+            self.generate_locale_number(&mut f)?;
+        }
+        if self.output == Output::All {
+            if self.typed_functions {
+                self.generate_typed_functions(&mut f)?;
+            }
+            self.generate_keys(&mut f)?;
+            if let Some(prefix) = self.ffi {
+                self.generate_ffi(&mut f, prefix, &sorted_languages)?;
+            }
+            if self.const_table {
+                self.generate_const_table(&mut f, &sorted_languages)?;
+            }
+        }
+
+        if self.lang_code {
+            let mut all_codes: Vec<_> = all_languages
+                .iter()
+                .map(|(lang, region)| (lang.as_str(), region.as_deref()))
+                .chain(lang_variants.iter().map(|lang| (*lang, None)))
+                .collect();
+            all_codes.sort_unstable();
+            all_codes.dedup();
+            self.generate_lang_code(&mut f, &all_codes)?;
+        }
+
+        #[cfg(feature = "serde")]
+        if self.serde {
+            self.generate_serde(&mut f, &lang_variants, &all_regions)?;
+        }
+
+        // the current language is stored in a thread local which needs `std`
+        #[cfg(feature = "current-lang")]
+        let thread_default = self.output == Output::All && !self.no_std;
+        #[cfg(not(feature = "current-lang"))]
+        let thread_default = false;
+        if thread_default {
+            #[cfg(feature = "current-lang")]
+            Self::generate_current_lang(&mut f)?;
+        } else {
+            write!(
+                f,
+                r#"
+
+                /// The first language of `Lang::all_languages()`.
+                impl Default for Lang {{
+                    fn default() -> Self {{
+                        *Lang::all_languages()[0]
+                    }}
+                }}
+                "#,
+            )?;
+        }
+
+        #[cfg(feature = "gettext")]
+        if self.output == Output::All && !self.no_std {
+            Self::generate_gettext(&mut f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl TwineFormatter<'_> {
+    fn generate_from_locale_env(
+        &self,
+        f: &mut CodeFormatter<fmt::Formatter>,
+        lang_variants: &[&str],
+    ) -> fmt::Result {
+        write!(
+            f,
+            r#"
+
+                /// Find the language of the POSIX locale defined by the environment variables
+                /// `LC_ALL`, `LC_MESSAGES` and `LANG` (in this order).
+                ///
+                /// The region is used only if it exists in the translations.
+                pub fn from_locale_env() -> Option<Lang> {{
+                    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+                        .iter()
+                        .filter_map(|name| std::env::var(name).ok())
+                        .find(|value| !value.is_empty())?;
+                    let locale = locale.split(&['.', '@'][..]).next().unwrap_or_default();
+                    let mut it = locale.splitn(2, '_');
+                    let language = it.next().unwrap_or_default();
+                    let region = it.next().unwrap_or_default();
+
+                    let lang = match language.to_lowercase().as_str() {{
+            "#,
+        )?;
+
+        f.indent(3);
+        for lang in lang_variants {
+            write!(
+                f,
+                r#"
+                {:?} => {},
+                "#,
+                lang.to_snake_case(),
+                self.lang_value(lang, None),
+            )?;
+        }
+
+        f.dedent(3);
+        write!(
+            f,
+            r#"
+                        _ => return None,
+                    }};
+
+                    Some(
+                        Lang::all_languages()
+                            .iter()
+                            .find(|x| {{
+                                x.language() == lang.language()
+                                    && x.region().eq_ignore_ascii_case(region)
+                            }})
+                            .map(|x| **x)
+                            .unwrap_or(lang),
+                    )
+                }}
+            "#,
+        )
+    }
+
+    fn generate_macros(&self, f: &mut CodeFormatter<fmt::Formatter>) -> fmt::Result {
+        let mut sorted: Vec<_> = self
+            .map
+            .iter()
+            .map(|(key, section)| (section.order.unwrap_or(0), key, &section.translations))
+            .collect();
+        sorted.sort_unstable();
+        let (plural_keys, keys): (Vec<_>, Vec<_>) = sorted
+            .into_iter()
+            .map(|(_, key, translations)| (key, translations))
+            .partition(|(_, translations)| is_plural(translations));
+
+        self.generate_translate_macro(f, TranslateMacro::Format, &keys)?;
+        self.generate_translate_macro(f, TranslateMacro::Write, &keys)?;
+        self.generate_bytes_macro(f)?;
+        self.generate_raw_macro(f, &keys)?;
+        self.generate_static_macro(f, &keys)?;
+
+        // the plural keys are available for both cardinal and ordinal numbers
+        if !plural_keys.is_empty() {
+            self.generate_plural_macro(f, "plural", "plural_category", &plural_keys)?;
+            self.generate_plural_macro(f, "ordinal", "ordinal_category", &plural_keys)?;
+        }
+
+        // the languages can only be checked when the `Lang` enum is generated
+        if self.output == Output::All {
+            self.generate_lang_macro(f)?;
+        }
+
+        // the current language is only available when the `Lang` enum is generated
+        #[cfg(feature = "current-lang")]
+        if self.output == Output::All && !self.no_std {
+            self.generate_current_lang_macro(f)?;
+        }
+
+        Ok(())
+    }
+
+    // a language in a pattern: `Lang::En(_)` for all its regions or `Lang::En("gb")`, `Lang::En`
+    // when the regions are ignored
+    fn lang_pattern(&self, lang: &str, region: Option<&str>) -> String {
+        match region {
+            _ if self.ignore_regions => format!("Lang::{}", lang),
+            Some(region) => format!("Lang::{}({:?})", lang, region),
+            None => format!("Lang::{}(_)", lang),
+        }
+    }
+
+    // a language as a value: `Lang::En("")` or `Lang::En("gb")`, `Lang::En` when the regions are
+    // ignored
+    fn lang_value(&self, lang: &str, region: Option<&str>) -> String {
+        match region {
+            _ if self.ignore_regions => format!("Lang::{}", lang),
+            region => format!("Lang::{}({:?})", lang, region.unwrap_or("")),
+        }
+    }
+
+    // every key has a translation in the language, in its region or in the language without
+    // region
+    fn is_complete(&self, lang: &str, region: Option<&str>) -> bool {
+        self.map.values().all(|section| {
+            section.translations.iter().any(|(code, _)| {
+                let (x_lang, x_region, _) = parse_lang(code);
+                x_lang == lang && (x_region.is_none() || x_region.as_deref() == region)
+            })
+        })
+    }
+
+    // the macros are exported at the root of the crate unless they are generated in a module
+    fn is_local(&self) -> bool {
+        self.output == Output::Module || self.local_macros.is_some()
+    }
+
+    // the path of the generated items in the macros
+    fn items_path(&self) -> String {
+        match self.local_macros {
+            Some(module) if self.output != Output::Module => format!("$crate::{}::", module),
+            _ => "$crate::".to_string(),
+        }
+    }
+
+    // `std` re-exports the items of `core` and `alloc` used by the generated code
+    fn core(&self) -> &'static str {
+        if self.no_std {
+            "core"
+        } else {
+            "std"
+        }
+    }
+
+    fn string_type(&self) -> &'static str {
+        if self.no_std {
+            "::alloc::string::String"
+        } else {
+            "String"
+        }
+    }
+
+    fn generate_macro_start(
+        &self,
+        f: &mut CodeFormatter<fmt::Formatter>,
+        name: &str,
+    ) -> fmt::Result {
+        if self.is_local() {
+            write!(
+                f,
+                r#"
+
+                #[allow(unused_macros)]
+                "#,
+            )?;
+        } else {
+            write!(
+                f,
+                r#"
+
+                #[macro_export]
+                "#,
+            )?;
+        }
+        write!(
+            f,
+            r#"
+            macro_rules! {name} {{
+            "#,
+        )
+    }
+
+    fn generate_macro_end(&self, f: &mut CodeFormatter<fmt::Formatter>, name: &str) -> fmt::Result {
+        write!(
+            f,
+            r#"
+            }}
+            "#,
+        )?;
+        if self.is_local() {
+            write!(
+                f,
+                r#"
+
+                #[allow(unused_imports)]
+                pub(crate) use {name};
+                "#,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn generate_translate_macro(
+        &self,
+        f: &mut CodeFormatter<fmt::Formatter>,
+        kind: TranslateMacro,
+        keys: &[(&String, &Vec<(String, String)>)],
+    ) -> fmt::Result {
+        self.generate_macro_start(f, kind.name())?;
+        f.indent(1);
+        self.generate_or_default_arms(
+            f,
+            kind.name(),
+            (kind.buffer(), kind.forward_buffer()),
+            self.named_args,
+        )?;
+
+        for (key, translations) in keys {
+            let key = Self::normalize_key(key.as_str());
+            // the named arguments must be tried first because an assignment is also an expression
+            if self.named_args {
+                self.generate_arm(
+                    f,
+                    kind,
+                    &key,
+                    "$(, $arg_name:ident = $arg_value:expr)+",
+                    "$(, $arg_name = $arg_value)+",
+                    translations,
+                )?;
+            }
+            self.generate_arity_checks(f, kind, &key, translations)?;
+            self.generate_arm(
+                f,
+                kind,
+                &key,
+                "$(, $fmt_args:expr)*",
+                "$(, $fmt_args)*",
+                translations,
+            )?;
+        }
+        self.generate_inline_fallback(f, kind)?;
+        f.dedent(1);
+        self.generate_macro_end(f, kind.name())
+    }
+
+    // `t_raw!()`: the translations with their escape sequences decoded, without formatting
+    fn generate_raw_macro(
+        &self,
+        f: &mut CodeFormatter<fmt::Formatter>,
+        keys: &[(&String, &Vec<(String, String)>)],
+    ) -> fmt::Result {
+        self.generate_macro_start(f, "t_raw")?;
+        f.indent(1);
+        self.generate_or_default_arms(f, "t_raw", ("", ""), false)?;
+
+        for (key, translations) in keys {
+            let key = Self::normalize_key(key.as_str());
+            write!(
+                f,
+                r#"
+                ({key} => $lang:expr $(; $_fallback:literal)?) => {{{{
+                    #[allow(unreachable_patterns)]
+                    match $lang {{
+                "#,
+            )?;
+            f.indent(2);
+
+            let fallback = self.fallback_lang(translations);
+            let mut match_arms: Vec<_> = translations
+                .iter()
+                .map(|(lang, text)| {
+                    let (lang, region, _) = parse_lang(lang.as_str());
+                    (lang, region, text)
+                })
+                .collect();
+            match_arms.sort_by(|(a_lang, a_region, _), (b_lang, b_region, _)| {
+                a_lang
+                    .cmp(b_lang)
+                    .then(a_region.is_none().cmp(&b_region.is_none()))
+            });
+            for (lang, region, text) in match_arms {
+                write!(
+                    f,
+                    r#"
+                    {}{} => {:?},
+                    "#,
+                    self.items_path(),
+                    self.lang_pattern(&lang, region.as_deref()),
+                    decode_escapes(text),
+                )?;
+            }
+            if let Some((_, text)) = translations
+                .iter()
+                .find(|(lang, _)| Some(lang.as_str()) == fallback)
+            {
+                write!(
+                    f,
+                    r#"
+                    _ => {:?},
+                    "#,
+                    decode_escapes(text),
+                )?;
+            }
+
+            f.dedent(2);
+            write!(
+                f,
+                r#"
+                    }}
+                }}}};
+                "#,
+            )?;
+        }
+        write!(
+            f,
+            r#"
+            ($_key:ident => $lang:expr; $fallback:literal) => {{{{
+                let _ = $lang;
+                $fallback
+            }}}};
+            "#,
+        )?;
+
+        f.dedent(1);
+        self.generate_macro_end(f, "t_raw")
+    }
+
+    // `const_lang!()`: a language checked at compile time. The language must be a constant
+    // expression, the dynamic languages cannot be checked before runtime and the macros accept
+    // them anyway: an unknown region falls back to its language.
+    fn generate_lang_macro(&self, f: &mut CodeFormatter<fmt::Formatter>) -> fmt::Result {
+        self.generate_macro_start(f, "const_lang")?;
+        write!(
+            f,
+            r#"
+                ($lang:expr) => {{{{
+                    const LANG: {items_path}Lang = $lang;
+                    const _: () = assert!(
+                        LANG.is_known(),
+                        concat!("unknown language: ", stringify!($lang)),
+                    );
+                    LANG
+                }}}};
+            "#,
+            items_path = self.items_path(),
+        )?;
+        self.generate_macro_end(f, "const_lang")
+    }
+
+    // `t_static!()`: the translations without placeholders as `&'static str` literals, usable in
+    // const contexts. The regions are compared as bytes because `&str` patterns are not allowed in
+    // constants.
+    fn generate_static_macro(
+        &self,
+        f: &mut CodeFormatter<fmt::Formatter>,
+        keys: &[(&String, &Vec<(String, String)>)],
+    ) -> fmt::Result {
+        self.generate_macro_start(f, "t_static")?;
+        f.indent(1);
+
+        for (key, translations) in keys {
+            let key = Self::normalize_key(key.as_str());
+            let has_placeholders = translations.iter().any(|(_, text)| {
+                count_arguments(text) > 0 || text.contains('{') || text.contains('}')
+            });
+            if has_placeholders {
+                write!(
+                    f,
+                    r#"
+                    ({key} => $($_tokens:tt)*) => {{{{
+                        compile_error!("t_static!({key}) has placeholders, use t!({key}) instead")
+                    }}}};
+                    "#,
+                )?;
+                continue;
+            }
+
+            write!(
+                f,
+                r#"
+                ({key} => $lang:expr $(; $_fallback:literal)?) => {{{{
+                    #[allow(unreachable_patterns)]
+                    match $lang {{
+                "#,
+            )?;
+            f.indent(2);
+
+            let fallback = self.fallback_lang(translations);
+            let mut match_arms: Vec<_> = translations
+                .iter()
+                .map(|(lang, text)| {
+                    let (lang, region, _) = parse_lang(lang.as_str());
+                    (lang, region, convert_printf(text, false).0)
+                })
+                .collect();
+            match_arms.sort_by(|(a_lang, a_region, _), (b_lang, b_region, _)| {
+                a_lang
+                    .cmp(b_lang)
+                    .then(a_region.is_none().cmp(&b_region.is_none()))
+            });
+            for (lang, region, out) in match_arms {
+                match region {
+                    Some(region) => write!(
+                        f,
+                        r#"
+                        {}Lang::{}(region) if matches!(region.as_bytes(), b{:?}) => "{}",
+                        "#,
+                        self.items_path(),
+                        lang,
+                        region,
+                        out,
+                    )?,
+                    None => write!(
+                        f,
+                        r#"
+                        {}{} => "{}",
+                        "#,
+                        self.items_path(),
+                        self.lang_pattern(&lang, None),
+                        out,
+                    )?,
+                }
+            }
+            if let Some((_, text)) = translations
+                .iter()
+                .find(|(lang, _)| Some(lang.as_str()) == fallback)
+            {
+                write!(
+                    f,
+                    r#"
+                    _ => "{}",
+                    "#,
+                    convert_printf(text, false).0,
+                )?;
+            }
+
+            f.dedent(2);
+            write!(
+                f,
+                r#"
+                    }}
+                }}}};
+                "#,
+            )?;
+        }
+        write!(
+            f,
+            r#"
+            ($_key:ident => $lang:expr; $fallback:literal) => {{{{
+                let _ = $lang;
+                $fallback
+            }}}};
+            "#,
+        )?;
+
+        f.dedent(1);
+        self.generate_macro_end(f, "t_static")
+    }
+
+    // the keys that do not exist use the literal given after the language:
+    // `t!(band_tool => ?lang)`: the `Option<Lang>` is replaced by its value or `Lang::default()`
+    // before calling the macro again, whatever the key
+    fn generate_or_default_arms(
+        &self,
+        f: &mut CodeFormatter<fmt::Formatter>,
+        name: &str,
+        (buffer, forward_buffer): (&str, &str),
+        named_args: bool,
+    ) -> fmt::Result {
+        let mut arms = Vec::new();
+        if named_args {
+            arms.push((
+                "$(, $arg_name:ident = $arg_value:expr)+",
+                "$(, $arg_name = $arg_value)+",
+            ));
+        }
+        arms.push(("$(, $fmt_args:expr)*", "$(, $fmt_args)*"));
+
+        for (pattern, args) in arms {
+            write!(
+                f,
+                r#"
+                ({buffer}$key:ident {pattern} => ?$lang:expr $(; $fallback:literal)?) => {{
+                    {items_path}{name}!(
+                        {forward_buffer}$key {args}
+                            => {core}::option::Option::unwrap_or_default($lang) $(; $fallback)?
+                    )
+                }};
+                "#,
+                items_path = self.items_path(),
+                core = self.core(),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    // `t!(new_key => lang; "fallback")`
+    fn generate_inline_fallback(
+        &self,
+        f: &mut CodeFormatter<fmt::Formatter>,
+        kind: TranslateMacro,
+    ) -> fmt::Result {
+        let mut arms = Vec::new();
+        if self.named_args {
+            arms.push((
+                "$(, $arg_name:ident = $arg_value:expr)+",
+                "$(, $arg_name = $arg_value)+",
+            ));
+        }
+        arms.push(("$(, $fmt_args:expr)*", "$(, $fmt_args)*"));
+
+        for (pattern, args) in arms {
+            write!(
+                f,
+                r#"
+                ({}$_key:ident {pattern} => $lang:expr; $fallback:literal) => {{{{
+                "#,
+                kind.buffer(),
+            )?;
+            if kind == TranslateMacro::Write {
+                write!(
+                    f,
+                    r#"
+                        use {}::fmt::Write as _;
+                    "#,
+                    self.core(),
+                )?;
+            }
+            write!(
+                f,
+                r#"
+                    let _ = $lang;
+                    {}$fallback {args})
+                }}}};
+                "#,
+                kind.call(self.no_std),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn generate_arm(
+        &self,
+        f: &mut CodeFormatter<fmt::Formatter>,
+        kind: TranslateMacro,
+        key: &str,
+        pattern: &str,
+        args: &str,
+        translations: &[(String, String)],
+    ) -> fmt::Result {
+        write!(
+            f,
+            r#"
+            ({}{key} {pattern} => $lang:expr $(; $_fallback:literal)?) => {{{{
+            "#,
+            kind.buffer(),
+        )?;
+        if kind == TranslateMacro::Write {
+            write!(
+                f,
+                r#"
+                    use {}::fmt::Write as _;
+                "#,
+                self.core(),
+            )?;
+        }
+        write!(
+            f,
+            r#"
+                #[allow(unreachable_patterns)]
+                match $lang {{
+            "#,
+        )?;
+        f.indent(2);
+
+        self.generate_match_arms(f, kind, translations, args)?;
+
+        f.dedent(2);
+        write!(
+            f,
+            r#"
+                }}
+            }}}};
+            "#,
+        )?;
+
+        Ok(())
+    }
+
+    // reject the calls with a wrong number of arguments with a clear message instead of the
+    // errors of `format!()` for every language
+    fn generate_arity_checks(
+        &self,
+        f: &mut CodeFormatter<fmt::Formatter>,
+        kind: TranslateMacro,
+        key: &str,
+        translations: &[(String, String)],
+    ) -> fmt::Result {
+        let arity = translations
+            .iter()
+            .map(|(_, text)| count_arguments(text))
+            .max()
+            .unwrap_or(0);
+        let message = format!(
+            "{}!({}) expects {} argument{}",
+            kind.name(),
+            key,
+            arity,
+            if arity == 1 { "" } else { "s" },
+        );
+
+        let args = |n: usize| {
+            (0..n)
+                .map(|i| format!(", $_arg{}:expr", i))
+                .collect::<String>()
+        };
+        for n in 0..arity {
+            write!(
+                f,
+                r#"
+                ({}{key}{} => $lang:expr $(; $_fallback:literal)?) => {{{{
+                    compile_error!("{message}, got {n}")
+                }}}};
+                "#,
+                kind.buffer(),
+                args(n),
+            )?;
+        }
+        write!(
+            f,
+            r#"
+            ({}{key}{} $(, $_args:expr)* => $lang:expr $(; $_fallback:literal)?) => {{{{
+                compile_error!("{message}, got more")
+            }}}};
+            "#,
+            kind.buffer(),
+            args(arity + 1),
+        )?;
+
+        Ok(())
+    }
+
+    // the format string of a translation and its arguments, wrapped in `LocaleNumber` with the
+    // separators of the language when the translation has numbers and `locale_numbers` is enabled
+    fn convert_numbers(&self, lang: &str, text: &str, args: &str) -> (String, String) {
+        let (out, _) = convert_printf_with(text, false, false, false, !self.named_args);
+
+        #[cfg(feature = "locale-numbers")]
+        if self.locale_numbers {
+            if let Some((thousands, decimal)) = numbers::separators(lang) {
+                let (locale_out, _) =
+                    convert_printf_with(text, false, true, false, !self.named_args);
+                if locale_out != out {
+                    let wrap = |arg: &str| {
+                        format!(
+                            "{}LocaleNumber(&{}, {:?}, {:?})",
+                            self.items_path(),
+                            arg,
+                            thousands,
+                            decimal,
+                        )
+                    };
+                    let args = args
+                        .replace("$fmt_args", &wrap("$fmt_args"))
+                        .replace("$arg_value", &wrap("$arg_value"));
+                    return (locale_out, args);
+                }
+            }
+        }
+        #[cfg(not(feature = "locale-numbers"))]
+        let _ = lang;
+
+        (out, args.to_string())
+    }
+
+    // the format string of a plural translation and the count argument (if used), wrapped in
+    // `LocaleNumber` with the separators of the language when `#` is used and `locale_count` is
+    // enabled
+    fn convert_count(&self, lang: &str, text: &str) -> (String, String) {
+        let (out, uses_count) = convert_printf_with(text, true, false, false, !self.named_args);
+        if !uses_count {
+            return (out, String::new());
+        }
+
+        #[cfg(feature = "locale-numbers")]
+        if self.locale_count {
+            if let Some((thousands, decimal)) = numbers::separators(lang) {
+                let (locale_out, _) =
+                    convert_printf_with(text, true, false, true, !self.named_args);
+                if locale_out != out {
+                    let count = format!(
+                        ", {}LocaleNumber(&count, {:?}, {:?})",
+                        self.items_path(),
+                        thousands,
+                        decimal,
+                    );
+                    return (locale_out, count);
+                }
+            }
+        }
+        #[cfg(not(feature = "locale-numbers"))]
+        let _ = lang;
+
+        (out, ", count".to_string())
+    }
+
+    // the placeholder `%{lang}` is a named argument of the format string: the language bound by
+    // the match arm
+    fn convert_lang((out, args): (String, String)) -> (String, String) {
+        if !out.contains(LANG_PLACEHOLDER) {
+            return (out, args);
+        }
+
+        (
+            out.replace(LANG_PLACEHOLDER, "{current_lang}"),
+            format!("{}, current_lang = current_lang", args),
+        )
+    }
+
+    fn generate_match_arms(
+        &self,
+        f: &mut CodeFormatter<fmt::Formatter>,
+        kind: TranslateMacro,
+        translations: &[(String, String)],
+        args: &str,
+    ) -> fmt::Result {
+        let mut match_arms = Vec::new();
+        let mut default_out = None;
+        let fallback = self.fallback_lang(translations);
+        for (lang, text) in translations {
+            let (out, lang_args) = Self::convert_lang(self.convert_numbers(lang, text, args));
+
+            if default_out.is_none() && Some(lang.as_str()) == fallback {
+                default_out = Some((out.clone(), lang_args.clone()));
+            }
+
+            // parse the language and region, then push the match arm
+            let (lang, region, _) = parse_lang(lang.as_str());
+            match_arms.push((lang, region, out, lang_args));
+        }
+        match_arms.sort_unstable_by(|(a_lang, a_region, _, _), (b_lang, b_region, _, _)| {
+            a_lang
+                .cmp(b_lang)
+                .then(a_region.is_none().cmp(&b_region.is_none()))
+        });
+
+        for (lang, region, format, args) in match_arms {
+            // the language is bound to the arm when the translation displays it
+            let binding = if format.contains("{current_lang}") {
+                "current_lang @ "
+            } else {
+                ""
+            };
+            write!(
+                f,
+                r#"
+                {}{}{} => {}"{}" {}),
+                "#,
+                binding,
+                self.items_path(),
+                self.lang_pattern(&lang, region.as_deref()),
+                kind.call(self.no_std),
+                format,
+                args,
+            )?;
+        }
+
+        if let Some((default_out, args)) = default_out {
+            let binding = if default_out.contains("{current_lang}") {
+                "current_lang"
+            } else {
+                "_"
+            };
+            write!(
+                f,
+                r#"
+                {binding} => {}"{default_out}" {args}),
+                "#,
+                kind.call(self.no_std),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn generate_plural_macro(
+        &self,
+        f: &mut CodeFormatter<fmt::Formatter>,
+        name: &str,
+        method: &str,
+        plural_keys: &[(&String, &Vec<(String, String)>)],
+    ) -> fmt::Result {
+        self.generate_macro_start(f, name)?;
+        f.indent(1);
+        self.generate_or_default_arms(f, name, ("", ""), self.named_args)?;
+
+        // the named arguments must be tried first because an assignment is also an expression:
+        // `plural!(cart_items, count = n, name = user => lang)`
+        let mut arms = Vec::new();
+        if self.named_args {
+            arms.push((
+                "count = $count:expr $(, $arg_name:ident = $arg_value:expr)*",
+                "$(, $arg_name = $arg_value)*",
+            ));
+        }
+        arms.push(("$count:expr $(, $fmt_args:expr)*", "$(, $fmt_args)*"));
+
+        for (key, translations) in plural_keys {
+            let key = Self::normalize_key(key.as_str());
+            for (pattern, args) in arms.iter() {
+                self.generate_plural_arm(f, method, &key, (pattern, args), translations)?;
+            }
+        }
+        f.dedent(1);
+        self.generate_macro_end(f, name)?;
+
+        Ok(())
+    }
+
+    // the arm of a plural key selecting the translation by the category of the count
+    fn generate_plural_arm(
+        &self,
+        f: &mut CodeFormatter<fmt::Formatter>,
+        method: &str,
+        key: &str,
+        (pattern, args): (&str, &str),
+        translations: &[(String, String)],
+    ) -> fmt::Result {
+        write!(
+            f,
+            r#"
+            ({key}, {pattern} => $lang:expr) => {{{{
+                let lang = $lang;
+                let count = $count;
+                #[allow(clippy::unnecessary_cast)]
+                let n = count as i64;
+                let category = lang.{method}(n);
+                #[allow(unreachable_patterns)]
+                match (lang, category) {{
+            "#,
+        )?;
+        f.indent(2);
+
+        // the category `zero` is used for 0 in every language but only for cardinal numbers
+        self.generate_plural_match_arms(f, translations, args, method == "plural_category")?;
+
+        f.dedent(2);
+        write!(
+            f,
+            r#"
+                }}
+            }}}};
+            "#,
+        )
+    }
+
+    // the language (with its region and without plural category) used when a key is not
+    // translated in the language requested
+    fn fallback_lang<'t>(&self, translations: &'t [(String, String)]) -> Option<&'t str> {
+        let langs = translations
+            .iter()
+            .map(|(lang, _)| lang.split('.').next().unwrap_or(lang));
+
+        self.fallback_order
+            .iter()
+            .map(String::as_str)
+            .chain(self.default_lang)
+            .find_map(|fallback| langs.clone().find(|lang| *lang == fallback))
+            .or_else(|| langs.clone().next())
+    }
+
+    // `LocaleNumber`: the arguments of `%d` and `%f` are formatted with `LowerExp` to change the
+    // separators, the other formatting traits are delegated to the argument
+    #[cfg(feature = "locale-numbers")]
+    fn generate_locale_number(&self, f: &mut CodeFormatter<fmt::Formatter>) -> fmt::Result {
+        write!(
+            f,
+            r#"
+
+            /// A number displayed with the separators of the thousands and of the decimals of a
+            /// language by the placeholders `%d` and `%f` (the trait `LowerExp` is used for them).
+            #[allow(dead_code)]
+            pub struct LocaleNumber<T>(pub T, pub &'static str, pub &'static str);
+
+            impl<T: {core}::fmt::Display> {core}::fmt::Display for LocaleNumber<T> {{
+                fn fmt(&self, f: &mut {core}::fmt::Formatter) -> {core}::fmt::Result {{
+                    {core}::fmt::Display::fmt(&self.0, f)
+                }}
+            }}
+
+            impl<T: {core}::fmt::LowerHex> {core}::fmt::LowerHex for LocaleNumber<T> {{
+                fn fmt(&self, f: &mut {core}::fmt::Formatter) -> {core}::fmt::Result {{
+                    {core}::fmt::LowerHex::fmt(&self.0, f)
+                }}
+            }}
+
+            impl<T: {core}::fmt::UpperHex> {core}::fmt::UpperHex for LocaleNumber<T> {{
+                fn fmt(&self, f: &mut {core}::fmt::Formatter) -> {core}::fmt::Result {{
+                    {core}::fmt::UpperHex::fmt(&self.0, f)
+                }}
+            }}
+
+            impl<T: {core}::fmt::Display> {core}::fmt::LowerExp for LocaleNumber<T> {{
+                fn fmt(&self, f: &mut {core}::fmt::Formatter) -> {core}::fmt::Result {{
+                    let number = match f.precision() {{
+                        Some(precision) => {format}"{{:.*}}", precision, self.0),
+                        None => {format}"{{}}", self.0),
+                    }};
+                    let (sign, number) = match number.strip_prefix('-') {{
+                        Some(number) => ("-", number),
+                        None if f.sign_plus() => ("+", number.as_str()),
+                        None => ("", number.as_str()),
+                    }};
+                    let (integer, fraction) = match number.split_once('.') {{
+                        Some((integer, fraction)) => (integer, Some(fraction)),
+                        None => (number, None),
+                    }};
+                    // `NaN`, `inf` and the types that are not numbers are written as they are
+                    if integer.is_empty() || !integer.bytes().all(|x| x.is_ascii_digit()) {{
+                        return {core}::fmt::Display::fmt(&self.0, f);
+                    }}
+
+                    let mut out = {string}::with_capacity(number.len() + integer.len() / 3 * self.1.len());
+                    out.push_str(sign);
+                    for (i, digit) in integer.char_indices() {{
+                        if i > 0 && (integer.len() - i) % 3 == 0 {{
+                            out.push_str(self.1);
+                        }}
+                        out.push(digit);
+                    }}
+                    if let Some(fraction) = fraction {{
+                        out.push_str(self.2);
+                        out.push_str(fraction);
+                    }}
+                    match f.width() {{
+                        Some(width) if f.align() == Some({core}::fmt::Alignment::Left) => {{
+                            write!(f, "{{:<1$}}", out, width)
+                        }}
+                        Some(width) => write!(f, "{{:>1$}}", out, width),
+                        None => f.write_str(&out),
+                    }}
+                }}
+            }}
+            "#,
+            core = self.core(),
+            format = TranslateMacro::Format.call(self.no_std),
+            string = self.string_type(),
+        )
+    }
+
+    fn generate_plural_match_arms(
+        &self,
+        f: &mut CodeFormatter<fmt::Formatter>,
+        translations: &[(String, String)],
+        args: &str,
+        zero_override: bool,
+    ) -> fmt::Result {
+        let mut match_arms = Vec::new();
+        let default_lang = self.fallback_lang(translations).map(|lang| {
+            let (lang, region, _) = parse_lang(lang);
+            (lang, region)
+        });
+        for (lang, text) in translations {
+            let (out, count) = self.convert_count(lang, text);
+            let format = format!(
+                r#"{}"{}"{} {})"#,
+                TranslateMacro::Format.call(self.no_std),
+                out,
+                count,
+                args,
+            );
+
+            let (lang, region, category) = parse_lang(lang.as_str());
+            let category = category.filter(|x| x != "other").map(|x| {
+                format!(
+                    "{}PluralCategory::{}",
+                    self.items_path(),
+                    x.to_upper_camel_case()
+                )
+            });
+            match_arms.push((lang, region, category, format));
+        }
+        // the arms of the regions go first, then the arms of the categories, "other" being last
+        match_arms.sort_by(
+            |(a_lang, a_region, a_category, _), (b_lang, b_region, b_category, _)| {
+                a_lang
+                    .cmp(b_lang)
+                    .then(a_region.is_none().cmp(&b_region.is_none()))
+                    .then(a_region.cmp(b_region))
+                    .then(a_category.is_none().cmp(&b_category.is_none()))
+            },
+        );
+
+        // the translations of the category `zero` go first when the count is 0, even if the rules
+        // of the language do not have this category
+        let zero = format!("{}PluralCategory::Zero", self.items_path());
+        let is_zero = |category: &Option<String>| zero_override && category.as_ref() == Some(&zero);
+        for (lang, region, _, format) in match_arms.iter().filter(|(_, _, x, _)| is_zero(x)) {
+            write!(
+                f,
+                r#"
+                ({}{}, _) if n == 0 => {},
+                "#,
+                self.items_path(),
+                self.lang_pattern(lang, region.as_deref()),
+                format,
+            )?;
+        }
+
+        for (lang, region, category, format) in match_arms.iter() {
+            write!(
+                f,
+                r#"
+                ({}{}, {}) => {},
+                "#,
+                self.items_path(),
+                self.lang_pattern(lang, region.as_deref()),
+                category.as_deref().unwrap_or("_"),
+                format,
+            )?;
+        }
+
+        // fallback to the categories of the fallback language
+        if let Some(default_lang) = default_lang {
+            let fallback_arms = match_arms.iter().filter(|(lang, region, _, _)| {
+                (lang, region) == (&default_lang.0, &default_lang.1)
+            });
+            for (_, _, _, format) in fallback_arms.clone().filter(|(_, _, x, _)| is_zero(x)) {
+                write!(
+                    f,
+                    r#"
+                    (_, _) if n == 0 => {},
+                    "#,
+                    format,
+                )?;
+            }
+            for (_, _, category, format) in fallback_arms {
+                write!(
+                    f,
+                    r#"
+                    (_, {}) => {},
+                    "#,
+                    category.as_deref().unwrap_or("_"),
+                    format,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // generate `Lang::parse()`: the same format as serde without allocation
+    fn generate_lang_parse(
+        &self,
+        f: &mut CodeFormatter<fmt::Formatter>,
+        all_languages: &[&str],
+        all_regions: &[&str],
+    ) -> fmt::Result {
+        write!(
+            f,
+            r#"
+
+            #[allow(dead_code)]
+            impl Lang {{
+                /// Parse a language serialized by serde: the language optionally followed by `_` and
+                /// the region, e.g. `fr` or `fr_be`. The region is case-insensitive.
+                ///
+                /// Returns `None` if the language or the region does not exist in the translations.
+                pub fn parse(value: &str) -> Option<Lang> {{
+            "#,
+        )?;
+
+        // the region is dropped
+        if self.ignore_regions {
+            write!(
+                f,
+                r#"
+                    let lang = match value.find('_') {{
+                        Some(i) => &value[..i],
+                        None => value,
+                    }};
+
+                    match lang {{
+                "#,
+            )?;
+            f.indent(3);
+            for lang in all_languages {
+                write!(
+                    f,
+                    r#"
+                    {:?} => Some(Lang::{}),
+                    "#,
+                    lang.to_snake_case(),
+                    lang,
+                )?;
+            }
+            f.dedent(3);
+            return write!(
+                f,
+                r#"
+                        _ => None,
+                    }}
+                }}
+            }}
+                "#,
+            );
+        }
+
+        write!(
+            f,
+            r#"
+                    let (lang, region) = match value.find('_') {{
+                        Some(i) => (&value[..i], &value[i + 1..]),
+                        None => (value, ""),
+                    }};
+                    let region = match region {{
+                        "" => "",
+            "#,
+        )?;
+        f.indent(3);
+
+        for region in all_regions {
+            write!(
+                f,
+                r#"
+                {region:?} => {region:?},
+                "#,
+            )?;
+        }
+
+        if all_regions.is_empty() {
+            write!(
+                f,
+                r#"
+                _ => return None,
+                "#,
+            )?;
+        } else {
+            write!(
+                f,
+                r#"
+                _ => *[{regions}]
+                    .iter()
+                    .find(|x| x.eq_ignore_ascii_case(region))?,
+                "#,
+                regions = all_regions
+                    .iter()
+                    .map(|x| format!("{:?}", x))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            )?;
+        }
+        write!(
+            f,
+            r#"
+            }};
+
+            match lang {{
+            "#,
+        )?;
+        f.indent(1);
+
+        for lang in all_languages {
+            write!(
+                f,
+                r#"
+                {:?} => Some(Lang::{}(region)),
+                "#,
+                lang.to_snake_case(),
+                lang,
+            )?;
+        }
+
+        f.dedent(4);
+        write!(
+            f,
+            r#"
+                        _ => None,
+                    }}
+                }}
+            }}
+            "#,
+        )
+    }
+
+    // `Lang::try_from(&str)` telling if the language or the region is unknown
+    fn generate_lang_try_from(&self, f: &mut CodeFormatter<fmt::Formatter>) -> fmt::Result {
+        write!(
+            f,
+            r#"
+
+            /// The error of `Lang::try_from(&str)`, with the part of the value that is unknown.
+            #[derive(Debug, Clone, PartialEq, Eq)]
+            #[allow(dead_code)]
+            pub enum LangParseError {{
+                /// The language is not translated: `de` for `de_ch`.
+                UnknownLanguage({string}),
+                /// The language is not translated in this region: `us` for `en_us`.
+                UnknownRegion({string}),
+            }}
+
+            impl {core}::fmt::Display for LangParseError {{
+                fn fmt(&self, f: &mut {core}::fmt::Formatter) -> {core}::fmt::Result {{
+                    match self {{
+                        LangParseError::UnknownLanguage(language) => {{
+                            write!(f, "unknown language: {{:?}}", language)
+                        }}
+                        LangParseError::UnknownRegion(region) => {{
+                            write!(f, "unknown region: {{:?}}", region)
+                        }}
+                    }}
+                }}
+            }}
+            "#,
+            core = self.core(),
+            string = self.string_type(),
+        )?;
+        // `std::error::Error` needs `std`
+        if !self.no_std {
+            write!(
+                f,
+                r#"
+
+                impl std::error::Error for LangParseError {{}}
+                "#,
+            )?;
+        }
+        write!(
+            f,
+            r#"
+
+            impl {core}::convert::TryFrom<&str> for Lang {{
+                type Error = LangParseError;
+
+                /// Parse a language like `Lang::parse()` (`fr` or `fr_be`, the region is
+                /// case-insensitive) but the language must be translated in the region.
+                fn try_from(value: &str) -> Result<Self, Self::Error> {{
+                    let (language, region) = match value.find('_') {{
+                        Some(i) => (&value[..i], &value[i + 1..]),
+                        None => (value, ""),
+                    }};
+                    let mut regions = Self::all_languages()
+                        .iter()
+                        .filter(|x| x.language() == language)
+                        .peekable();
+                    if regions.peek().is_none() {{
+                        return Err(LangParseError::UnknownLanguage(language.into()));
+                    }}
+                    regions
+                        {find_region}
+                        .map(|x| **x)
+                        .ok_or_else(|| LangParseError::UnknownRegion(region.into()))
+                }}
+            }}
+            "#,
+            core = self.core(),
+            // the region is dropped when the regions are ignored
+            find_region = if self.ignore_regions {
+                ".next()"
+            } else {
+                ".find(|x| x.region().eq_ignore_ascii_case(region))"
+            },
+        )
+    }
+
+    fn generate_plural_rules(
+        &self,
+        f: &mut CodeFormatter<fmt::Formatter>,
+        lang_variants: &[&str],
+    ) -> fmt::Result {
+        write!(
+            f,
+            r#"
+
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            #[allow(dead_code)]
+            pub enum PluralCategory {{
+                Zero,
+                One,
+                Two,
+                Few,
+                Many,
+                Other,
+            }}
+
+            impl Lang {{
+            "#,
+        )?;
+        f.indent(1);
+
+        self.generate_plural_rule(f, "plural_category", lang_variants, plural::cardinal_rule)?;
+        self.generate_plural_rule(f, "ordinal_category", lang_variants, plural::ordinal_rule)?;
+
+        f.dedent(1);
+        write!(
+            f,
+            r#"
+            }}
+            "#,
+        )?;
+
+        Ok(())
+    }
+
+    fn generate_plural_rule(
+        &self,
+        f: &mut CodeFormatter<fmt::Formatter>,
+        method: &str,
+        lang_variants: &[&str],
+        rule: fn(&str) -> plural::Rules,
+    ) -> fmt::Result {
+        write!(
+            f,
+            r#"
+            #[allow(dead_code, unknown_lints, clippy::manual_is_multiple_of)]
+            pub fn {method}(&self, n: i64) -> PluralCategory {{
+                let n = n.unsigned_abs();
+                match self {{
+            "#,
+        )?;
+        f.indent(2);
+
+        for lang in lang_variants {
+            write!(
+                f,
+                r#"
+                {} => {{
+                "#,
+                self.lang_pattern(lang, None),
+            )?;
+            f.indent(1);
+            for (category, condition) in rule(lang.to_snake_case().as_str()) {
+                write!(
+                    f,
+                    r#"
+                    if {} {{
+                        return PluralCategory::{};
+                    }}
+                    "#,
+                    condition, category,
+                )?;
+            }
+            f.dedent(1);
+            write!(
+                f,
+                r#"
+                }}
+                "#,
+            )?;
+        }
+
+        f.dedent(2);
+        write!(
+            f,
+            r#"
+                }}
+                PluralCategory::Other
+            }}
+            "#,
+        )?;
+
+        Ok(())
+    }
+
+    // generate a function for every key with the types of the arguments inferred from the printf's
+    // format placeholders
+    fn generate_typed_functions(&self, f: &mut CodeFormatter<fmt::Formatter>) -> fmt::Result {
+        let lang_path = match self.output {
+            Output::Module => "crate::Lang",
+            _ => "Lang",
+        };
+        let mut sorted: Vec<_> = self.map.iter().collect();
+        sorted.sort_unstable_by_key(|(key, section)| (section.order.unwrap_or(0), *key));
+
+        for (key, section) in sorted {
+            let translations = &section.translations;
+            if translations
+                .iter()
+                .any(|(_, text)| RE_NAMED_ARGUMENT.is_match(&text.replace(LANG_PLACEHOLDER, "")))
+            {
+                continue;
+            }
+
+            let mut kinds: BTreeMap<usize, Vec<PlaceholderKind>> = BTreeMap::new();
+            for (_, text) in translations {
+                for (position, kind) in parse_placeholders(text) {
+                    let kinds = kinds.entry(position).or_default();
+                    if !kinds.contains(&kind) {
+                        kinds.push(kind);
+                    }
+                }
+            }
+            let arity = kinds.keys().copied().max().unwrap_or(0);
+            let plural = is_plural(translations);
+            // the count of the plural keys is always the first argument
+            let first = if plural { 2 } else { 1 };
+
+            let mut params = String::new();
+            let mut args = String::new();
+            if plural {
+                params.push_str(", count: i64");
+                args.push_str(", count");
+            }
+            for position in first..=arity {
+                let type_ =
+                    Self::argument_type(kinds.get(&position).map(Vec::as_slice), self.core());
+                write!(params, ", arg{}: {}", position, type_)?;
+                write!(args, ", arg{}", position)?;
+            }
+
+            let name = Self::normalize_key(key.as_str());
+            let function = Self::function_name(key.as_str());
+            let call = if plural {
+                format!("plural!({name}{args} => *lang)")
+            } else {
+                format!("t!({name}{args} => *lang)")
+            };
+            write!(
+                f,
+                r#"
+
+                /// Translate the key `{key}`.
+                "#,
+            )?;
+            if let Some(context) = section.context.as_ref() {
+                write!(
+                    f,
+                    r#"
+                    ///
+                    /// Context: <{context}>
+                    "#,
+                )?;
+            }
+            if let Some(replacement) = section.deprecated.as_ref() {
+                write!(
+                    f,
+                    r#"
+                    #[deprecated(note = "use {}")]
+                    "#,
+                    Self::normalize_key(replacement),
+                )?;
+            }
+            write!(
+                f,
+                r#"
+                #[allow(dead_code)]
+                pub fn {function}(lang: &{lang_path}{params}) -> {string} {{
+                    {call}
+                }}
+                "#,
+                string = self.string_type(),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    // the type of an argument of the typed functions
+    fn argument_type(kinds: Option<&[PlaceholderKind]>, core: &str) -> String {
+        match kinds.unwrap_or_default() {
+            [PlaceholderKind::Int] => "i64".to_string(),
+            [PlaceholderKind::Float] => "f64".to_string(),
+            kinds => {
+                let mut traits: Vec<_> = kinds
+                    .iter()
+                    .map(|kind| match kind {
+                        PlaceholderKind::Hex => "LowerHex",
+                        PlaceholderKind::UpperHex => "UpperHex",
+                        _ => "Display",
+                    })
+                    .collect();
+                if traits.is_empty() {
+                    traits.push("Display");
+                }
+                traits.sort_unstable();
+                traits.dedup();
+                let traits: Vec<_> = traits
+                    .iter()
+                    .map(|x| format!("{}::fmt::{}", core, x))
+                    .collect();
+                format!("impl {}", traits.join(" + "))
+            }
+        }
+    }
+
+    // generate the `Key` enum and the `preview()` function returning the raw translations
+    // the variants of `Key` with their key and section, in the order of their declaration: by
+    // emission order then by variant
+    fn sorted_variants(&self) -> Vec<(String, &String, &Section)> {
+        let mut sorted: Vec<_> = self
+            .map
+            .iter()
+            .map(|(key, section)| {
+                (
+                    case::to_upper_camel_case(&Self::normalize_key(key.as_str())),
+                    key,
+                    section,
+                )
+            })
+            .collect();
+        sorted.sort_unstable_by(|(a_key, _, a), (b_key, _, b)| {
+            (a.order.unwrap_or(0), a_key).cmp(&(b.order.unwrap_or(0), b_key))
+        });
+        sorted
+    }
+
+    fn generate_keys(&self, f: &mut CodeFormatter<fmt::Formatter>) -> fmt::Result {
+        // the `Lang` enum is at the root of the crate when the keys are generated in a module
+        let lang_path = match self.output {
+            Output::Module => "crate::Lang",
+            _ => "Lang",
+        };
+        let sorted = self.sorted_variants();
+        // `Key::ALL` stays sorted by variant
+        let mut variants: Vec<_> = sorted.iter().map(|(key, _, _)| key).collect();
+        variants.sort_unstable();
+
+        write!(
+            f,
+            r#"
+
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            #[allow(dead_code)]
+            pub enum Key {{
+            "#,
+        )?;
+        f.indent(1);
+
+        for (key, name, section) in sorted.iter() {
+            if let Some(context) = section.context.as_ref() {
+                write!(
+                    f,
+                    r#"
+                    /// Context: <{context}>
+                    "#,
+                )?;
+            }
+            if let Some(crate_name) = self.doctests.as_deref() {
+                self.generate_key_doctest(f, crate_name, name, section)?;
+            }
+            write!(
+                f,
+                r#"
+                {key},
+                "#,
+            )?;
+        }
+
+        f.dedent(1);
+        write!(
+            f,
+            r#"
+            }}
+
+            #[allow(dead_code)]
+            pub fn preview(key: Key) -> &'static [({lang_path}, &'static str)] {{
+                match key {{
+            "#,
+        )?;
+        f.indent(2);
+
+        for (key, _, section) in sorted.iter() {
+            // only the category "other" of the plural keys is used
+            let mut previews: Vec<_> = section
+                .translations
+                .iter()
+                .map(|(lang, text)| (parse_lang(lang.as_str()), text))
+                .filter(|((_, _, category), _)| category.as_deref().unwrap_or("other") == "other")
+                .map(|((lang, region, _), text)| (lang, region, text))
+                .collect();
+            previews.sort_by(|(a_lang, a_region, _), (b_lang, b_region, _)| {
+                a_lang.cmp(b_lang).then(a_region.cmp(b_region))
+            });
+
+            write!(
+                f,
+                r#"
+                Key::{key} => &[
+                "#,
+            )?;
+            f.indent(1);
+            for (lang, region, text) in previews {
+                write!(
+                    f,
+                    r#"
+                    ({}{}, {:?}),
+                    "#,
+                    lang_path.trim_end_matches("Lang"),
+                    self.lang_value(&lang, region.as_deref()),
+                    decode_escapes(text),
+                )?;
+            }
+            f.dedent(1);
+            write!(
+                f,
+                r#"
+                ],
+                "#,
+            )?;
+        }
+
+        f.dedent(2);
+        write!(
+            f,
+            r#"
+                }}
+            }}
+
+            #[allow(dead_code)]
+            impl Key {{
+                /// Find a key by its name in the INI file.
+                pub fn from_name(name: &str) -> Option<Key> {{
+                    match name {{
+            "#,
+        )?;
+        f.indent(3);
+
+        let mut names: Vec<_> = self.map.keys().collect();
+        names.sort_unstable();
+        for name in names.iter() {
+            write!(
+                f,
+                r#"
+                {:?} => Some(Key::{}),
+                "#,
+                name,
+                case::to_upper_camel_case(&Self::normalize_key(name.as_str())),
+            )?;
+        }
+
+        f.dedent(3);
+        write!(
+            f,
+            r#"
+                        _ => None,
+                    }}
+                }}
+
+                /// All the keys, sorted by variant.
+                pub const ALL: &[Key] = &[
+            "#,
+        )?;
+        f.indent(3);
+
+        for key in variants.iter() {
+            write!(
+                f,
+                r#"
+                Key::{key},
+                "#,
+            )?;
+        }
+
+        f.dedent(3);
+        write!(
+            f,
+            r#"
+                ];
+
+                /// Iterate over all the keys, sorted by variant: `Key::iter().map(preview)`.
+                pub fn iter() -> impl Iterator<Item = Key> {{
+                    Self::ALL.iter().copied()
+                }}
+            }}
+            "#,
+        )?;
+
+        #[cfg(feature = "phf")]
+        let lookup = {
+            let mut map = phf_codegen::Map::new();
+            for name in names.iter() {
+                map.entry(
+                    name.as_str(),
+                    &format!(
+                        "Key::{}",
+                        case::to_upper_camel_case(&Self::normalize_key(name.as_str()))
+                    ),
+                );
+            }
+            write!(
+                f,
+                r#"
+
+                /// The keys by their name in the INI file.
+                #[allow(dead_code)]
+                pub static KEYS: phf::Map<&'static str, Key> = {};
+                "#,
+                map.build().to_string().replace('\n', "\n                "),
+            )?;
+            "KEYS.get(name).copied()"
+        };
+        #[cfg(not(feature = "phf"))]
+        let lookup = "Key::from_name(name)";
+
+        write!(
+            f,
+            r#"
+
+            /// Returns the raw translation (see `preview()`) of a key, by its name in the INI file,
+            /// in a language. The translation of the language without region is used if the
+            /// region is not translated.
+            #[allow(dead_code)]
+            pub fn translate(name: &str, lang: {lang_path}) -> Option<&'static str> {{
+                let translations = preview({lookup}?);
+                translations
+                    .iter()
+                    .find(|(x, _)| *x == lang)
+                    .or_else(|| {{
+                        translations
+                            .iter()
+                            .find(|(x, _)| x.language() == lang.language() && x.region().is_empty())
+                    }})
+                    .map(|(_, text)| *text)
+            }}
+
+            /// Returns the raw translation of a key in a language (see `preview()`) to
+            /// describe a value in its `Display` implementation (e.g. an error variant). The
+            /// translation of the language without region is used if the region is not
+            /// translated, then the translation used by `t!()` for the other languages.
+            #[allow(dead_code)]
+            pub fn describe(key: Key, lang: &{lang_path}) -> {string} {{
+                let translations = preview(key);
+                let text = translations
+                    .iter()
+                    .find(|(x, _)| x == lang)
+                    .or_else(|| {{
+                        translations
+                            .iter()
+                            .find(|(x, _)| x.language() == lang.language() && x.region().is_empty())
+                    }})
+                    .map(|(_, text)| *text)
+                    .unwrap_or(match key {{
+            "#,
+            string = self.string_type(),
         )?;
         f.indent(3);
 
-        let mut sorted_languages: Vec<_> = all_languages.iter().collect();
-        sorted_languages.sort_unstable();
+        for (key, _, section) in sorted.iter() {
+            let fallback = self.fallback_lang(&section.translations);
+            // only the category "other" of the plural keys is used
+            let text = section
+                .translations
+                .iter()
+                .find(|(lang, _)| {
+                    let (_, _, category) = parse_lang(lang);
+                    lang.split('.').next() == fallback
+                        && category.as_deref().unwrap_or("other") == "other"
+                })
+                .map_or(String::new(), |(_, text)| decode_escapes(text));
+            write!(
+                f,
+                r#"
+                Key::{key} => {text:?},
+                "#,
+            )?;
+        }
+
+        f.dedent(3);
+        write!(
+            f,
+            r#"
+                    }});
+                {string}::from(text)
+            }}
+
+            /// Returns `true` if the translations of a key are markdown (metadata
+            /// `format = markdown`): they are rendered as markdown instead of plain text.
+            #[allow(dead_code)]
+            pub fn is_markdown(key: Key) -> bool {{
+            "#,
+            string = self.string_type(),
+        )?;
+        let markdown: Vec<_> = sorted
+            .iter()
+            .filter(|(_, _, section)| section.markdown)
+            .map(|(key, _, _)| format!("Key::{}", key))
+            .collect();
+        if markdown.is_empty() {
+            write!(
+                f,
+                r#"
+                    let _ = key;
+                    false
+                }}
+                "#,
+            )?;
+        } else {
+            write!(
+                f,
+                r#"
+                    matches!(key, {})
+                }}
+                "#,
+                markdown.join(" | "),
+            )?;
+        }
+
+        // the maps are only available with `std`
+        if !self.no_std {
+            write!(
+                f,
+                r#"
+
+                /// Checks that all the keys of translations loaded at runtime (e.g. an override
+                /// file) exist: the unknown keys are returned sorted alphabetically.
+                #[allow(dead_code)]
+                pub fn validate_overrides(
+                    map: &std::collections::HashMap<String, String>,
+                ) -> Result<(), Vec<String>> {{
+                    let mut unknown: Vec<String> = map
+                        .keys()
+                        .filter(|name| {{
+                            let name = name.as_str();
+                            {lookup}.is_none()
+                        }})
+                        .cloned()
+                        .collect();
+                    if unknown.is_empty() {{
+                        return Ok(());
+                    }}
+                    unknown.sort_unstable();
+                    Err(unknown)
+                }}
+                "#,
+            )?;
+        }
+
+        // the named arguments given in a map at runtime (e.g. a template filled by the user)
+        if !self.no_std && self.named_args {
+            write!(
+                f,
+                r#"
+
+                /// The named arguments missing from the map given to `format_dynamic()`, in the
+                /// order of the translation.
+                #[derive(Debug, Clone, PartialEq, Eq)]
+                pub struct MissingArg(pub Vec<String>);
+
+                impl std::fmt::Display for MissingArg {{
+                    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {{
+                        write!(f, "missing named arguments: {{}}", self.0.join(", "))
+                    }}
+                }}
+
+                impl std::error::Error for MissingArg {{}}
+
+                /// Returns the translation of a key in a language like `describe()` with its named
+                /// arguments (`{{name}}`) replaced by the values of `args`. The names missing from
+                /// `args` are returned in the error, `%{{lang}}` is replaced by the code of the
+                /// language.
+                #[allow(dead_code)]
+                pub fn format_dynamic(
+                    key: Key,
+                    lang: &{lang_path},
+                    args: &std::collections::HashMap<&str, &str>,
+                ) -> Result<String, MissingArg> {{
+                    let text = describe(key, lang);
+                    let mut formatted = String::with_capacity(text.len());
+                    let mut missing: Vec<String> = Vec::new();
+                    let mut rest = text.as_str();
+                    while let Some(start) = rest.find('{{') {{
+                        let (before, after) = rest.split_at(start);
+                        let len = after[1..]
+                            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                            .unwrap_or(after.len() - 1);
+                        let name = &after[1..=len];
+                        if len == 0 || !after[len + 1..].starts_with('}}') {{
+                            formatted.push_str(before);
+                            formatted.push('{{');
+                            rest = &after[1..];
+                            continue;
+                        }}
+                        if name == "lang" && before.ends_with('%') {{
+                            formatted.push_str(&before[..before.len() - 1]);
+                            formatted.push_str(&lang.code());
+                        }} else {{
+                            formatted.push_str(before);
+                            match args.get(name) {{
+                                Some(value) => formatted.push_str(value),
+                                None if !missing.iter().any(|x| x == name) => {{
+                                    missing.push(name.to_string())
+                                }}
+                                None => {{}}
+                            }}
+                        }}
+                        rest = &after[len + 2..];
+                    }}
+                    formatted.push_str(rest);
+                    if missing.is_empty() {{
+                        Ok(formatted)
+                    }} else {{
+                        Err(MissingArg(missing))
+                    }}
+                }}
+                "#,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    // `/// # Examples` calling `t!()` in the first language of a key, with sample arguments
+    fn generate_key_doctest(
+        &self,
+        f: &mut CodeFormatter<fmt::Formatter>,
+        crate_name: &str,
+        name: &str,
+        section: &Section,
+    ) -> fmt::Result {
+        let translations = &section.translations;
+        let named_args = translations
+            .iter()
+            .any(|(_, text)| RE_NAMED_ARGUMENT.is_match(&text.replace(LANG_PLACEHOLDER, "")));
+        if self.is_local() || is_plural(translations) || named_args {
+            return Ok(());
+        }
+        let (lang, text) = match translations.first() {
+            Some(translation) => translation,
+            None => return Ok(()),
+        };
+
+        let mut kinds: BTreeMap<usize, Vec<PlaceholderKind>> = BTreeMap::new();
+        for (_, text) in translations {
+            for (position, kind) in parse_placeholders(text) {
+                kinds.entry(position).or_default().push(kind);
+            }
+        }
+        let arity = kinds.keys().copied().max().unwrap_or(0);
+        let mut args = String::new();
+        for position in 1..=arity {
+            let kinds = kinds.get(&position).map(Vec::as_slice).unwrap_or_default();
+            let sample = if kinds
+                .iter()
+                .any(|x| matches!(x, PlaceholderKind::Hex | PlaceholderKind::UpperHex))
+            {
+                "255"
+            } else if kinds.contains(&PlaceholderKind::Float) {
+                "1.5"
+            } else if kinds.contains(&PlaceholderKind::Int) {
+                "1"
+            } else {
+                "\"a\""
+            };
+            write!(args, ", {}", sample)?;
+        }
+
+        let (camel_lang, region, _) = parse_lang(lang.as_str());
+        let call = format!(
+            "{crate_name}::t!({}{args} => {crate_name}::{})",
+            Self::normalize_key(name),
+            self.lang_value(&camel_lang, region.as_deref()),
+        );
+        // the output is only known without arguments, the other calls check the arguments
+        let example = if arity == 0 && !text.contains('{') && !text.contains('}') {
+            format!(
+                "assert_eq!({}, \"{}\");",
+                call,
+                convert_printf(text, false).0
+            )
+        } else {
+            format!("let _ = {};", call)
+        };
+        if section.context.is_some() {
+            write!(
+                f,
+                r#"
+                ///
+                "#,
+            )?;
+        }
+        write!(
+            f,
+            r#"
+            /// # Examples
+            ///
+            /// ```
+            /// {example}
+            /// ```
+            "#,
+        )
+    }
+
+    // the `extern "C"` functions giving the translations by the indices of the key and the language
+    // `TABLE[key as usize][lang_index(&lang)]`: the raw translations of every key in every
+    // language, the missing cells filled with the fallback of `t!()`
+    fn generate_const_table(
+        &self,
+        f: &mut CodeFormatter<fmt::Formatter>,
+        sorted_languages: &[&(String, Option<String>)],
+    ) -> fmt::Result {
+        let sorted = self.sorted_variants();
+
+        write!(
+            f,
+            r#"
+
+            /// The number of keys: the rows of `TABLE`, indexed by `key as usize`.
+            #[allow(dead_code)]
+            pub const NUM_KEYS: usize = {};
+
+            /// The number of languages: the columns of `TABLE`, indexed by `lang_index(&lang)`.
+            #[allow(dead_code)]
+            pub const NUM_LANGS: usize = {};
+
+            /// The raw translations by key and language (see `preview()`):
+            /// `TABLE[Key::BandTool as usize][lang_index(&lang)]`.
+            #[allow(dead_code)]
+            pub const TABLE: [[&str; NUM_LANGS]; NUM_KEYS] = [
+            "#,
+            sorted.len(),
+            sorted_languages.len(),
+        )?;
+        f.indent(1);
+
+        for (_, name, section) in sorted.iter() {
+            // only the category "other" of the plural keys is used
+            let translations: Vec<_> = section
+                .translations
+                .iter()
+                .map(|(lang, text)| (lang.split('.').next().unwrap_or(lang), text))
+                .zip(
+                    section
+                        .translations
+                        .iter()
+                        .map(|(lang, _)| parse_lang(lang)),
+                )
+                .filter(|(_, (_, _, category))| category.as_deref().unwrap_or("other") == "other")
+                .map(|((code, text), (lang, region, _))| (code, lang, region, text.as_str()))
+                .collect();
+            let fallback = self.fallback_lang(&section.translations);
+            let fallback = translations
+                .iter()
+                .find(|(code, _, _, _)| Some(*code) == fallback)
+                .map_or("", |(_, _, _, text)| text);
+            let cells: Vec<_> = sorted_languages
+                .iter()
+                .map(|(lang, region)| {
+                    let text = translations
+                        .iter()
+                        .find(|(_, x, y, _)| x == lang && y == region)
+                        .or_else(|| {
+                            translations
+                                .iter()
+                                .find(|(_, x, y, _)| x == lang && y.is_none())
+                        })
+                        .map_or(fallback, |(_, _, _, text)| text);
+                    format!("{:?}", decode_escapes(text))
+                })
+                .collect();
+            write!(
+                f,
+                r#"
+                // {name}
+                [{}],
+                "#,
+                cells.join(", "),
+            )?;
+        }
+
+        f.dedent(1);
+        write!(
+            f,
+            r#"
+            ];
+
+            /// The index of a language in the columns of `TABLE`, in the order of
+            /// `Lang::all_languages()`. A region that is not translated uses the column of its
+            /// language (or of its first region).
+            #[allow(dead_code)]
+            pub const fn lang_index(lang: &Lang) -> usize {{
+                match lang {{
+            "#,
+        )?;
+        f.indent(2);
+
+        for (i, (lang, region)) in sorted_languages.iter().enumerate() {
+            if self.ignore_regions {
+                write!(
+                    f,
+                    r#"
+                    Lang::{lang} => {i},
+                    "#,
+                )?;
+                continue;
+            }
+            if let Some(region) = region {
+                write!(
+                    f,
+                    r#"
+                    Lang::{lang}(region) if matches!(region.as_bytes(), b{region:?}) => {i},
+                    "#,
+                )?;
+            }
+            // after its last column, the other regions of the language use the column without
+            // region (the first one) or of its first region
+            let is_last = sorted_languages
+                .get(i + 1)
+                .map_or(true, |(next, _)| next != lang);
+            if is_last {
+                let base = sorted_languages
+                    .iter()
+                    .position(|(x, _)| x == lang)
+                    .expect("the language has a column");
+                write!(
+                    f,
+                    r#"
+                    Lang::{lang}(_) => {base},
+                    "#,
+                )?;
+            }
+        }
+
+        f.dedent(2);
+        write!(
+            f,
+            r#"
+                }}
+            }}
+            "#,
+        )
+    }
+
+    fn generate_ffi(
+        &self,
+        f: &mut CodeFormatter<fmt::Formatter>,
+        prefix: &str,
+        sorted_languages: &[&(String, Option<String>)],
+    ) -> fmt::Result {
+        let mut names: Vec<_> = self.map.keys().collect();
+        names.sort_unstable();
+
+        write!(
+            f,
+            r#"
+
+            /// The names of the keys by their index in the `extern "C"` functions.
+            #[allow(dead_code)]
+            pub static FFI_KEYS: [&str; {}] = [
+            "#,
+            names.len(),
+        )?;
+        f.indent(1);
+        for name in names.iter() {
+            write!(
+                f,
+                r#"
+                {name:?},
+                "#,
+            )?;
+        }
+        f.dedent(1);
+        write!(
+            f,
+            r#"
+            ];
+
+            /// The codes of the languages by their index in the `extern "C"` functions.
+            #[allow(dead_code)]
+            pub static FFI_LANGS: [&str; {}] = [
+            "#,
+            sorted_languages.len(),
+        )?;
+        f.indent(1);
+        for (lang, region) in sorted_languages.iter() {
+            let code = match region {
+                Some(region) if self.uppercase_regions => {
+                    format!("{}_{}", lang.to_lowercase(), region.to_uppercase())
+                }
+                Some(region) => format!("{}_{}", lang.to_lowercase(), region),
+                None => lang.to_lowercase(),
+            };
+            write!(
+                f,
+                r#"
+                {code:?},
+                "#,
+            )?;
+        }
+        f.dedent(1);
+        write!(
+            f,
+            r#"
+            ];
+
+            // the translations terminated by NUL, by `key * FFI_LANGS.len() + lang`
+            static FFI_TRANSLATIONS: [Option<&str>; {}] = [
+            "#,
+            names.len() * sorted_languages.len(),
+        )?;
+        f.indent(1);
+        for name in names.iter() {
+            let section = &self.map[*name];
+            // only the category "other" of the plural keys is used
+            let translations: Vec<_> = section
+                .translations
+                .iter()
+                .map(|(lang, text)| (parse_lang(lang.as_str()), text))
+                .filter(|((_, _, category), _)| category.as_deref().unwrap_or("other") == "other")
+                .map(|((lang, region, _), text)| (lang, region, text))
+                .collect();
+            write!(
+                f,
+                r#"
+                // {name}
+                "#,
+            )?;
+            for (lang, region) in sorted_languages.iter() {
+                let text = translations
+                    .iter()
+                    .find(|(x, y, _)| x == lang && y == region)
+                    .or_else(|| {
+                        translations
+                            .iter()
+                            .find(|(x, y, _)| x == lang && y.is_none())
+                    });
+                match text {
+                    Some((_, _, text)) => write!(
+                        f,
+                        r#"
+                        Some({:?}),
+                        "#,
+                        format!("{}\0", decode_escapes(text)),
+                    )?,
+                    None => write!(
+                        f,
+                        r#"
+                        None,
+                        "#,
+                    )?,
+                }
+            }
+        }
+        f.dedent(1);
+        write!(
+            f,
+            r#"
+            ];
+
+            // the index of `name` in `list` (compared like `code()` for the languages), or -1
+            fn ffi_index(list: &[&str], name: &[u8]) -> i32 {{
+                list.iter()
+                    .position(|x| {{
+                        x.len() == name.len()
+                            && x.bytes().zip(name).all(|(a, b)| {{
+                                a.eq_ignore_ascii_case(if *b == b'-' {{ &b'_' }} else {{ b }})
+                            }})
+                    }})
+                    .map_or(-1, |x| x as i32)
+            }}
+
+            /// The number of keys.
+            #[no_mangle]
+            pub extern "C" fn {prefix}_key_count() -> u32 {{
+                FFI_KEYS.len() as u32
+            }}
+
+            /// The number of languages.
+            #[no_mangle]
+            pub extern "C" fn {prefix}_lang_count() -> u32 {{
+                FFI_LANGS.len() as u32
+            }}
+
+            /// The index of a key by its name in the INI file, -1 if the key is unknown.
+            ///
+            /// # Safety
+            ///
+            /// `name` must point to `len` readable bytes.
+            #[no_mangle]
+            pub unsafe extern "C" fn {prefix}_key_index(name: *const u8, len: usize) -> i32 {{
+                if name.is_null() {{
+                    return -1;
+                }}
+                let name = unsafe {{ {core}::slice::from_raw_parts(name, len) }};
+                FFI_KEYS
+                    .iter()
+                    .position(|x| x.as_bytes() == name)
+                    .map_or(-1, |x| x as i32)
+            }}
+
+            /// The index of a language by its code (`en_gb` or `en-GB`), -1 if the language is
+            /// unknown.
+            ///
+            /// # Safety
+            ///
+            /// `code` must point to `len` readable bytes.
+            #[no_mangle]
+            pub unsafe extern "C" fn {prefix}_lang_index(code: *const u8, len: usize) -> i32 {{
+                if code.is_null() {{
+                    return -1;
+                }}
+                ffi_index(&FFI_LANGS, unsafe {{ {core}::slice::from_raw_parts(code, len) }})
+            }}
+
+            /// The raw translation of a key in a language (escape sequences decoded), terminated by
+            /// NUL: its length without NUL is written in `len`. The translation of the language
+            /// without region is used if the region is not translated, NULL is returned if there is
+            /// none or if an index is out of range.
+            ///
+            /// # Safety
+            ///
+            /// `len` must be NULL or point to a writable `size_t`.
+            #[no_mangle]
+            pub unsafe extern "C" fn {prefix}_translate(key: u32, lang: u32, len: *mut usize) -> *const u8 {{
+                let (key, lang) = (key as usize, lang as usize);
+                if key >= FFI_KEYS.len() || lang >= FFI_LANGS.len() {{
+                    return {core}::ptr::null();
+                }}
+                match FFI_TRANSLATIONS[key * FFI_LANGS.len() + lang] {{
+                    Some(text) => {{
+                        if !len.is_null() {{
+                            unsafe {{ *len = text.len() - 1 }};
+                        }}
+                        text.as_ptr()
+                    }}
+                    None => {core}::ptr::null(),
+                }}
+            }}
+            "#,
+            core = self.core(),
+        )
+    }
+
+    fn generate_lang_code(
+        &self,
+        f: &mut CodeFormatter<fmt::Formatter>,
+        all_codes: &[(&str, Option<&str>)],
+    ) -> fmt::Result {
+        let variant = |lang: &str, region: Option<&str>| match region {
+            Some(region) => format!("{}{}", lang, region.to_upper_camel_case()),
+            None => lang.to_string(),
+        };
+
+        write!(
+            f,
+            r#"
+
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            #[repr(u16)]
+            #[allow(dead_code)]
+            pub enum LangCode {{
+            "#,
+        )?;
+        f.indent(1);
+
+        for (i, (lang, region)) in all_codes.iter().enumerate() {
+            write!(
+                f,
+                r#"
+                {} = {},
+                "#,
+                variant(lang, *region),
+                i,
+            )?;
+        }
+
+        f.dedent(1);
+        write!(
+            f,
+            r#"
+            }}
+
+            impl From<Lang> for LangCode {{
+                fn from(lang: Lang) -> Self {{
+                    #[allow(unreachable_patterns)]
+                    match lang {{
+            "#,
+        )?;
+        f.indent(3);
 
-        for (lang, region) in &sorted_languages {
+        // the regions go first, the unknown regions use the code of the language
+        let mut arms: Vec<_> = all_codes.iter().collect();
+        arms.sort_by_key(|(lang, region)| (*lang, region.is_none()));
+        for (lang, region) in arms {
             write!(
                 f,
                 r#"
-                &Lang::{}({:?}),
+                {} => LangCode::{},
                 "#,
-                lang,
-                region.as_deref().unwrap_or(""),
+                self.lang_pattern(lang, *region),
+                variant(lang, *region),
             )?;
         }
 
@@ -329,70 +5882,54 @@ impl fmt::Display for TwineFormatter {
         write!(
             f,
             r#"
-                    ]
-                }}
-            }}
-            "#,
-        )?;
-
-        f.dedent(3);
-        write!(
-            f,
-            r#"
-            impl std::fmt::Display for Lang {{
-                fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {{
-                    write!(f, "{{}}", self.language())?;
-                    let region = self.region();
-                    if !region.is_empty() {{
-                        write!(f, "_{{}}", region)?;
                     }}
-                    Ok(())
                 }}
             }}
-            "#,
-        )?;
 
-        f.dedent(3);
-        write!(
-            f,
-            r#"
-            impl Lang {{
-                pub fn language(&self) -> &'static str {{
-                    match self {{
+            impl From<LangCode> for Lang {{
+                fn from(code: LangCode) -> Self {{
+                    match code {{
             "#,
         )?;
-
         f.indent(3);
-        for lang in &lang_variants {
+
+        for (lang, region) in all_codes {
             write!(
                 f,
                 r#"
-                Lang::{}(_) => {:?},
+                LangCode::{} => {},
                 "#,
-                lang,
-                lang.to_snake_case(),
+                variant(lang, *region),
+                self.lang_value(lang, *region),
             )?;
         }
 
-        f.dedent(2);
+        f.dedent(3);
         write!(
             f,
             r#"
                     }}
                 }}
+            }}
 
-                pub fn region(&self) -> &str {{
-                    match self {{
+            impl {core}::convert::TryFrom<u16> for LangCode {{
+                type Error = u16;
+
+                fn try_from(code: u16) -> Result<Self, Self::Error> {{
+                    match code {{
             "#,
+            core = self.core(),
         )?;
+        f.indent(3);
 
-        f.indent(2);
-        for lang in &lang_variants {
+        for (i, (lang, region)) in all_codes.iter().enumerate() {
             write!(
                 f,
                 r#"
-                Lang::{lang}(region) => region,
+                {} => Ok(LangCode::{}),
                 "#,
+                i,
+                variant(lang, *region),
             )?;
         }
 
@@ -400,129 +5937,201 @@ impl fmt::Display for TwineFormatter {
         write!(
             f,
             r#"
+                        code => Err(code),
                     }}
                 }}
             }}
             "#,
         )?;
 
-        #[cfg(feature = "serde")]
-        {
-            let mut all_regions: Vec<_> = all_languages
-                .iter()
-                .filter_map(|(_, region)| region.as_deref())
-                .collect();
-            all_regions.sort_unstable_by(|a, b| a.cmp(b).reverse());
-            Self::generate_serde(&mut f, &lang_variants, &all_regions)?;
-        }
-
         Ok(())
     }
-}
 
-impl TwineFormatter {
-    #[allow(clippy::single_char_add_str)]
-    fn generate_match_arms(
-        &self,
-        f: &mut CodeFormatter<fmt::Formatter>,
-        translations: &[(String, String)],
-        all_languages: &mut HashSet<(String, Option<String>)>,
-    ) -> fmt::Result {
-        let mut match_arms = Vec::new();
-        let mut default_out = None;
-        for (lang, text) in translations {
-            // transform all printf's format placeholder to Rust's format
-            let mut out = String::new();
-            for caps in RE_PRINTF.captures_iter(text.as_str()) {
-                if let Some(type_) = caps.name("type") {
-                    out.push_str("{");
-                    if let Some(parameter) = caps.name("parameter") {
-                        let parameter: usize = parameter
-                            .as_str()
-                            .parse()
-                            .expect("could not parse parameter index");
-                        write!(out, "{}", parameter.saturating_sub(1))?;
-                    }
-                    out.push_str(":");
-                    if let Some(flag) = caps.name("flags") {
-                        out.push_str(flag.as_str());
-                    }
-                    if let Some(width) = caps.name("width") {
-                        out.push_str(width.as_str());
-                    }
-                    if let Some(precision) = caps.name("precision") {
-                        out.push_str(precision.as_str());
-                    }
-                    match type_.as_str() {
-                        x @ "x" | x @ "X" => out.push_str(x),
-                        _ => {}
-                    }
-                    out.push_str("}");
-                } else if &caps[0] == "%%" {
-                    out.push_str("%");
-                } else if &caps[0] == "\"" {
-                    out.push_str("\\\"");
-                } else if let Some(unicode) = caps.name("unicode") {
-                    out.push_str(r"\u{");
-                    out.push_str(unicode.as_str());
-                    out.push_str(r"}");
-                } else {
-                    out.push_str(&caps[0]);
-                }
-            }
+    // turns all the keys into snake case automatically
+    fn normalize_key(key: &str) -> String {
+        case::to_snake_case(key).replace('.', "__")
+    }
 
-            if default_out.is_none() {
-                default_out = Some(out.clone());
-            }
+    // the name of the typed function of a key: a raw identifier for the keywords (`r#type`)
+    fn function_name(key: &str) -> String {
+        let name = Self::normalize_key(key);
+        if KEYWORDS.contains(&name.as_str()) {
+            format!("r#{name}")
+        } else {
+            name
+        }
+    }
 
-            // parse the language and region, then push the match arm
-            let caps = RE_LANG.captures(lang.as_str()).expect("lang can be parsed");
-            let lang = caps
-                .get(1)
-                .expect("the language is always there")
-                .as_str()
-                .to_upper_camel_case();
-            let region = caps.get(3);
-            all_languages.insert((lang.clone(), region.map(|x| x.as_str().to_string())));
-            match_arms.push((lang, region.map(|x| format!("{:?}", x.as_str())), out));
-        }
-        match_arms.sort_unstable_by(|(a_lang, a_region, _), (b_lang, b_region, _)| {
-            a_lang
-                .cmp(b_lang)
-                .then(a_region.is_none().cmp(&b_region.is_none()))
-        });
+    #[cfg(feature = "current-lang")]
+    fn generate_current_lang(f: &mut CodeFormatter<fmt::Formatter>) -> fmt::Result {
+        write!(
+            f,
+            r#"
 
-        for (lang, region, format) in match_arms {
-            write!(
-                f,
-                r#"
-                $crate::Lang::{}({}) => format!("{}" $(, $fmt_args)*),
-                "#,
-                lang,
-                region.as_deref().unwrap_or("_"),
-                format,
-            )?;
-        }
+            thread_local! {{
+                static CURRENT_LANG: std::cell::Cell<Option<Lang>> = const {{ std::cell::Cell::new(None) }};
+            }}
 
-        if let Some(default_out) = default_out {
-            write!(
-                f,
-                r#"
-                _ => format!("{default_out}" $(, $fmt_args)*),
-                "#,
-            )?;
-        }
+            /// Set the current language of the current thread, used by the macro `tl!`.
+            #[allow(dead_code)]
+            pub fn set_lang(lang: Lang) {{
+                CURRENT_LANG.with(|x| x.set(Some(lang)));
+            }}
 
-        Ok(())
+            /// The current language of the current thread: the first language of
+            /// `Lang::all_languages()` until `set_lang()` is called.
+            #[allow(dead_code)]
+            pub fn get_lang() -> Lang {{
+                CURRENT_LANG
+                    .with(|x| x.get())
+                    .unwrap_or(*Lang::all_languages()[0])
+            }}
+
+            thread_local! {{
+                static DEFAULT_LANG: std::cell::Cell<Option<Lang>> = const {{ std::cell::Cell::new(None) }};
+            }}
+
+            #[allow(dead_code)]
+            impl Lang {{
+                /// Set the default language of the current thread, used by the macro `t_default!`
+                /// and `Lang::default()`. It does not change the current language of `tl!`.
+                pub fn set_default(lang: Lang) {{
+                    DEFAULT_LANG.with(|x| x.set(Some(lang)));
+                }}
+
+                /// The default language of the current thread: the first language of
+                /// `Lang::all_languages()` until `Lang::set_default()` is called.
+                pub fn get_default() -> Lang {{
+                    DEFAULT_LANG
+                        .with(|x| x.get())
+                        .unwrap_or(*Lang::all_languages()[0])
+                }}
+            }}
+
+            /// The default language of the current thread, see `Lang::get_default()`.
+            impl Default for Lang {{
+                fn default() -> Self {{
+                    Lang::get_default()
+                }}
+            }}
+            "#,
+        )
     }
 
-    // turns all the keys into snake case automatically
-    fn normalize_key(key: &str) -> String {
-        key.to_snake_case().replace('.', "__")
+    // `t_bytes!()`: the translation as UTF-8 bytes, returned or written into a `Vec<u8>`
+    fn generate_bytes_macro(&self, f: &mut CodeFormatter<fmt::Formatter>) -> fmt::Result {
+        self.generate_macro_start(f, "t_bytes")?;
+        write!(
+            f,
+            r#"
+                (&mut $buf:expr, $($tokens:tt)*) => {{{{
+                    struct Bytes<'a>(&'a mut {vec}<u8>);
+
+                    impl {core}::fmt::Write for Bytes<'_> {{
+                        fn write_str(&mut self, s: &str) -> {core}::fmt::Result {{
+                            self.0.extend_from_slice(s.as_bytes());
+                            Ok(())
+                        }}
+                    }}
+
+                    let mut bytes = Bytes(&mut $buf);
+                    {items_path}t_into!(bytes, $($tokens)*)
+                }}}};
+                ($($tokens:tt)*) => {{
+                    {items_path}t!($($tokens)*).into_bytes()
+                }};
+            "#,
+            vec = if self.no_std {
+                "::alloc::vec::Vec"
+            } else {
+                "::std::vec::Vec"
+            },
+            core = self.core(),
+            items_path = self.items_path(),
+        )?;
+        self.generate_macro_end(f, "t_bytes")
+    }
+
+    #[cfg(feature = "current-lang")]
+    fn generate_current_lang_macro(&self, f: &mut CodeFormatter<fmt::Formatter>) -> fmt::Result {
+        self.generate_macro_start(f, "tl")?;
+        write!(
+            f,
+            r#"
+                ($key:ident $($tokens:tt)*) => {{
+                    {items_path}t!($key $($tokens)* => {items_path}get_lang())
+                }};
+            "#,
+            items_path = self.items_path(),
+        )?;
+        self.generate_macro_end(f, "tl")?;
+
+        self.generate_macro_start(f, "t_default")?;
+        write!(
+            f,
+            r#"
+                ($key:ident $($tokens:tt)*) => {{
+                    {items_path}t!($key $($tokens)* => {items_path}Lang::get_default())
+                }};
+            "#,
+            items_path = self.items_path(),
+        )?;
+        self.generate_macro_end(f, "t_default")
+    }
+
+    #[cfg(feature = "gettext")]
+    fn generate_gettext(f: &mut CodeFormatter<fmt::Formatter>) -> fmt::Result {
+        write!(
+            f,
+            r#"
+
+            /// Set the language used by `gettext()` and `ngettext()` in the current thread.
+            ///
+            /// This is the same as `set_lang()`.
+            #[allow(dead_code)]
+            pub fn set_current_lang(lang: Lang) {{
+                set_lang(lang);
+            }}
+
+            /// The language used by `gettext()` and `ngettext()` in the current thread.
+            #[allow(dead_code)]
+            pub fn current_lang() -> Option<Lang> {{
+                CURRENT_LANG.with(|x| x.get())
+            }}
+
+            /// Returns the raw translation of a key in the current language, or the key itself if
+            /// the key or the language is unknown.
+            ///
+            /// The escape sequences of the translation are decoded but the printf's placeholders
+            /// are kept. The category "other" of the plural keys is used.
+            #[allow(dead_code)]
+            pub fn gettext(key: &str) -> &str {{
+                match current_lang() {{
+                    Some(lang) => translate(key, lang).unwrap_or(key),
+                    None => key,
+                }}
+            }}
+
+            /// Returns the translation of `singular_key` if `n` is in the category "one" of the
+            /// current language, the translation of `plural_key` otherwise.
+            ///
+            /// See `gettext()`.
+            #[allow(dead_code)]
+            pub fn ngettext<'a>(singular_key: &'a str, plural_key: &'a str, n: i64) -> &'a str {{
+                match current_lang().map(|lang| lang.plural_category(n)) {{
+                    Some(PluralCategory::One) => gettext(singular_key),
+                    Some(_) => gettext(plural_key),
+                    None if n == 1 => singular_key,
+                    None => plural_key,
+                }}
+            }}
+            "#,
+        )
     }
 
     #[cfg(feature = "serde")]
     fn generate_serde(
+        &self,
         f: &mut CodeFormatter<fmt::Formatter>,
         all_languages: &[&str],
         all_regions: &[&str],
@@ -537,7 +6146,35 @@ impl TwineFormatter {
                     D: serde::Deserializer<'de>,
                 {{
                     use serde::de;
-                    use std::fmt;
+                    use {core}::fmt;{alloc}
+
+                    // Levenshtein distance between two strings
+                    fn distance(a: &str, b: &str) -> usize {{
+                        let b: Vec<char> = b.chars().collect();
+                        let mut row: Vec<usize> = (0..=b.len()).collect();
+                        for (i, a) in a.chars().enumerate() {{
+                            let mut previous = row[0];
+                            row[0] = i + 1;
+                            for (j, b) in b.iter().enumerate() {{
+                                let current = row[j + 1];
+                                row[j + 1] = if a == *b {{
+                                    previous
+                                }} else {{
+                                    1 + previous.min(row[j]).min(current)
+                                }};
+                                previous = current;
+                            }}
+                        }}
+                        row[b.len()]
+                    }}
+
+                    // suggest the closest valid value in the error message
+                    fn expected(what: &str, value: &str, valid: &[&str]) -> String {{
+                        match valid.iter().min_by_key(|x| distance(value, x)) {{
+                            Some(closest) => format!("{{}} (did you mean '{{}}'?)", what, closest),
+                            None => what.to_string(),
+                        }}
+                    }}
 
                     struct LangVisitor;
 
@@ -555,50 +6192,38 @@ impl TwineFormatter {
                         {{
                             let mut it = value.splitn(2, '_');
                             let lang = it.next().unwrap();
-                            let region = it.next().unwrap_or("");
-
-                            let region = match region.to_lowercase().as_str() {{
             "#,
+            core = self.core(),
+            alloc = if self.no_std {
+                "\n                    use ::alloc::{format, string::{String, ToString}, vec::Vec};"
+            } else {
+                ""
+            },
         )?;
-        f.indent(5);
+        f.indent(4);
 
-        for region in all_regions {
+        // the region is dropped
+        if self.ignore_regions {
             write!(
                 f,
                 r#"
-                {region:?} => {region:?},
-                "#,
-                region = region,
-            )?;
-        }
-
-        f.dedent(1);
-        write!(
-            f,
-            r#"
-                "" => "",
-                _ => {{
-                    return Err(de::Error::invalid_value(
-                        de::Unexpected::Str(region),
-                        &"existing region",
-                    ));
-                }}
-            }};
-
-            match lang {{
-            "#,
-        )?;
-        f.indent(1);
 
-        for lang in all_languages {
-            write!(
-                f,
-                r#"
-                {:?} => Ok(Lang::{}(region)),
+                match lang {{
                 "#,
-                lang.to_snake_case(),
-                lang,
             )?;
+            f.indent(1);
+            for lang in all_languages {
+                write!(
+                    f,
+                    r#"
+                    {:?} => Ok(Lang::{}),
+                    "#,
+                    lang.to_snake_case(),
+                    lang,
+                )?;
+            }
+        } else {
+            self.generate_serde_region(f, all_languages, all_regions)?;
         }
 
         f.dedent(5);
@@ -606,8 +6231,8 @@ impl TwineFormatter {
             f,
             r#"
                                 _ => Err(de::Error::invalid_value(
-                                    de::Unexpected::Str(region),
-                                    &"existing language",
+                                    de::Unexpected::Str(lang),
+                                    &expected("existing language", lang, &[{languages}]).as_str(),
                                 )),
                             }}
                         }}
@@ -624,18 +6249,44 @@ impl TwineFormatter {
                 {{
                     match self {{
             "#,
+            languages = all_languages
+                .iter()
+                .map(|x| format!("{:?}", x.to_snake_case()))
+                .collect::<Vec<_>>()
+                .join(", "),
         )?;
         f.indent(3);
 
         for lang in all_languages {
+            if self.ignore_regions {
+                write!(
+                    f,
+                    r#"
+                    Lang::{} => serializer.serialize_str({:?}),
+                    "#,
+                    lang,
+                    lang.to_snake_case(),
+                )?;
+                continue;
+            }
             write!(
                 f,
                 r#"
                 Lang::{variant}("") => serializer.serialize_str({lang:?}),
                 Lang::{variant}(region) => serializer.serialize_str(
-                    &format!("{{}}_{{}}", {lang:?}, region),
+                    &{format}("{{}}_{{}}", {lang:?}, {region}),
                 ),
                 "#,
+                region = if self.uppercase_regions {
+                    "region.to_ascii_uppercase()"
+                } else {
+                    "region"
+                },
+                format = if self.no_std {
+                    "::alloc::format!"
+                } else {
+                    "format!"
+                },
                 variant = lang,
                 lang = lang.to_snake_case(),
             )?;
@@ -653,4 +6304,69 @@ impl TwineFormatter {
 
         Ok(())
     }
+
+    // the arms of the languages of the deserialization, with the region checked first
+    #[cfg(feature = "serde")]
+    fn generate_serde_region(
+        &self,
+        f: &mut CodeFormatter<fmt::Formatter>,
+        all_languages: &[&str],
+        all_regions: &[&str],
+    ) -> fmt::Result {
+        write!(
+            f,
+            r#"
+            let region = it.next().unwrap_or("");
+
+            let region = match region.to_lowercase().as_str() {{
+            "#,
+        )?;
+        f.indent(1);
+
+        for region in all_regions {
+            write!(
+                f,
+                r#"
+                {region:?} => {region:?},
+                "#,
+                region = region,
+            )?;
+        }
+
+        f.dedent(1);
+        write!(
+            f,
+            r#"
+                "" => "",
+                _ => {{
+                    return Err(de::Error::invalid_value(
+                        de::Unexpected::Str(region),
+                        &expected("existing region", region, &[{regions}]).as_str(),
+                    ));
+                }}
+            }};
+
+            match lang {{
+            "#,
+            regions = all_regions
+                .iter()
+                .map(|x| format!("{:?}", x))
+                .collect::<Vec<_>>()
+                .join(", "),
+        )?;
+        f.indent(1);
+
+        for lang in all_languages {
+            write!(
+                f,
+                r#"
+                {:?} => Ok(Lang::{}(region)),
+                "#,
+                lang.to_snake_case(),
+                lang,
+            )?;
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,57 @@
+// Locale-aware numbers: the separators of the thousands and of the decimals by language, used by
+// the placeholders `%d` and `%f` when the option `locale_numbers` of the `Builder` is enabled.
+
+// (language or language-region, thousands separator, decimal separator), the regions come first
+static SEPARATORS: &[(&str, &str, &str)] = &[
+    ("de-ch", "’", "."),
+    ("de-li", "’", "."),
+    ("fr-ch", "\u{202f}", "."),
+    ("bg", "\u{a0}", ","),
+    ("cs", "\u{a0}", ","),
+    ("da", ".", ","),
+    ("de", ".", ","),
+    ("el", ".", ","),
+    ("en", ",", "."),
+    ("es", ".", ","),
+    ("et", "\u{a0}", ","),
+    ("fi", "\u{a0}", ","),
+    ("fr", "\u{202f}", ","),
+    ("he", ",", "."),
+    ("hr", ".", ","),
+    ("hu", "\u{a0}", ","),
+    ("id", ".", ","),
+    ("it", ".", ","),
+    ("ja", ",", "."),
+    ("ko", ",", "."),
+    ("lt", "\u{a0}", ","),
+    ("lv", "\u{a0}", ","),
+    ("nb", "\u{a0}", ","),
+    ("nl", ".", ","),
+    ("nn", "\u{a0}", ","),
+    ("no", "\u{a0}", ","),
+    ("pl", "\u{a0}", ","),
+    ("pt", ".", ","),
+    ("ro", ".", ","),
+    ("ru", "\u{a0}", ","),
+    ("sk", "\u{a0}", ","),
+    ("sl", ".", ","),
+    ("sr", ".", ","),
+    ("sv", "\u{a0}", ","),
+    ("th", ",", "."),
+    ("tr", ".", ","),
+    ("uk", "\u{a0}", ","),
+    ("zh", ",", "."),
+];
+
+/// The separators of the thousands and of the decimals of a language as written in the INI file
+/// (`de`, `de-ch`, `de-ch.one`), `None` if the language is unknown.
+pub(crate) fn separators(lang: &str) -> Option<(&'static str, &'static str)> {
+    let lang = lang.split('.').next().unwrap_or(lang).to_ascii_lowercase();
+    let language = lang.split('-').next().unwrap_or(&lang);
+
+    SEPARATORS
+        .iter()
+        .find(|(x, _, _)| *x == lang)
+        .or_else(|| SEPARATORS.iter().find(|(x, _, _)| *x == language))
+        .map(|(_, thousands, decimal)| (*thousands, *decimal))
+}
@@ -0,0 +1,196 @@
+// CLDR plural rules restricted to integer operands
+// see: https://unicode-org.github.io/cldr-staging/charts/latest/supplemental/language_plural_rules.html
+//
+// Every rule is a list of conditions on `n` (the absolute value of the count, as `u64`) tried in
+// order, the category `Other` being used when none of them match.
+
+pub(crate) type Rules = &'static [(&'static str, &'static str)];
+
+static CARDINAL_RULES: &[(&[&str], Rules)] = &[
+    (
+        &[
+            "bo", "dz", "id", "ig", "ja", "jv", "km", "ko", "lo", "ms", "my", "sg", "th", "to",
+            "vi", "yo", "zh",
+        ],
+        &[],
+    ),
+    (
+        &[
+            "af", "az", "bg", "da", "de", "el", "en", "et", "eu", "fi", "fy", "gl", "hu", "ka",
+            "kk", "ky", "lb", "mn", "nb", "nl", "nn", "no", "sq", "sv", "sw", "ta", "te", "tr",
+            "ur", "uz",
+        ],
+        &[("One", "n == 1")],
+    ),
+    (
+        &["am", "bn", "fa", "gu", "hi", "hy", "kn", "zu"],
+        &[("One", "n <= 1")],
+    ),
+    (
+        &["ca", "es", "it"],
+        &[("One", "n == 1"), ("Many", "n != 0 && n % 1_000_000 == 0")],
+    ),
+    (
+        &["fr", "pt"],
+        &[("One", "n <= 1"), ("Many", "n != 0 && n % 1_000_000 == 0")],
+    ),
+    (
+        &["be", "ru", "uk"],
+        &[
+            ("One", "n % 10 == 1 && n % 100 != 11"),
+            (
+                "Few",
+                "(2..=4).contains(&(n % 10)) && !(12..=14).contains(&(n % 100))",
+            ),
+            ("Many", "true"),
+        ],
+    ),
+    (
+        &["pl"],
+        &[
+            ("One", "n == 1"),
+            (
+                "Few",
+                "(2..=4).contains(&(n % 10)) && !(12..=14).contains(&(n % 100))",
+            ),
+            ("Many", "true"),
+        ],
+    ),
+    (
+        &["bs", "hr", "sh", "sr"],
+        &[
+            ("One", "n % 10 == 1 && n % 100 != 11"),
+            (
+                "Few",
+                "(2..=4).contains(&(n % 10)) && !(12..=14).contains(&(n % 100))",
+            ),
+        ],
+    ),
+    (
+        &["cs", "sk"],
+        &[("One", "n == 1"), ("Few", "(2..=4).contains(&n)")],
+    ),
+    (
+        &["lt"],
+        &[
+            ("One", "n % 10 == 1 && !(11..=19).contains(&(n % 100))"),
+            ("Few", "n % 10 >= 2 && !(11..=19).contains(&(n % 100))"),
+        ],
+    ),
+    (
+        &["lv"],
+        &[
+            ("Zero", "n % 10 == 0 || (11..=19).contains(&(n % 100))"),
+            ("One", "n % 10 == 1 && n % 100 != 11"),
+        ],
+    ),
+    (
+        &["ro"],
+        &[
+            ("One", "n == 1"),
+            ("Few", "n == 0 || (1..=19).contains(&(n % 100))"),
+        ],
+    ),
+    (
+        &["sl"],
+        &[
+            ("One", "n % 100 == 1"),
+            ("Two", "n % 100 == 2"),
+            ("Few", "(3..=4).contains(&(n % 100))"),
+        ],
+    ),
+    (&["he", "iw"], &[("One", "n == 1"), ("Two", "n == 2")]),
+    (
+        &["ar"],
+        &[
+            ("Zero", "n == 0"),
+            ("One", "n == 1"),
+            ("Two", "n == 2"),
+            ("Few", "(3..=10).contains(&(n % 100))"),
+            ("Many", "(11..=99).contains(&(n % 100))"),
+        ],
+    ),
+    (
+        &["ga"],
+        &[
+            ("One", "n == 1"),
+            ("Two", "n == 2"),
+            ("Few", "(3..=6).contains(&n)"),
+            ("Many", "(7..=10).contains(&n)"),
+        ],
+    ),
+    (
+        &["cy"],
+        &[
+            ("Zero", "n == 0"),
+            ("One", "n == 1"),
+            ("Two", "n == 2"),
+            ("Few", "n == 3"),
+            ("Many", "n == 6"),
+        ],
+    ),
+];
+
+// languages that are not in the table use the English rule
+static DEFAULT_CARDINAL_RULE: Rules = &[("One", "n == 1")];
+
+static ORDINAL_RULES: &[(&[&str], Rules)] = &[
+    (
+        &["en"],
+        &[
+            ("One", "n % 10 == 1 && n % 100 != 11"),
+            ("Two", "n % 10 == 2 && n % 100 != 12"),
+            ("Few", "n % 10 == 3 && n % 100 != 13"),
+        ],
+    ),
+    (
+        &["fr", "ga", "hy", "lo", "ms", "ro", "vi"],
+        &[("One", "n == 1")],
+    ),
+    (
+        &["sv"],
+        &[(
+            "One",
+            "(1..=2).contains(&(n % 10)) && !(11..=12).contains(&(n % 100))",
+        )],
+    ),
+    (&["hu"], &[("One", "n == 1 || n == 5")]),
+    (
+        &["ca"],
+        &[
+            ("One", "n == 1 || n == 3"),
+            ("Two", "n == 2"),
+            ("Few", "n == 4"),
+        ],
+    ),
+    (
+        &["it"],
+        &[("Many", "n == 11 || n == 8 || n == 80 || n == 800")],
+    ),
+];
+
+// languages that are not in the table only have the category `Other`
+static DEFAULT_ORDINAL_RULE: Rules = &[];
+
+/// Returns the cardinal plural rule of a language.
+pub(crate) fn cardinal_rule(lang: &str) -> Rules {
+    find_rule(CARDINAL_RULES, lang).unwrap_or(DEFAULT_CARDINAL_RULE)
+}
+
+/// Returns the ordinal plural rule of a language.
+pub(crate) fn ordinal_rule(lang: &str) -> Rules {
+    find_rule(ORDINAL_RULES, lang).unwrap_or(DEFAULT_ORDINAL_RULE)
+}
+
+fn find_rule(rules: &[(&[&str], Rules)], lang: &str) -> Option<Rules> {
+    rules
+        .iter()
+        .find(|(langs, _)| langs.contains(&lang))
+        .map(|(_, rule)| *rule)
+}
+
+/// Returns `true` if the name is a plural category: `zero`, `one`, `two`, `few`, `many` or
+/// `other`.
+pub(crate) fn is_category(name: &str) -> bool {
+    matches!(name, "zero" | "one" | "two" | "few" | "many" | "other")
+}
@@ -0,0 +1,217 @@
+// Scanner of the values: splits a value in a single pass into literal text, printf's format
+// placeholders and escape sequences.
+//
+// The placeholders supported are `%[parameter$][flags][width][.precision]type` with the flags `-`,
+// `+` and `#` and the types `d`, `i`, `s`, `@`, `x`, `X` and `f`.
+// see: https://docs.microsoft.com/en-us/cpp/c-runtime-library/format-specification-syntax-printf-and-wprintf-functions?view=msvc-160
+
+/// A printf's format placeholder: `%1$-5.2f`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Placeholder<'a> {
+    /// The position of the argument (`1` in `%1$s`).
+    pub(crate) parameter: Option<&'a str>,
+    pub(crate) flags: Option<&'a str>,
+    pub(crate) width: Option<&'a str>,
+    /// The precision with its dot (`.2`).
+    pub(crate) precision: Option<&'a str>,
+    pub(crate) type_: &'a str,
+}
+
+/// A fragment of a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Fragment<'a> {
+    Placeholder(&'a str, Placeholder<'a>),
+    /// `\u00e9` with its hexadecimal digits.
+    Unicode(&'a str, &'a str),
+    /// Any other escape sequence: `\n`, `\"`, ...
+    Escape(&'a str),
+    /// `%%`
+    PercentPercent,
+    /// A `%` that does not start a placeholder.
+    Percent,
+    /// `"`
+    Quote,
+    /// Literal text without `%`, `"` and `\`.
+    Text(&'a str),
+}
+
+impl<'a> Fragment<'a> {
+    /// The fragment as written in the value.
+    pub(crate) fn as_str(&self) -> &'a str {
+        match self {
+            Fragment::Placeholder(source, _)
+            | Fragment::Unicode(source, _)
+            | Fragment::Escape(source)
+            | Fragment::Text(source) => source,
+            Fragment::PercentPercent => "%%",
+            Fragment::Percent => "%",
+            Fragment::Quote => "\"",
+        }
+    }
+}
+
+/// Returns an iterator over the fragments of a value.
+///
+/// A backslash at the end of the value is dropped.
+pub(crate) fn fragments(text: &str) -> Fragments<'_> {
+    Fragments { text, pos: 0 }
+}
+
+/// Returns the `%`-sequences of a value that look like placeholders but are not valid: a `%`
+/// followed by a letter, a digit or a dot (`%z`, `%5`, `%.f`) and `%@` followed by a letter
+/// (`%@s`). The other `%` are literal (`50 %`, `%.0f%`).
+pub(crate) fn invalid_placeholders(text: &str) -> Vec<String> {
+    let mut invalid = Vec::new();
+    let mut fragments = fragments(text).peekable();
+    while let Some(fragment) = fragments.next() {
+        let next = match fragments.peek() {
+            Some(Fragment::Text(next)) => *next,
+            _ => continue,
+        };
+        let next_char = next.chars().next().unwrap_or(' ');
+        let sequence_end = match fragment {
+            Fragment::Percent if next_char.is_ascii_alphanumeric() || next_char == '.' => next
+                .find(|c: char| !c.is_ascii_alphanumeric() && c != '.')
+                .unwrap_or(next.len()),
+            Fragment::Placeholder(_, placeholder)
+                if placeholder.type_ == "@" && next_char.is_ascii_alphabetic() =>
+            {
+                1
+            }
+            _ => continue,
+        };
+        invalid.push(format!("{}{}", fragment.as_str(), &next[..sequence_end]));
+    }
+    invalid
+}
+
+pub(crate) struct Fragments<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Fragments<'a> {
+    fn digits(&self, start: usize) -> usize {
+        self.text.as_bytes()[start.min(self.text.len())..]
+            .iter()
+            .take_while(|x| x.is_ascii_digit())
+            .count()
+    }
+
+    // parse the placeholder starting at `start` (on the `%`), returns its end
+    fn placeholder(&self, start: usize) -> Option<(usize, Placeholder<'a>)> {
+        let text = self.text;
+        let bytes = text.as_bytes();
+        let mut i = start + 1;
+
+        let n = self.digits(i);
+        let parameter = if n > 0 && bytes.get(i + n) == Some(&b'$') {
+            i += n + 1;
+            Some(&text[i - n - 1..i - 1])
+        } else {
+            None
+        };
+        let flags = match bytes.get(i) {
+            Some(b'-') | Some(b'+') | Some(b'#') => {
+                i += 1;
+                Some(&text[i - 1..i])
+            }
+            _ => None,
+        };
+        let n = self.digits(i);
+        let width = if n > 0 {
+            i += n;
+            Some(&text[i - n..i])
+        } else {
+            None
+        };
+        let precision = if bytes.get(i) == Some(&b'.') {
+            let n = self.digits(i + 1);
+            if n == 0 {
+                return None;
+            }
+            i += n + 1;
+            Some(&text[i - n - 1..i])
+        } else {
+            None
+        };
+        match bytes.get(i) {
+            Some(b'd') | Some(b'i') | Some(b's') | Some(b'@') | Some(b'x') | Some(b'X')
+            | Some(b'f') => {
+                i += 1;
+            }
+            _ => return None,
+        }
+
+        Some((
+            i,
+            Placeholder {
+                parameter,
+                flags,
+                width,
+                precision,
+                type_: &text[i - 1..i],
+            },
+        ))
+    }
+}
+
+impl<'a> Iterator for Fragments<'a> {
+    type Item = Fragment<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let text = self.text;
+        let bytes = text.as_bytes();
+        loop {
+            let start = self.pos;
+            let byte = *bytes.get(start)?;
+            match byte {
+                b'%' => {
+                    if let Some((end, placeholder)) = self.placeholder(start) {
+                        self.pos = end;
+                        return Some(Fragment::Placeholder(&text[start..end], placeholder));
+                    }
+                    if bytes.get(start + 1) == Some(&b'%') {
+                        self.pos = start + 2;
+                        return Some(Fragment::PercentPercent);
+                    }
+                    self.pos = start + 1;
+                    return Some(Fragment::Percent);
+                }
+                b'"' => {
+                    self.pos = start + 1;
+                    return Some(Fragment::Quote);
+                }
+                b'\\' => {
+                    let is_unicode = bytes.get(start + 1) == Some(&b'u')
+                        && bytes
+                            .get(start + 2..start + 6)
+                            .map(|x| x.iter().all(u8::is_ascii_hexdigit))
+                            .unwrap_or(false);
+                    if is_unicode {
+                        self.pos = start + 6;
+                        return Some(Fragment::Unicode(
+                            &text[start..start + 6],
+                            &text[start + 2..start + 6],
+                        ));
+                    }
+                    match text[start + 1..].chars().next() {
+                        Some(c) if c != '\n' => {
+                            self.pos = start + 1 + c.len_utf8();
+                            return Some(Fragment::Escape(&text[start..self.pos]));
+                        }
+                        // nothing to escape: the backslash is dropped
+                        _ => self.pos = start + 1,
+                    }
+                }
+                _ => {
+                    self.pos = bytes[start..]
+                        .iter()
+                        .position(|x| matches!(x, b'%' | b'"' | b'\\'))
+                        .map_or(text.len(), |i| start + i);
+                    return Some(Fragment::Text(&text[start..self.pos]));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,94 @@
+// Pseudolocalization: a fake language derived from the default one to test the layout of the user
+// interface with accented and longer texts, without waiting for the real translations.
+
+use crate::printf::{self, Fragment};
+
+static ACCENTED: &[(char, char)] = &[
+    ('A', 'Å'),
+    ('C', 'Ç'),
+    ('D', 'Ð'),
+    ('E', 'É'),
+    ('G', 'Ĝ'),
+    ('H', 'Ĥ'),
+    ('I', 'Î'),
+    ('J', 'Ĵ'),
+    ('K', 'Ķ'),
+    ('L', 'Ļ'),
+    ('N', 'Ñ'),
+    ('O', 'Ö'),
+    ('R', 'Ŕ'),
+    ('S', 'Š'),
+    ('T', 'Ţ'),
+    ('U', 'Û'),
+    ('W', 'Ŵ'),
+    ('Y', 'Ý'),
+    ('Z', 'Ž'),
+    ('a', 'å'),
+    ('c', 'ç'),
+    ('d', 'ð'),
+    ('e', 'é'),
+    ('g', 'ĝ'),
+    ('h', 'ĥ'),
+    ('i', 'î'),
+    ('j', 'ĵ'),
+    ('k', 'ķ'),
+    ('l', 'ļ'),
+    ('n', 'ñ'),
+    ('o', 'ö'),
+    ('r', 'ŕ'),
+    ('s', 'š'),
+    ('t', 'ţ'),
+    ('u', 'û'),
+    ('w', 'ŵ'),
+    ('y', 'ý'),
+    ('z', 'ž'),
+];
+
+/// Accents the letters of a translation and pads it by roughly 30% with `!`, the whole being
+/// surrounded by brackets: `Hello World` becomes `[Ĥéļļö Ŵöŕļð!!!!]`.
+///
+/// The printf's placeholders, the escape sequences, the named arguments (`{name}`) and the count
+/// shorthand of the plurals (`#`) are kept untouched.
+pub(crate) fn pseudolocalize(text: &str) -> String {
+    let mut out = String::from("[");
+    let mut letters = 0;
+    for fragment in printf::fragments(text) {
+        let text = match fragment {
+            Fragment::Text(text) => text,
+            fragment => {
+                out.push_str(fragment.as_str());
+                continue;
+            }
+        };
+        let mut in_argument = false;
+        for c in text.chars() {
+            match c {
+                '{' => in_argument = true,
+                '}' => in_argument = false,
+                _ if in_argument => {}
+                _ => {
+                    if c.is_alphanumeric() {
+                        letters += 1;
+                    }
+                    out.push(accent(c));
+                    continue;
+                }
+            }
+            out.push(c);
+        }
+    }
+    for _ in 0..(letters * 3 + 9) / 10 {
+        out.push('!');
+    }
+    out.push(']');
+
+    out
+}
+
+fn accent(c: char) -> char {
+    ACCENTED
+        .iter()
+        .find(|(plain, _)| *plain == c)
+        .map(|(_, accented)| *accented)
+        .unwrap_or(c)
+}
@@ -0,0 +1,176 @@
+// Reader of the translations written in TOML: every table is a key and its string values are the
+// translations, the dotted keys and the inline tables give the plural categories
+// (`en.one = "..."` or `en = { one = "...", other = "..." }`).
+//
+// The document is parsed by the crate `toml`, then its tables are mapped to the sections of the
+// Twine INI format: the strings are written back with the escape sequences of the INI format. A
+// table containing only plural categories is a plural translation, the other nested tables are
+// keys (`[errors.not_found]` is the key `errors.not_found`).
+
+use crate::{
+    expand_regions, lang_error, plural, position_error, Section, TwineData, DEFAULTS_SECTION,
+    SCHEMA_SECTION,
+};
+use ::toml::{Table, Value};
+use std::collections::HashMap;
+use std::io;
+
+/// Read the translations of a TOML document.
+pub(crate) fn read_toml(text: &str) -> io::Result<TwineData> {
+    let document: Table = text
+        .parse()
+        .map_err(|err: ::toml::de::Error| invalid(err.to_string()))?;
+    let mut map = HashMap::new();
+
+    for (key, value) in &document {
+        match value {
+            Value::Table(table) => read_key(key, table, &mut map)?,
+            Value::Array(array) if array.iter().all(Value::is_table) => {
+                return Err(invalid(format!(
+                    "arrays of tables are not supported: {}",
+                    key
+                )))
+            }
+            _ => return Err(invalid(format!("key-value outside table: {}", key))),
+        }
+    }
+
+    Ok(map)
+}
+
+// a table is a key when it is empty or has translations or metadata, its nested tables are other
+// keys
+fn read_key(key: &str, table: &Table, map: &mut TwineData) -> io::Result<()> {
+    // the tables `[__defaults__]` and `[__schema__]` contain keys instead of languages
+    let in_defaults = key == DEFAULTS_SECTION || key == SCHEMA_SECTION;
+    let mut section = Section {
+        position: map.len(),
+        ..Section::default()
+    };
+    let mut nested = Vec::new();
+
+    for (name, value) in table {
+        // the names of these tables are keys, even the names of the metadata
+        if in_defaults {
+            match value {
+                Value::String(text) => add_translation(&mut section, key, name, text, true)?,
+                _ => return Err(invalid_translation(key, name, value)),
+            }
+            continue;
+        }
+        match (name.as_str(), value) {
+            ("maxlen", Value::Integer(maxlen)) if *maxlen >= 0 => {
+                section.maxlen = Some(*maxlen as usize);
+            }
+            ("order", Value::Integer(order)) => section.order = Some(*order),
+            ("deprecated", Value::String(deprecated)) => {
+                section.deprecated = Some(deprecated.clone());
+            }
+            ("context", Value::String(context)) => section.context = Some(context.clone()),
+            ("tm_id", Value::String(id)) => section.tm_id = Some(id.clone()),
+            ("format", Value::String(format)) if format == "markdown" || format == "plain" => {
+                section.markdown = format == "markdown";
+            }
+            ("fuzzy", Value::Array(langs)) if langs.iter().all(Value::is_str) => {
+                for lang in langs.iter().filter_map(Value::as_str) {
+                    check_lang(key, lang)?;
+                    section.fuzzy.extend(expand_regions(lang));
+                }
+            }
+            ("maxlen" | "order" | "deprecated" | "context" | "tm_id" | "format" | "fuzzy", _) => {
+                return Err(invalid(format!("invalid {} in [{}]: {}", name, key, value)));
+            }
+            (_, Value::String(text)) => add_translation(&mut section, key, name, text, false)?,
+            (_, Value::Table(categories)) if is_plural(categories) => {
+                for (category, text) in categories {
+                    let lang = format!("{}.{}", name, category);
+                    let text = text.as_str().unwrap_or_default();
+                    add_translation(&mut section, key, &lang, text, false)?;
+                }
+            }
+            (_, Value::Table(table)) => nested.push((format!("{}.{}", key, name), table)),
+            _ => return Err(invalid_translation(key, name, value)),
+        }
+    }
+
+    if table.is_empty() || nested.len() < table.len() {
+        map.insert(key.to_owned(), section);
+    }
+    for (key, table) in nested {
+        read_key(&key, table, map)?;
+    }
+
+    Ok(())
+}
+
+fn add_translation(
+    section: &mut Section,
+    key: &str,
+    lang: &str,
+    text: &str,
+    in_defaults: bool,
+) -> io::Result<()> {
+    if let Some(placeholder) = position_error(text) {
+        return Err(invalid(format!(
+            "invalid placeholder position in [{}]: {}",
+            key, placeholder
+        )));
+    }
+    let langs = if in_defaults {
+        vec![lang.to_owned()]
+    } else {
+        check_lang(key, lang)?;
+        expand_regions(lang)
+    };
+    for lang in langs {
+        section.translations.push((lang, escape(text)));
+    }
+    Ok(())
+}
+
+// `{ one = "...", other = "..." }`: only strings by plural category
+fn is_plural(table: &Table) -> bool {
+    !table.is_empty()
+        && table
+            .iter()
+            .all(|(category, text)| plural::is_category(category) && text.is_str())
+}
+
+fn check_lang(key: &str, lang: &str) -> io::Result<()> {
+    match lang_error(lang) {
+        Some(error) => Err(invalid(format!("{} in [{}]: {}", error, key, lang))),
+        None => Ok(()),
+    }
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn invalid_translation(key: &str, name: &str, value: &Value) -> io::Error {
+    invalid(format!(
+        "invalid translation in [{}]: {} = {}",
+        key, name, value
+    ))
+}
+
+// a decoded string written with the escape sequences of the INI format, the whitespace around the
+// value is escaped as the INI parser would trim it
+fn escape(string: &str) -> String {
+    let len = string.chars().count();
+    let mut escaped = String::with_capacity(string.len());
+    for (i, c) in string.chars().enumerate() {
+        match c {
+            '\\' => escaped.push_str(r"\\"),
+            '\n' => escaped.push_str(r"\n"),
+            '\r' => escaped.push_str(r"\r"),
+            '\t' => escaped.push_str(r"\t"),
+            c if c.is_whitespace() && (i == 0 || i == len - 1) => {
+                escaped.push_str(&format!(r"\u{:04x}", c as u32));
+            }
+            c if c.is_control() => escaped.push_str(&format!(r"\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
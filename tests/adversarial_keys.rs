@@ -0,0 +1,88 @@
+mod common;
+
+use common::build;
+
+#[test]
+fn adversarial_keys() {
+    let translations = r#"
+        [band=>tool]
+            en = Tool
+        [a = b, c; d]
+            en = Letters
+        [type]
+            en = Type
+        [=>,=]
+            en = Nothing
+        "#;
+    let err = twine::Builder::new()
+        .cargo_output(std::io::sink())
+        .build_translations_from_str(&[translations], "i18n.rs")
+        .unwrap_err();
+    // nothing is left of a key made only of the tokens of the macros
+    match err {
+        twine::TwineError::InvalidKey { key, normalized } => {
+            assert_eq!(key, "=>,=");
+            assert_eq!(normalized, "");
+        }
+        err => panic!("unexpected error: {}", err),
+    }
+
+    let translations = translations.replace("[=>,=]", "[x=>,=]");
+    let generated = build(twine::Builder::new().typed_functions(true), &translations);
+
+    // only letters, digits and `_` are left in the identifiers
+    assert!(generated.contains("(band_tool => $lang:expr"));
+    assert!(generated.contains("(a_b_c_d => $lang:expr"));
+    assert!(generated.contains("(x => $lang:expr"));
+    assert!(generated.contains("(type => $lang:expr"));
+    assert!(generated.contains("    ABCD,\n"));
+    // the names of the keys are string literals
+    assert!(generated.contains("\"band=>tool\" => Some(Key::BandTool),"));
+    assert!(generated.contains("\"a = b, c; d\" => Some(Key::ABCD),"));
+    // the functions of the keywords use raw identifiers
+    assert!(generated.contains("pub fn r#type(lang: &Lang) -> String {"));
+    assert!(generated.contains("pub fn band_tool(lang: &Lang) -> String {"));
+}
+
+#[test]
+fn key_collision() {
+    for (translations, (key, other, normalized)) in [
+        (
+            "[band=>tool]\n    en = Tool\n[band, tool]\n    en = Tool\n",
+            ("band, tool", "band=>tool", "band_tool"),
+        ),
+        // different in snake case but not in camel case
+        ("[a_1]\n    en = A\n[a1]\n    en = A\n", ("a1", "a_1", "A1")),
+    ] {
+        let err = twine::Builder::new()
+            .cargo_output(std::io::sink())
+            .build_translations_from_str(&[translations], "i18n.rs")
+            .unwrap_err();
+
+        match err {
+            twine::TwineError::KeyCollision {
+                key: err_key,
+                other: err_other,
+                normalized: err_normalized,
+            } => {
+                assert_eq!(err_key, key);
+                assert_eq!(err_other, other);
+                assert_eq!(err_normalized, normalized);
+            }
+            err => panic!("unexpected error: {}", err),
+        }
+    }
+}
+
+#[test]
+fn reserved_key() {
+    let err = twine::Builder::new()
+        .cargo_output(std::io::sink())
+        .build_translations_from_str(&["[self]\n    en = Self\n"], "i18n.rs")
+        .unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "key \"self\" cannot be turned into a Rust identifier (got \"self\")"
+    );
+}
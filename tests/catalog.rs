@@ -0,0 +1,587 @@
+use twine::{
+    Catalog, CatalogDiff, Diagnostic, DiagnosticKind, MergeConflict, MergeStrategy,
+    PlaceholderKind, Severity, TranslationChange, ValidateOpts,
+};
+
+const OLD: &str = r#"
+    [band_tool]
+        en = Tool
+        fr = Outil
+    [band_the_doors]
+        en = The Doors
+        fr = Les portes
+    [band_the_jackson_5]
+        en = The Jackson 5
+        fr = Les 5 fils de Jack
+    "#;
+
+const NEW: &str = r#"
+    [band_tool]
+        en = Tool
+        fr = L'outil
+        de = Werkzeug
+    [band_the_doors]
+        en = The Doors
+    [band_rage_against_the_machine]
+        en = Rage Against the Machine
+        fr = Colère contre la machine
+    "#;
+
+#[test]
+fn diff() {
+    let old: Catalog = OLD.parse().unwrap();
+    let new: Catalog = NEW.parse().unwrap();
+
+    assert_eq!(
+        old.diff(&new),
+        CatalogDiff {
+            added_keys: vec!["band_rage_against_the_machine".to_string()],
+            removed_keys: vec!["band_the_jackson_5".to_string()],
+            changes: vec![
+                TranslationChange {
+                    key: "band_the_doors".to_string(),
+                    lang: "fr".to_string(),
+                    old: Some("Les portes".to_string()),
+                    new: None,
+                },
+                TranslationChange {
+                    key: "band_tool".to_string(),
+                    lang: "de".to_string(),
+                    old: None,
+                    new: Some("Werkzeug".to_string()),
+                },
+                TranslationChange {
+                    key: "band_tool".to_string(),
+                    lang: "fr".to_string(),
+                    old: Some("Outil".to_string()),
+                    new: Some("L'outil".to_string()),
+                },
+            ],
+        },
+    );
+    assert_eq!(new.diff(&new), CatalogDiff::default());
+}
+
+#[cfg(feature = "catalog-serde")]
+#[test]
+fn diff_serde() {
+    let old: Catalog = OLD.parse().unwrap();
+    let new: Catalog = NEW.parse().unwrap();
+    let diff = old.diff(&new);
+
+    let json = serde_json::to_value(&diff).unwrap();
+    assert_eq!(json["added_keys"][0], "band_rage_against_the_machine");
+    assert_eq!(json["changes"][2]["old"], "Outil");
+    assert_eq!(json["changes"][2]["new"], "L'outil");
+    let deserialized: CatalogDiff = serde_json::from_value(json).unwrap();
+    assert_eq!(deserialized, diff);
+}
+
+#[cfg(feature = "catalog-serde")]
+#[test]
+fn to_json_by_lang() {
+    let catalog: Catalog = "\
+[band_rage_against_the_machine]
+    en = Rage Against the Machine
+    en-gb = Wrath Against the Machine
+    fr = Colère contre la machine
+[band_tool]
+    en = Tool
+    fr = Outil
+[n_files]
+    en.one = %d file
+    en.other = %d files
+"
+    .parse()
+    .unwrap();
+
+    assert_eq!(
+        catalog.to_json_by_lang(),
+        serde_json::json!({
+            "en": {
+                "band_rage_against_the_machine": "Rage Against the Machine",
+                "band_tool": "Tool",
+                "n_files_one": "%d file",
+                "n_files_other": "%d files",
+            },
+            "en_gb": {
+                "band_rage_against_the_machine": "Wrath Against the Machine",
+            },
+            "fr": {
+                "band_rage_against_the_machine": "Colère contre la machine",
+                "band_tool": "Outil",
+            },
+        }),
+    );
+}
+
+#[test]
+fn write_ini_comments() {
+    let ini = "\
+; bands
+[band_tool]
+    ; the band, not the object
+    en = Tool
+    # same in French
+    fr = Outil
+# the doors
+; are not translated
+[band_the_doors]
+    maxlen = 20
+    deprecated = band_tool
+    context = https://example.com/doors.png
+    order = -1
+    tm_id = CROWDIN-12345
+    format = markdown
+    fuzzy = fr
+    en = The Doors
+    ; literally
+    fr = Les portes
+; end of file
+";
+    let catalog: Catalog = ini.parse().unwrap();
+    let mut output = Vec::new();
+    catalog.write_ini(&mut output).unwrap();
+
+    assert_eq!(String::from_utf8(output).unwrap(), ini);
+}
+
+fn merge_conflicts() -> Vec<MergeConflict> {
+    vec![MergeConflict {
+        key: "band_tool".to_string(),
+        lang: "fr".to_string(),
+        current: "Outil".to_string(),
+        incoming: "L'outil".to_string(),
+    }]
+}
+
+#[test]
+fn merge_prefer_self() {
+    let mut catalog: Catalog = OLD.parse().unwrap();
+
+    let conflicts = catalog.merge(NEW.parse().unwrap(), MergeStrategy::PreferSelf);
+
+    assert_eq!(conflicts, merge_conflicts());
+    assert_eq!(catalog.get("band_tool", "fr"), Some("Outil"));
+    assert_eq!(catalog.get("band_tool", "de"), Some("Werkzeug"));
+    assert_eq!(catalog.get("band_the_doors", "fr"), Some("Les portes"));
+    assert_eq!(
+        catalog.get("band_rage_against_the_machine", "en"),
+        Some("Rage Against the Machine"),
+    );
+    assert_eq!(
+        catalog.keys(),
+        vec![
+            "band_rage_against_the_machine",
+            "band_the_doors",
+            "band_the_jackson_5",
+            "band_tool",
+        ],
+    );
+}
+
+#[test]
+fn merge_prefer_other() {
+    let mut catalog: Catalog = OLD.parse().unwrap();
+
+    let conflicts = catalog.merge(NEW.parse().unwrap(), MergeStrategy::PreferOther);
+
+    assert_eq!(conflicts, merge_conflicts());
+    assert_eq!(catalog.get("band_tool", "fr"), Some("L'outil"));
+    assert_eq!(catalog.get("band_tool", "de"), Some("Werkzeug"));
+    assert_eq!(
+        catalog.get("band_the_jackson_5", "fr"),
+        Some("Les 5 fils de Jack")
+    );
+}
+
+#[test]
+fn merge_error() {
+    let mut catalog: Catalog = OLD.parse().unwrap();
+
+    let conflicts = catalog.merge(NEW.parse().unwrap(), MergeStrategy::Error);
+
+    assert_eq!(conflicts, merge_conflicts());
+    assert_eq!(catalog.diff(&OLD.parse().unwrap()), CatalogDiff::default());
+
+    // without conflict everything is merged
+    let conflicts = catalog.merge(
+        "[band_tool]\n    de = Werkzeug\n".parse().unwrap(),
+        MergeStrategy::Error,
+    );
+
+    assert_eq!(conflicts, vec![]);
+    assert_eq!(catalog.get("band_tool", "de"), Some("Werkzeug"));
+}
+
+#[test]
+fn find_unused() {
+    let catalog: Catalog = r#"
+        [band_tool]
+            en = Tool
+        [band_the_doors]
+            en = The Doors
+        [NameWith.DotInIt]
+            en = Name with a dot
+        [n_files]
+            en.one = %d file
+            en.other = %d files
+        [format_string]
+            en = %s, %@!
+        [band_abba]
+            en = ABBA
+        [band_queen]
+            en = Queen
+        [unused]
+            en = Unused
+        "#
+    .parse()
+    .unwrap();
+    let src_dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(src_dir.as_ref().join("ui")).unwrap();
+    std::fs::write(
+        src_dir.as_ref().join("main.rs"),
+        r#"
+        fn main() {
+            println!("{}", t!(band_tool => lang));
+            println!("{}", plural!(n_files, 2 => lang));
+            t_into!(&mut buf, format_string, "Hello", "World" => lang).unwrap();
+            body.extend(t_bytes!(band_abba => lang));
+            t_bytes!(&mut body, band_queen => lang).unwrap();
+        }
+        "#,
+    )
+    .unwrap();
+    std::fs::write(
+        src_dir.as_ref().join("ui").join("mod.rs"),
+        "fn title() -> String { t! { name_with_dot_in_it => Lang::En(\"\") } }",
+    )
+    .unwrap();
+    // only the Rust files are searched
+    std::fs::write(
+        src_dir.as_ref().join("README.md"),
+        "t!(band_the_doors => lang)",
+    )
+    .unwrap();
+
+    assert_eq!(
+        catalog.find_unused(&[src_dir.as_ref()]).unwrap(),
+        vec!["band_the_doors", "unused"],
+    );
+}
+
+#[test]
+fn placeholders() {
+    let catalog: Catalog = r#"
+        [format_mixed]
+            en = %s: %.1f%% (%d, %x, %X, %@)
+            fr = %2$.1f %% : %1$s (%3$i, %4$x, %5$X, %6$@)
+        [format_repeated]
+            en = %1$s and %1$s
+        [n_files]
+            en.one = %d file
+            en.other = %d files
+        "#
+    .parse()
+    .unwrap();
+
+    let expected = vec![
+        PlaceholderKind::Str,
+        PlaceholderKind::Float,
+        PlaceholderKind::Int,
+        PlaceholderKind::Hex,
+        PlaceholderKind::UpperHex,
+        PlaceholderKind::Str,
+    ];
+    assert_eq!(catalog.placeholders("format_mixed", "en"), expected);
+    assert_eq!(catalog.placeholders("format_mixed", "fr"), expected);
+    assert_eq!(
+        catalog.placeholders("format_repeated", "en"),
+        vec![PlaceholderKind::Str],
+    );
+    assert_eq!(
+        catalog.placeholders("n_files", "en.one"),
+        vec![PlaceholderKind::Int],
+    );
+    assert!(catalog.placeholders("format_mixed", "de").is_empty());
+    assert!(catalog.placeholders("unknown", "en").is_empty());
+}
+
+#[test]
+fn expansion_report() {
+    let catalog: Catalog = "\
+[button_ok]
+    en = OK
+    fr = D'accord
+    de = OK
+[band_tool]
+    en = Tool
+    fr = Outil
+[n_files]
+    en.one = One file
+    en.other = %d files
+    fr.one = Un fichier
+    fr.many = %d de fichiers
+[app_title]
+    fr = Titre
+"
+    .parse()
+    .unwrap();
+
+    let report = catalog.expansion_report("en");
+
+    // the longest translations compared to the source come first
+    assert_eq!(
+        report,
+        vec![
+            ("button_ok", "fr", 4.0),
+            ("n_files", "fr.many", 1.75),
+            ("band_tool", "fr", 1.25),
+            ("n_files", "fr.one", 1.25),
+            ("button_ok", "de", 1.0),
+        ]
+    );
+    assert!(catalog.expansion_report("it").is_empty());
+}
+
+#[test]
+fn validate() {
+    let catalog: Catalog = "\
+[band_tool]
+    en = Tool
+    fr = Outil
+    de = \n[band.tool]
+    en = Tool
+    fr = Outil
+    de = Werkzeug
+[format_string]
+    fr = %s, %@ !
+    en = %s, %@!
+    de = %s!
+[format_percentage]
+    en = %.0f%%
+    fr = %.0f %z
+[n_files]
+    en.one = %d file
+    en.other = %d files
+"
+    .parse()
+    .unwrap();
+
+    let diagnostics = catalog.validate(ValidateOpts {
+        source_lang: Some("en".to_string()),
+    });
+    let summary: Vec<_> = diagnostics
+        .iter()
+        .map(|x| (x.severity, x.kind, x.key.as_str(), x.lang.as_deref()))
+        .collect();
+
+    assert_eq!(
+        summary,
+        vec![
+            (
+                Severity::Error,
+                DiagnosticKind::KeyCollision,
+                "band_tool",
+                None
+            ),
+            (
+                Severity::Warning,
+                DiagnosticKind::EmptyValue,
+                "band_tool",
+                Some("de")
+            ),
+            (
+                Severity::Warning,
+                DiagnosticKind::MissingLanguage,
+                "format_percentage",
+                Some("de")
+            ),
+            (
+                Severity::Warning,
+                DiagnosticKind::InvalidPlaceholder,
+                "format_percentage",
+                Some("fr")
+            ),
+            (
+                Severity::Warning,
+                DiagnosticKind::PlaceholderMismatch,
+                "format_string",
+                Some("de")
+            ),
+            (
+                Severity::Warning,
+                DiagnosticKind::MissingLanguage,
+                "n_files",
+                Some("de")
+            ),
+            (
+                Severity::Warning,
+                DiagnosticKind::MissingLanguage,
+                "n_files",
+                Some("fr")
+            ),
+        ],
+    );
+    assert_eq!(
+        diagnostics[0],
+        Diagnostic {
+            severity: Severity::Error,
+            kind: DiagnosticKind::KeyCollision,
+            key: "band_tool".to_string(),
+            lang: None,
+            message: "key \"band_tool\" has the same name as \"band.tool\"".to_string(),
+        },
+    );
+}
+
+#[test]
+fn binary() {
+    let catalog: Catalog = "\
+[__languages__]
+    en, fr
+[band_tool]
+    maxlen = 0
+    en = Tool
+    fr = Outil
+; the doors
+[band_the_doors]
+    deprecated = band_tool
+    context = https://example.com/doors.png
+    order = -1
+    tm_id = CROWDIN-12345
+    format = markdown
+    fuzzy = fr
+    en = The Doors
+    fr = Les portes
+[n_files]
+    en.one = %d file
+    en.other = %d files
+"
+    .parse()
+    .unwrap();
+    let mut binary = Vec::new();
+    catalog.serialize_binary(&mut binary).unwrap();
+    let loaded = Catalog::load_binary(&mut binary.as_slice()).unwrap();
+
+    assert_eq!(catalog.diff(&loaded), CatalogDiff::default());
+    // everything but the comments is kept
+    let mut ini = Vec::new();
+    loaded.write_ini(&mut ini).unwrap();
+    assert_eq!(
+        String::from_utf8(ini).unwrap(),
+        "\
+[__languages__]
+    en
+    fr
+[band_tool]
+    maxlen = 0
+    en = Tool
+    fr = Outil
+[band_the_doors]
+    deprecated = band_tool
+    context = https://example.com/doors.png
+    order = -1
+    tm_id = CROWDIN-12345
+    format = markdown
+    fuzzy = fr
+    en = The Doors
+    fr = Les portes
+[n_files]
+    en.one = %d file
+    en.other = %d files
+",
+    );
+
+    let err = Catalog::load_binary(&mut "[band_tool]".as_bytes()).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    let err = Catalog::load_binary(&mut &binary[..binary.len() - 1]).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+    let err = Catalog::load_binary(&mut b"TWNC\x02".as_ref()).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert!(err
+        .to_string()
+        .starts_with("unsupported binary catalog version 2 (supported: 1)"));
+}
+
+fn binary_u32(binary: &mut Vec<u8>, value: u32) {
+    binary.extend_from_slice(&value.to_le_bytes());
+}
+
+fn binary_str(binary: &mut Vec<u8>, value: &str) {
+    binary_u32(binary, value.len() as u32);
+    binary.extend_from_slice(value.as_bytes());
+}
+
+#[test]
+fn binary_unknown_metadata() {
+    let mut binary = b"TWNC\x01".to_vec();
+    binary_u32(&mut binary, 1);
+    binary_str(&mut binary, "band_tool");
+    binary_u32(&mut binary, 0);
+    // a metadata of a later version (tag 99) then `tm_id` (tag 5)
+    binary_u32(&mut binary, 2);
+    binary_u32(&mut binary, 99);
+    binary_str(&mut binary, "unknown");
+    binary_u32(&mut binary, 5);
+    binary_str(&mut binary, "CROWDIN-12345");
+    binary_u32(&mut binary, 1);
+    binary_str(&mut binary, "en");
+    binary_str(&mut binary, "Tool");
+
+    let loaded = Catalog::load_binary(&mut binary.as_slice()).unwrap();
+    assert_eq!(loaded.external_id("band_tool"), Some("CROWDIN-12345"));
+    assert_eq!(loaded.get("band_tool", "en"), Some("Tool"));
+}
+
+#[test]
+fn source_of() {
+    let mut catalog = Catalog::from_named_reader("old.ini", &mut OLD.as_bytes()).unwrap();
+    let new = Catalog::from_named_reader("new.ini", &mut NEW.as_bytes()).unwrap();
+
+    assert_eq!(catalog.source_of("band_tool", "fr"), Some("old.ini"));
+    catalog.merge(new, MergeStrategy::PreferOther);
+
+    // the values changed come from the new file, the others stay in the old one
+    assert_eq!(catalog.source_of("band_tool", "fr"), Some("new.ini"));
+    assert_eq!(catalog.source_of("band_tool", "de"), Some("new.ini"));
+    assert_eq!(catalog.source_of("band_tool", "en"), Some("old.ini"));
+    assert_eq!(catalog.source_of("band_the_doors", "fr"), Some("old.ini"));
+    assert_eq!(
+        catalog.source_of("band_rage_against_the_machine", "en"),
+        Some("new.ini"),
+    );
+    assert_eq!(catalog.source_of("band_tool", "nl"), None);
+    assert_eq!(
+        OLD.parse::<Catalog>().unwrap().source_of("band_tool", "en"),
+        None
+    );
+
+    // the values kept stay in the old file
+    let mut catalog = Catalog::from_named_reader("old.ini", &mut OLD.as_bytes()).unwrap();
+    catalog.merge(NEW.parse().unwrap(), MergeStrategy::PreferSelf);
+    assert_eq!(catalog.source_of("band_tool", "fr"), Some("old.ini"));
+    assert_eq!(catalog.source_of("band_tool", "de"), None);
+}
+
+#[test]
+fn group_by_language() {
+    let catalog: Catalog = r#"
+        [band_tool]
+            en = Tool
+            en-gb = Tool
+            fr = Outil
+        [n_files]
+            en.one = %d file
+            en.other = %d files
+            de-ch.other = %d Dateien
+        "#
+    .parse()
+    .unwrap();
+
+    let groups = catalog.group_by_language();
+    assert_eq!(groups.len(), 3);
+    assert_eq!(groups["en"], ["en", "en-gb"]);
+    assert_eq!(groups["fr"], ["fr"]);
+    assert_eq!(groups["de"], ["de-ch"]);
+}
@@ -0,0 +1,21 @@
+// helpers shared by the integration tests, not all of them are used by every test
+#![allow(dead_code)]
+
+use std::fs;
+
+/// The code generated from the Twine INI `ini` by `builder`, without writing to `OUT_DIR`.
+pub fn build(builder: twine::Builder, ini: &str) -> String {
+    try_build(builder, ini).unwrap()
+}
+
+/// The code generated from the Twine INI `ini` by `builder` or the error.
+pub fn try_build(mut builder: twine::Builder, ini: &str) -> Result<String, twine::TwineError> {
+    let mut code = Vec::new();
+    builder.build_translations_to_writer(&mut [ini.as_bytes()], &mut code)?;
+    Ok(String::from_utf8(code).unwrap())
+}
+
+/// The content of a file of `tests/fixtures`.
+pub fn fixture(name: &str) -> String {
+    fs::read_to_string(format!("tests/fixtures/{}", name)).unwrap()
+}
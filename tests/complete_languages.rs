@@ -0,0 +1,26 @@
+mod common;
+
+use common::{build, fixture};
+
+#[test]
+fn complete_languages() {
+    let code = build(twine::Builder::new(), &fixture("complete_languages.ini"));
+
+    // `fr` misses `colour`, `en-gb` is completed by `en`
+    assert!(code.contains("pub fn complete_languages() -> Vec<Lang> {"));
+    assert!(
+        code.contains("const COMPLETE_LANGUAGES: &[Lang] = &[Lang::En(\"\"), Lang::En(\"gb\")];")
+    );
+}
+
+#[test]
+fn complete_languages_without_regions() {
+    let code = build(
+        twine::Builder::new()
+            .ignore_regions(true)
+            .cargo_output(std::io::sink()),
+        &fixture("complete_languages.ini"),
+    );
+
+    assert!(code.contains("const COMPLETE_LANGUAGES: &[Lang] = &[Lang::En];"));
+}
@@ -0,0 +1,48 @@
+mod common;
+
+use common::{build, fixture};
+
+#[test]
+fn const_table() {
+    let code = build(
+        twine::Builder::new().const_table(true),
+        &fixture("const_table.ini"),
+    );
+
+    assert!(code.contains("pub const NUM_KEYS: usize = 3;"));
+    assert!(code.contains("pub const NUM_LANGS: usize = 4;"));
+    assert!(code.contains("pub const TABLE: [[&str; NUM_LANGS]; NUM_KEYS] = ["));
+    // the rows follow the variants of `Key` (by order), the columns `Lang::all_languages()`
+    // (`de_ch`, `en`, `en_gb`, `fr`), the missing cells are filled with the fallbacks
+    assert!(code.contains(
+        "    // n_files\n    [\"%d files\", \"%d files\", \"%d files\", \"%d fichiers\"],\n    \
+        // band_tool\n    [\"Tool\", \"Tool\", \"Tool\", \"Outil\"],\n    \
+        // colour\n    [\"Farbe\", \"Color\", \"Colour\", \"Color\"],\n];"
+    ));
+    assert!(code.contains("pub const fn lang_index(lang: &Lang) -> usize {"));
+    assert!(code.contains("Lang::En(region) if matches!(region.as_bytes(), b\"gb\") => 2,"));
+    assert!(code.contains("Lang::En(_) => 1,"));
+    // `de` has no column without region
+    assert!(code.contains("Lang::De(_) => 0,"));
+}
+
+#[test]
+fn const_table_without_regions() {
+    let code = build(
+        twine::Builder::new()
+            .const_table(true)
+            .ignore_regions(true)
+            .cargo_output(std::io::sink()),
+        &fixture("const_table.ini"),
+    );
+
+    assert!(code.contains("pub const NUM_LANGS: usize = 3;"));
+    assert!(code.contains("Lang::En => 1,"));
+}
+
+#[test]
+fn const_table_disabled() {
+    let code = build(twine::Builder::new(), &fixture("const_table.ini"));
+
+    assert!(!code.contains("TABLE"));
+}
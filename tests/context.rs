@@ -0,0 +1,36 @@
+use std::fs;
+
+#[test]
+fn context() {
+    let out_dir = tempfile::tempdir().unwrap();
+    std::env::set_var("OUT_DIR", out_dir.as_ref().as_os_str());
+
+    twine::Builder::new()
+        .typed_functions(true)
+        .build_translations(&["tests/fixtures/context.ini"], "i18n.rs")
+        .unwrap();
+    let generated = fs::read_to_string(out_dir.as_ref().join("i18n.rs")).unwrap();
+
+    assert!(generated.contains(
+        "/// Translate the key `band_tool`.\n///\n/// Context: \
+        <https://example.com/screenshots/bands.png>\n#[allow(dead_code)]\npub fn band_tool(",
+    ));
+    assert!(
+        generated.contains(
+            "    /// Context: <https://example.com/screenshots/bands.png>\n    BandTool,\n",
+        )
+    );
+    assert_eq!(generated.matches("Context:").count(), 2);
+    // the metadata is not a translation
+    assert!(!generated.contains("Context("));
+
+    let catalog =
+        twine::Catalog::from_reader(&mut fs::File::open("tests/fixtures/context.ini").unwrap())
+            .unwrap();
+    assert_eq!(
+        catalog.context("band_tool"),
+        Some("https://example.com/screenshots/bands.png"),
+    );
+    assert_eq!(catalog.context("band_the_doors"), None);
+    assert_eq!(catalog.context("unknown"), None);
+}
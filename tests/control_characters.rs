@@ -0,0 +1,34 @@
+#[test]
+fn control_characters_strict() {
+    let err = twine::Builder::new()
+        .strict(true)
+        .cargo_output(std::io::sink())
+        .build_translations(&["tests/fixtures/control.ini"], "i18n.rs")
+        .unwrap_err();
+
+    match err {
+        twine::TwineError::InvalidCharacter {
+            key,
+            lang,
+            codepoint,
+        } => {
+            assert_eq!(key, "band_tool");
+            assert_eq!(lang, "fr");
+            assert_eq!(codepoint, 0);
+        }
+        err => panic!("unexpected error: {}", err),
+    }
+}
+
+#[test]
+fn lone_carriage_return_strict() {
+    let err = twine::Builder::new()
+        .strict(true)
+        .build_translations_from_str(&["[band_tool]\n    en = To\rol\n"], "i18n.rs")
+        .unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "translation \"en\" of key \"band_tool\" contains the invalid character U+000D",
+    );
+}
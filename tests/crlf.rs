@@ -0,0 +1,31 @@
+use std::fs;
+
+#[test]
+fn crlf() {
+    let translations = "\
+        [band_tool]\r\n\
+        \x20   en = Tool\r\n\
+        \x20   fr = Outil\r\r\n\
+        [band_the_doors]\r\n\
+        \x20   ; comment\r\n\
+        \x20   en = The Doors \r\n\
+        \x20   fr = Les portes\r\n\
+        ";
+    let out_dir = tempfile::tempdir().unwrap();
+    std::env::set_var("OUT_DIR", out_dir.as_ref().as_os_str());
+
+    let mut cargo_output = Vec::new();
+    twine::Builder::new()
+        .strict(true)
+        .cargo_output(&mut cargo_output)
+        .build_translations_from_str(&[translations], "i18n.rs")
+        .unwrap();
+    let generated = fs::read_to_string(out_dir.as_ref().join("i18n.rs")).unwrap();
+    println!("{}", generated);
+
+    assert!(!generated.contains('\r'));
+    assert!(generated.contains(r#"$crate::Lang::En(_) => format!("Tool" $(, $fmt_args)*),"#));
+    assert!(generated.contains(r#"$crate::Lang::Fr(_) => format!("Outil" $(, $fmt_args)*),"#));
+    assert!(generated.contains(r#"$crate::Lang::En(_) => format!("The Doors" $(, $fmt_args)*),"#));
+    assert!(generated.contains(r#"$crate::Lang::Fr(_) => format!("Les portes" $(, $fmt_args)*),"#));
+}
@@ -0,0 +1,41 @@
+mod common;
+
+use common::build;
+
+#[test]
+fn default_lang() {
+    let translations =
+        "[band_tool]\n    en = Tool\n    fr = Outil\n[band_the_doors]\n    en = The Doors\n";
+    let mut cargo_output = Vec::new();
+    let generated = build(
+        twine::Builder::new()
+            .default_lang("fr")
+            .cargo_output(&mut cargo_output),
+        translations,
+    );
+    let cargo_output = String::from_utf8(cargo_output).unwrap();
+
+    assert_eq!(
+        cargo_output,
+        "cargo:warning=key \"band_the_doors\" is not translated in the default language\n",
+    );
+    assert!(generated.contains(r#"_ => format!("Outil" $(, $fmt_args)*),"#));
+    assert!(generated.contains(r#"_ => format!("The Doors" $(, $fmt_args)*),"#));
+}
+
+#[test]
+fn missing_default_strict() {
+    let err = twine::Builder::new()
+        .strict(true)
+        .default_lang("en")
+        .cargo_output(std::io::sink())
+        .build_translations(&["tests/fixtures/missing_default.ini"], "i18n.rs")
+        .unwrap_err();
+
+    match err {
+        twine::TwineError::MissingDefault { key } => {
+            assert_eq!(key, "band_the_jackson_5");
+        }
+        err => panic!("unexpected error: {}", err),
+    }
+}
@@ -0,0 +1,43 @@
+use std::fs;
+
+#[test]
+fn defaults() {
+    let out_dir = tempfile::tempdir().unwrap();
+    std::env::set_var("OUT_DIR", out_dir.as_ref().as_os_str());
+
+    twine::Builder::new()
+        .build_translations(&["tests/fixtures/defaults.ini"], "i18n.rs")
+        .unwrap();
+    let generated = fs::read_to_string(out_dir.as_ref().join("i18n.rs")).unwrap();
+
+    // the key itself comes before the patterns
+    assert!(generated
+        .contains(r#"$crate::Lang::De(_) => format!("Tool (untranslated)" $(, $fmt_args)*),"#));
+    assert!(
+        generated.contains(r#"$crate::Lang::Fr(_) => format!("Unknown band" $(, $fmt_args)*),"#)
+    );
+    assert!(
+        generated.contains(r#"$crate::Lang::De(_) => format!("Unknown band" $(, $fmt_args)*),"#)
+    );
+    assert!(
+        generated.contains(r#"$crate::Lang::En(_) => format!("Unknown band" $(, $fmt_args)*),"#)
+    );
+    // the keys that do not match use their first translation
+    assert!(generated.contains(r#"_ => format!("{:}, {:} !" $(, $fmt_args)*),"#));
+    assert!(!generated.contains("__defaults__"));
+    assert!(!generated.contains("band_*"));
+
+    // the languages of the fallback order come first
+    twine::Builder::new()
+        .fallback_order(&["en"])
+        .build_translations(&["tests/fixtures/defaults.ini"], "i18n.rs")
+        .unwrap();
+    let generated = fs::read_to_string(out_dir.as_ref().join("i18n.rs")).unwrap();
+
+    assert!(!generated.contains("Tool (untranslated)"));
+    assert!(generated.contains(r#"_ => format!("The Doors" $(, $fmt_args)*),"#));
+    // the key is not translated in English
+    assert!(
+        generated.contains(r#"$crate::Lang::En(_) => format!("Unknown band" $(, $fmt_args)*),"#)
+    );
+}
@@ -0,0 +1,25 @@
+mod common;
+
+use common::build;
+
+#[test]
+fn defaults_named_as_metadata() {
+    let ini = "\
+[__defaults__]
+    order = pending
+    context = Context (untranslated)
+[order]
+    en = Order
+[context]
+    en = Context
+[band_tool]
+    en = Tool
+    fr = Outil
+";
+    let generated = build(twine::Builder::new(), ini);
+
+    // the keys `order` and `context` are not read as the metadata of the section
+    assert!(generated.contains(r#"$crate::Lang::Fr(_) => format!("pending" $(, $fmt_args)*),"#));
+    assert!(generated
+        .contains(r#"$crate::Lang::Fr(_) => format!("Context (untranslated)" $(, $fmt_args)*),"#));
+}
@@ -0,0 +1,29 @@
+use std::fs;
+
+#[test]
+fn deprecated() {
+    let out_dir = tempfile::tempdir().unwrap();
+    std::env::set_var("OUT_DIR", out_dir.as_ref().as_os_str());
+
+    let mut cargo_output = Vec::new();
+    // the deprecated keys are not errors in strict mode
+    twine::Builder::new()
+        .strict(true)
+        .typed_functions(true)
+        .cargo_output(&mut cargo_output)
+        .build_translations(&["tests/fixtures/deprecated.ini"], "i18n.rs")
+        .unwrap();
+    let cargo_output = String::from_utf8(cargo_output).unwrap();
+    let generated = fs::read_to_string(out_dir.as_ref().join("i18n.rs")).unwrap();
+
+    assert!(cargo_output.contains(
+        "cargo:warning=key \"band_the_tool\" is deprecated, use \"band_tool\" instead\n"
+    ));
+    assert_eq!(cargo_output.matches("is deprecated").count(), 1);
+    assert!(generated.contains(
+        "/// Translate the key `band_the_tool`.\n#[deprecated(note = \"use band_tool\")]\n#[allow(dead_code)]\npub fn band_the_tool(",
+    ));
+    assert!(!generated.contains("#[deprecated(note = \"use band_the_tool\")]"));
+    // the metadata is not a translation
+    assert!(!generated.contains("Deprecated"));
+}
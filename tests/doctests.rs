@@ -0,0 +1,48 @@
+use std::fs;
+
+#[test]
+fn doctests() {
+    let translations = r#"
+        [band_tool]
+            context = https://example.com/bands.png
+            fr = Outil
+            en = Tool
+        [format_string]
+            en-gb = %s, %@!
+        [format_percentage]
+            en = %.0f%
+        [n_files]
+            en.one = %d file
+            en.other = %d files
+        "#;
+    let out_dir = tempfile::tempdir().unwrap();
+    std::env::set_var("OUT_DIR", out_dir.as_ref().as_os_str());
+
+    twine::Builder::new()
+        .doctests(true)
+        .build_translations_from_str(&[translations], "i18n.rs")
+        .unwrap();
+    let generated = fs::read_to_string(out_dir.as_ref().join("i18n.rs")).unwrap();
+
+    // the examples use the name of the crate including the file
+    assert!(generated.contains(
+        "    /// Context: <https://example.com/bands.png>\n    ///\n    /// # Examples\n    ///\n    \
+        /// ```\n    /// assert_eq!(twine::t!(band_tool => twine::Lang::Fr(\"\")), \"Outil\");\n    \
+        /// ```\n    BandTool,\n"
+    ));
+    assert!(generated.contains(
+        "    /// let _ = twine::t!(format_string, \"a\", \"a\" => twine::Lang::En(\"gb\"));\n"
+    ));
+    assert!(generated
+        .contains("    /// let _ = twine::t!(format_percentage, 1.5 => twine::Lang::En(\"\"));\n"));
+    // the plural keys do not have an example
+    assert_eq!(generated.matches("/// # Examples").count(), 3);
+
+    // disabled by default
+    twine::Builder::new()
+        .build_translations_from_str(&[translations], "i18n.rs")
+        .unwrap();
+    let generated = fs::read_to_string(out_dir.as_ref().join("i18n.rs")).unwrap();
+
+    assert!(!generated.contains("/// # Examples"));
+}
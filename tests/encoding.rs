@@ -0,0 +1,18 @@
+#[cfg(feature = "encoding_rs")]
+#[test]
+fn windows_1252() {
+    use std::fs;
+
+    let out_dir = tempfile::tempdir().unwrap();
+    std::env::set_var("OUT_DIR", out_dir.as_ref().as_os_str());
+
+    twine::Builder::new()
+        .encoding(twine::encoding_rs::WINDOWS_1252)
+        .cargo_output(std::io::sink())
+        .build_translations(&["tests/fixtures/windows-1252.ini"], "i18n.rs")
+        .unwrap();
+    let generated = fs::read_to_string(out_dir.as_ref().join("i18n.rs")).unwrap();
+
+    assert!(generated.contains("\"Colère contre la machine\""));
+    assert!(generated.contains("\"“Quoted” € 5\""));
+}
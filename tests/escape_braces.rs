@@ -0,0 +1,29 @@
+mod common;
+
+use common::build;
+
+const INI: &str = "\
+[braces]
+    en = {y} is not an argument, { alone
+[n_braces]
+    en.one = {# brace}
+    en.other = {# braces}
+";
+
+#[test]
+fn escape_braces() {
+    let code = build(twine::Builder::new(), INI);
+
+    // a named argument would capture a variable `y` and a lone `{` would not compile
+    assert!(code.contains("\"{{y}} is not an argument, {{ alone\""));
+    assert!(code.contains("\"{{{0} brace}}\""));
+    assert!(code.contains("\"{{{0} braces}}\""));
+}
+
+#[test]
+fn named_args_keep_braces() {
+    let code = build(twine::Builder::new().named_args(true), INI);
+
+    assert!(code.contains("\"{y} is not an argument, { alone\""));
+    assert!(!code.contains("{{y}}"));
+}
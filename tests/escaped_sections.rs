@@ -0,0 +1,25 @@
+use std::fs;
+
+#[test]
+fn escaped_sections() {
+    let out_dir = tempfile::tempdir().unwrap();
+    std::env::set_var("OUT_DIR", out_dir.as_ref().as_os_str());
+
+    twine::Builder::new()
+        .build_translations(&["tests/fixtures/escaped_sections.ini"], "i18n.rs")
+        .unwrap();
+    let generated = fs::read_to_string(out_dir.as_ref().join("i18n.rs")).unwrap();
+
+    // the key keeps its bracket, only the identifier is normalized
+    assert!(generated.contains("(array_0 => $lang:expr"));
+    assert!(generated.contains("\"array[0]\" => Some(Key::Array0)"));
+
+    let ini = fs::read_to_string("tests/fixtures/escaped_sections.ini").unwrap();
+    let catalog: twine::Catalog = ini.parse().unwrap();
+    assert_eq!(catalog.keys(), vec!["array[0]", "band_tool"]);
+    assert_eq!(catalog.get("array[0]", "fr"), Some("Premier élément"));
+
+    let mut output = Vec::new();
+    catalog.write_ini(&mut output).unwrap();
+    assert_eq!(String::from_utf8(output).unwrap(), ini);
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use common::{build, fixture};
+use twine::Catalog;
+
+fn load(path: &str) -> Catalog {
+    Catalog::from_reader(&mut std::fs::File::open(path).unwrap()).unwrap()
+}
+
+#[test]
+fn external_id() {
+    let catalog = load("tests/fixtures/external_id.ini");
+
+    assert_eq!(catalog.external_id("band_tool"), Some("CROWDIN-12345"));
+    assert_eq!(catalog.external_id("n_files"), Some("lokalise:n_files"));
+    assert_eq!(catalog.external_id("band_the_doors"), None);
+    assert_eq!(catalog.external_id("unknown"), None);
+    // the metadata is not a translation
+    assert_eq!(catalog.languages("band_tool"), ["en", "fr"]);
+}
+
+#[test]
+fn external_id_round_trip() {
+    let catalog = load("tests/fixtures/external_id.ini");
+
+    let mut ini = Vec::new();
+    catalog.write_ini(&mut ini).unwrap();
+    assert_eq!(
+        String::from_utf8(ini.clone()).unwrap(),
+        std::fs::read_to_string("tests/fixtures/external_id.ini").unwrap(),
+    );
+
+    let mut binary = Vec::new();
+    catalog.serialize_binary(&mut binary).unwrap();
+    let reloaded = Catalog::load_binary(&mut binary.as_slice()).unwrap();
+    assert_eq!(reloaded.external_id("band_tool"), Some("CROWDIN-12345"));
+    assert_eq!(reloaded.external_id("band_the_doors"), None);
+}
+
+#[test]
+fn external_id_not_generated() {
+    let code = build(
+        twine::Builder::new().cargo_output(std::io::sink()),
+        &fixture("external_id.ini"),
+    );
+
+    assert!(!code.contains("CROWDIN-12345"));
+    assert!(!code.contains("Lang::Id"));
+}
+
+#[test]
+fn indonesian() {
+    // `id` is always a translation, even without `[__languages__]`
+    let catalog = load("tests/fixtures/external_id_indonesian.ini");
+
+    assert_eq!(catalog.external_id("band_tool"), Some("CROWDIN-12345"));
+    assert_eq!(catalog.get("band_tool", "id"), Some("Alat"));
+    assert_eq!(catalog.languages("band_tool"), ["en", "id"]);
+
+    let code = build(
+        twine::Builder::new().cargo_output(std::io::sink()),
+        &fixture("external_id_indonesian.ini"),
+    );
+
+    assert!(code.contains(r#"$crate::Lang::Id(_) => format!("Alat" $(, $fmt_args)*),"#));
+}
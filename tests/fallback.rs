@@ -0,0 +1,40 @@
+use std::fs;
+
+#[test]
+fn fallback_order() {
+    let translations = r#"
+        [band_tool]
+            en = Tool
+            fr = Outil
+            de = Werkzeug
+        [band_the_doors]
+            en = The Doors
+            fr = Les portes
+        [band_rage_against_the_machine]
+            en = Rage Against the Machine
+            de-ch = Wut gegen die Maschine
+        [n_files]
+            en.one = %d file
+            en.other = %d files
+            fr.one = %d fichier
+            fr.other = %d fichiers
+        "#;
+    let out_dir = tempfile::tempdir().unwrap();
+    std::env::set_var("OUT_DIR", out_dir.as_ref().as_os_str());
+
+    twine::Builder::new()
+        .fallback_order(&["de", "fr", "en"])
+        .build_translations_from_str(&[translations], "i18n.rs")
+        .unwrap();
+    let generated = fs::read_to_string(out_dir.as_ref().join("i18n.rs")).unwrap();
+    println!("{}", generated);
+
+    assert!(generated.contains(r#"_ => format!("Werkzeug" $(, $fmt_args)*),"#));
+    assert!(generated.contains(r#"_ => format!("Les portes" $(, $fmt_args)*),"#));
+    // the region must match, the first translation is used otherwise
+    assert!(generated.contains(r#"_ => format!("Rage Against the Machine" $(, $fmt_args)*),"#));
+    assert!(generated.contains(
+        r#"(_, $crate::PluralCategory::One) => format!("{:} fichier", count $(, $fmt_args)*),"#
+    ));
+    assert!(generated.contains(r#"(_, _) => format!("{:} fichiers", count $(, $fmt_args)*),"#));
+}
@@ -0,0 +1,49 @@
+use std::fs;
+
+#[test]
+fn ffi() {
+    let translations = r#"
+        [band_tool]
+            fr = Outil
+            en = Tool
+        [app_ruin_the_band]
+            en = Ruin a band
+            en-gb = Ruin a band, mate
+        [n_files]
+            en.one = %d file
+            en.other = %d files
+        "#;
+    let out_dir = tempfile::tempdir().unwrap();
+    std::env::set_var("OUT_DIR", out_dir.as_ref().as_os_str());
+
+    twine::Builder::new()
+        .ffi("my_app")
+        .build_translations_from_str(&[translations], "i18n.rs")
+        .unwrap();
+    let generated = fs::read_to_string(out_dir.as_ref().join("i18n.rs")).unwrap();
+
+    // the keys are sorted alphabetically and the languages like `Lang::all_languages()`
+    assert!(generated.contains(
+        "pub static FFI_KEYS: [&str; 3] = [\n    \"app_ruin_the_band\",\n    \"band_tool\",\n    \
+        \"n_files\",\n];\n"
+    ));
+    assert!(generated.contains(
+        "pub static FFI_LANGS: [&str; 3] = [\n    \"en\",\n    \"en_gb\",\n    \"fr\",\n];\n"
+    ));
+    // a region falls back to its language, the plural keys use the category "other"
+    assert!(generated.contains(
+        "    // app_ruin_the_band\n    Some(\"Ruin a band\\0\"),\n    Some(\"Ruin a band, mate\\0\"),\n    \
+        None,\n    // band_tool\n    Some(\"Tool\\0\"),\n    Some(\"Tool\\0\"),\n    Some(\"Outil\\0\"),\n    \
+        // n_files\n    Some(\"%d files\\0\"),\n    Some(\"%d files\\0\"),\n    None,\n"
+    ));
+    assert!(generated.contains("pub extern \"C\" fn my_app_key_count() -> u32 {\n"));
+    assert!(generated.contains("pub unsafe extern \"C\" fn my_app_translate("));
+
+    // disabled by default
+    twine::Builder::new()
+        .build_translations_from_str(&[translations], "i18n.rs")
+        .unwrap();
+    let generated = fs::read_to_string(out_dir.as_ref().join("i18n.rs")).unwrap();
+
+    assert!(!generated.contains("extern \"C\""));
+}
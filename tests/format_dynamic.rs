@@ -0,0 +1,52 @@
+mod common;
+
+use common::build;
+
+const INI: &str = "\
+[greeting]
+    en = Hello {name}, you have {count} messages!
+    fr = Bonjour {name}, vous avez {count} messages !
+[band_tool]
+    en = Tool
+    fr = Outil
+";
+
+#[test]
+fn format_dynamic() {
+    let code = build(
+        twine::Builder::new()
+            .named_args(true)
+            .cargo_output(std::io::sink()),
+        INI,
+    );
+
+    assert!(code.contains("pub struct MissingArg(pub Vec<String>);"));
+    assert!(code.contains("impl std::error::Error for MissingArg {}"));
+    assert!(code.contains(
+        "pub fn format_dynamic(\n    key: Key,\n    lang: &Lang,\n    \
+        args: &std::collections::HashMap<&str, &str>,\n) -> Result<String, MissingArg> {"
+    ));
+    assert!(code.contains("let text = describe(key, lang);"));
+}
+
+#[test]
+fn format_dynamic_without_named_args() {
+    let code = build(twine::Builder::new().cargo_output(std::io::sink()), INI);
+
+    assert!(!code.contains("MissingArg"));
+    assert!(!code.contains("fn format_dynamic("));
+}
+
+#[test]
+fn format_dynamic_no_std() {
+    let code = build(
+        twine::Builder::new()
+            .named_args(true)
+            .no_std(true)
+            .cargo_output(std::io::sink()),
+        INI,
+    );
+
+    // the map of the arguments needs `std`
+    assert!(!code.contains("fn format_dynamic("));
+}
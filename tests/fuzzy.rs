@@ -0,0 +1,77 @@
+use std::fs;
+use twine::{Catalog, MergeStrategy};
+
+#[test]
+fn fuzzy() {
+    // a warning by default, even in strict mode
+    let mut cargo_output = Vec::new();
+    twine::Builder::new()
+        .strict(true)
+        .cargo_output(&mut cargo_output)
+        .build_translations_to_writer(
+            &mut [fs::File::open("tests/fixtures/fuzzy.ini").unwrap()],
+            &mut std::io::sink(),
+        )
+        .unwrap();
+    let cargo_output = String::from_utf8(cargo_output).unwrap();
+    assert!(cargo_output.contains(
+        "cargo:warning=translation \"fr\" of key \"band_the_doors\" is fuzzy and needs to be \
+        reviewed\n"
+    ));
+    assert!(cargo_output.contains(
+        "cargo:warning=translation \"en-gb\" of key \"band_the_doors\" is fuzzy and needs to be \
+        reviewed\n"
+    ));
+    assert_eq!(cargo_output.matches("is fuzzy").count(), 2);
+
+    // an error with `fail_on_fuzzy` in strict mode
+    let err = twine::Builder::new()
+        .strict(true)
+        .fail_on_fuzzy(true)
+        .cargo_output(std::io::sink())
+        .build_translations_to_writer(
+            &mut [fs::File::open("tests/fixtures/fuzzy.ini").unwrap()],
+            &mut std::io::sink(),
+        )
+        .unwrap_err();
+    match err {
+        twine::TwineError::FuzzyTranslation { key, lang } => {
+            assert_eq!(key, "band_the_doors");
+            assert_eq!(lang, "fr");
+        }
+        err => panic!("unexpected error: {}", err),
+    }
+
+    // still a warning with `fail_on_fuzzy` outside of strict mode
+    let mut cargo_output = Vec::new();
+    twine::Builder::new()
+        .fail_on_fuzzy(true)
+        .cargo_output(&mut cargo_output)
+        .build_translations_to_writer(
+            &mut [fs::File::open("tests/fixtures/fuzzy.ini").unwrap()],
+            &mut std::io::sink(),
+        )
+        .unwrap();
+    let cargo_output = String::from_utf8(cargo_output).unwrap();
+    assert_eq!(cargo_output.matches("is fuzzy").count(), 2);
+}
+
+#[test]
+fn catalog() {
+    let mut catalog =
+        Catalog::from_reader(&mut fs::File::open("tests/fixtures/fuzzy.ini").unwrap()).unwrap();
+
+    assert!(catalog.is_fuzzy("band_the_doors", "fr"));
+    assert!(catalog.is_fuzzy("band_the_doors", "en-gb"));
+    assert!(!catalog.is_fuzzy("band_the_doors", "en"));
+    assert!(!catalog.is_fuzzy("band_tool", "fr"));
+    assert!(!catalog.is_fuzzy("unknown", "fr"));
+
+    // the markers follow the values
+    let reviewed =
+        "[band_the_doors]\n    fr = Les Doors\n[band_tool]\n    fuzzy = fr\n    fr = L'outil\n";
+    catalog.merge(reviewed.parse().unwrap(), MergeStrategy::PreferOther);
+    assert!(!catalog.is_fuzzy("band_the_doors", "fr"));
+    assert!(catalog.is_fuzzy("band_the_doors", "en-gb"));
+    assert!(catalog.is_fuzzy("band_tool", "fr"));
+}
@@ -0,0 +1,32 @@
+use std::fs;
+
+#[test]
+fn html_escape() {
+    let out_dir = tempfile::tempdir().unwrap();
+    std::env::set_var("OUT_DIR", out_dir.as_ref().as_os_str());
+
+    twine::Builder::new()
+        .html_escape(true)
+        .build_translations_from_str(
+            &[r#"
+            [comparison]
+                en = a < b
+                fr = a < b & c > %s
+            [format_percentage]
+                en = %.0f%% <b>%s</b>
+            [unicode]
+                en = \u003Cbr\u003E\u00e9
+            "#],
+            "i18n.rs",
+        )
+        .unwrap();
+    let generated = fs::read_to_string(out_dir.as_ref().join("i18n.rs")).unwrap();
+
+    assert!(generated.contains("\"a &lt; b\""));
+    assert!(generated.contains("\"a &lt; b &amp; c &gt; {:}\""));
+    // the placeholders are untouched
+    assert!(generated.contains("\"{:.0}% &lt;b&gt;{:}&lt;/b&gt;\""));
+    // the escape sequences of the special characters are escaped too
+    assert!(generated.contains(r#""&lt;br&gt;\u{00e9}""#));
+    assert!(!generated.contains("a < b"));
+}
@@ -0,0 +1,36 @@
+#[test]
+fn identical() {
+    let out_dir = tempfile::tempdir().unwrap();
+    std::env::set_var("OUT_DIR", out_dir.as_ref().as_os_str());
+
+    let mut cargo_output = Vec::new();
+    // the identical values are not errors in strict mode
+    twine::Builder::new()
+        .strict(true)
+        .check_identical(true)
+        .cargo_output(&mut cargo_output)
+        .build_translations(&["tests/fixtures/identical.ini"], "i18n.rs")
+        .unwrap();
+    let cargo_output = String::from_utf8(cargo_output).unwrap();
+
+    assert_eq!(
+        cargo_output,
+        "\
+        cargo:rerun-if-changed=tests/fixtures/identical.ini\n\
+        cargo:warning=key \"band_the_doors\" has the same translation in [\"en\", \"fr\", \
+        \"de\"]: \"The Doors\"\n\
+        cargo:warning=key \"n_files\" has the same translation in [\"en.other\", \
+        \"fr.other\"]: \"%d files\"\n",
+    );
+
+    // the check is disabled by default
+    let mut cargo_output = Vec::new();
+    twine::Builder::new()
+        .cargo_output(&mut cargo_output)
+        .build_translations(&["tests/fixtures/identical.ini"], "i18n.rs")
+        .unwrap();
+
+    assert!(!String::from_utf8(cargo_output)
+        .unwrap()
+        .contains("same translation"));
+}
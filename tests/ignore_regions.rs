@@ -0,0 +1,77 @@
+mod common;
+
+use common::{build, try_build};
+
+const INI: &str = "\
+[band_tool]
+    en = Tool
+    en-gb = Tool
+    fr = Outil
+    fr-be = Outil
+[colour]
+    en = Color
+    en-gb = Colour
+    fr = Couleur
+[n_files]
+    en.one = %d file
+    en.other = %d files
+    en-gb.other = %d files
+";
+
+#[test]
+fn ignore_regions() {
+    let mut cargo_output = Vec::new();
+    let code = build(
+        twine::Builder::new()
+            .ignore_regions(true)
+            .cargo_output(&mut cargo_output),
+        INI,
+    );
+
+    // unit variants
+    assert!(code.contains("pub enum Lang {"));
+    assert!(code.contains("    En,\n"));
+    assert!(code.contains("    Fr,\n"));
+    assert!(!code.contains("En(&'static str)"));
+    assert!(code.contains("&Lang::En,"));
+    assert!(code.contains("\"fr\" => Some(Lang::Fr),"));
+
+    // the last translation of the regions is used
+    assert!(code.contains("$crate::Lang::En => \"Colour\","));
+    assert!(!code.contains("\"Color\""));
+
+    assert_eq!(
+        String::from_utf8(cargo_output).unwrap(),
+        "cargo:warning=the regions of \"en\" have different translations of key \"colour\", \
+        the last one is used\n",
+    );
+}
+
+#[test]
+fn ignore_regions_strict() {
+    let err = try_build(
+        twine::Builder::new()
+            .ignore_regions(true)
+            .strict(true)
+            .cargo_output(std::io::sink()),
+        INI,
+    )
+    .unwrap_err();
+
+    match err {
+        twine::TwineError::RegionConflict { key, lang } => {
+            assert_eq!(key, "colour");
+            assert_eq!(lang, "en");
+        }
+        err => panic!("unexpected error: {}", err),
+    }
+}
+
+#[test]
+fn keep_regions() {
+    let code = build(twine::Builder::new().cargo_output(std::io::sink()), INI);
+
+    assert!(code.contains("En(&'static str)"));
+    assert!(code.contains("\"Colour\""));
+    assert!(code.contains("\"Color\""));
+}
@@ -0,0 +1,24 @@
+use std::fs;
+
+#[test]
+fn indentation() {
+    let out_dir = tempfile::tempdir().unwrap();
+    std::env::set_var("OUT_DIR", out_dir.as_ref().as_os_str());
+
+    twine::Builder::new()
+        .build_translations(&["tests/fixtures/bands.ini"], "spaces.rs")
+        .unwrap();
+    twine::Builder::new()
+        .indentation("\t")
+        .build_translations(&["tests/fixtures/bands.ini"], "tabs.rs")
+        .unwrap();
+    let spaces = fs::read_to_string(out_dir.as_ref().join("spaces.rs")).unwrap();
+    let tabs = fs::read_to_string(out_dir.as_ref().join("tabs.rs")).unwrap();
+    println!("{}\n========\n{}", spaces, tabs);
+
+    assert!(spaces.contains("\n    pub fn all_languages() -> &'static [&'static Lang] {\n"));
+    assert!(tabs.contains("\n\tpub fn all_languages() -> &'static [&'static Lang] {\n"));
+    assert!(tabs.contains("\n\t\t\t$crate::Lang::Fr(_) => format!(\"Outil\" $(, $fmt_args)*),\n"));
+    assert!(tabs.lines().all(|line| !line.starts_with(' ')));
+    assert_eq!(tabs.replace('\t', "    "), spaces);
+}
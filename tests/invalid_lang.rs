@@ -0,0 +1,89 @@
+mod common;
+
+use common::{build, try_build};
+
+#[test]
+fn invalid_plural_category() {
+    let err = try_build(
+        twine::Builder::new(),
+        "[n_files]\n    en.one = %d file\n    en.bogus = %d files\n",
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "could not read Twine INI file: invalid plural category at line 3: en.bogus",
+    );
+    assert!(matches!(err, twine::TwineError::Parse { file: None, .. }));
+}
+
+#[test]
+fn invalid_language() {
+    let err = try_build(
+        twine::Builder::new(),
+        "[band_tool]\n    en = Tool\n    %% = x\n",
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "could not read Twine INI file: invalid language at line 3: %%",
+    );
+}
+
+#[test]
+fn invalid_language_suffix() {
+    for lang in ["fr!!", "en-gb-xx", "en.one.bogus"] {
+        let err = try_build(
+            twine::Builder::new(),
+            &format!("[band_tool]\n    en = Tool\n    {} = y\n", lang),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "could not read Twine INI file: invalid language at line 3: {}",
+                lang
+            ),
+        );
+    }
+}
+
+#[test]
+fn invalid_language_declared() {
+    let err = try_build(
+        twine::Builder::new(),
+        "[__languages__]\n    en, %%\n[band_tool]\n    en = Tool\n",
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "could not read Twine INI file: invalid language at line 2: %%",
+    );
+}
+
+#[test]
+fn invalid_language_with_file_name() {
+    let err = twine::Builder::new()
+        .cargo_output(std::io::sink())
+        .build_translations_from_named_str(
+            &[(
+                "translations/bad.ini",
+                "[n_files]\n    en.lots = %d files\n",
+            )],
+            "i18n.rs",
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "could not read Twine INI file translations/bad.ini: invalid plural category at line 2: \
+        en.lots",
+    );
+}
+
+#[test]
+fn valid_languages() {
+    let code = build(
+        twine::Builder::new(),
+        "[n_files]\n    en.one = %d file\n    en-gb,au.other = %d files\n",
+    );
+    assert!(code.contains("macro_rules! t"));
+}
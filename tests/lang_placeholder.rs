@@ -0,0 +1,26 @@
+use std::fs;
+
+#[test]
+fn lang_placeholder() {
+    let out_dir = tempfile::tempdir().unwrap();
+    std::env::set_var("OUT_DIR", out_dir.as_ref().as_os_str());
+
+    twine::Builder::new()
+        .build_translations(&["tests/fixtures/lang_placeholder.ini"], "i18n.rs")
+        .unwrap();
+    let generated = fs::read_to_string(out_dir.as_ref().join("i18n.rs")).unwrap();
+
+    // every arm binds the language requested, the fallback arm too
+    for arm in &[
+        r#"current_lang @ $crate::Lang::En("gb") => format!("<html lang=\"{current_lang}\">" $(, $fmt_args)*, current_lang = current_lang),"#,
+        r#"current_lang @ $crate::Lang::En(_) => format!("<html lang=\"{current_lang}\">" $(, $fmt_args)*, current_lang = current_lang),"#,
+        r#"current_lang @ $crate::Lang::Fr(_) => format!("<html lang=\"{current_lang}\">" $(, $fmt_args)*, current_lang = current_lang),"#,
+        r#"current_lang => format!("<html lang=\"{current_lang}\">" $(, $fmt_args)*, current_lang = current_lang),"#,
+    ] {
+        assert!(generated.contains(arm), "missing arm: {}", arm);
+    }
+    // the placeholder does not use an argument
+    assert!(generated.contains(r#"current_lang @ $crate::Lang::En(_) => format!("Hello {:} ({current_lang})" $(, $fmt_args)*, current_lang = current_lang),"#));
+    assert!(generated.contains(r#"$crate::Lang::Fr(_) => format!("Bonjour {:}" $(, $fmt_args)*),"#));
+    assert!(generated.contains("t!(format_greeting) expects 1 argument, got 0"));
+}
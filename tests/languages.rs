@@ -0,0 +1,56 @@
+mod common;
+
+use common::{build, fixture};
+
+#[test]
+fn languages() {
+    let mut cargo_output = Vec::new();
+    let generated = build(
+        twine::Builder::new().cargo_output(&mut cargo_output),
+        &fixture("languages.ini"),
+    );
+    let cargo_output = String::from_utf8(cargo_output).unwrap();
+
+    assert_eq!(
+        cargo_output,
+        "cargo:warning=translation \"eng\" of key \"band_the_doors\" uses a language that is not \
+        declared\n",
+    );
+    // the section is not a key
+    assert!(!generated.contains("__languages__"));
+    assert!(!generated.contains("Key::Languages"));
+    // the languages not declared are only reported
+    assert!(generated.contains("Eng(&'static str),"));
+}
+
+#[test]
+fn unknown_language_strict() {
+    let err = twine::Builder::new()
+        .strict(true)
+        .cargo_output(std::io::sink())
+        .build_translations(&["tests/fixtures/languages.ini"], "i18n.rs")
+        .unwrap_err();
+
+    match err {
+        twine::TwineError::UnknownLanguage { key, lang } => {
+            assert_eq!(key, "band_the_doors");
+            assert_eq!(lang, "eng");
+        }
+        err => panic!("unexpected error: {}", err),
+    }
+}
+
+#[test]
+fn languages_catalog() {
+    let catalog: twine::Catalog = "[__languages__]\n    en, fr\n[band_tool]\n    en = Tool\n"
+        .parse()
+        .unwrap();
+    let mut output = Vec::new();
+    catalog.write_ini(&mut output).unwrap();
+
+    assert_eq!(catalog.keys(), vec!["band_tool"]);
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "[__languages__]\n    en\n    fr\n[band_tool]\n    en = Tool\n",
+    );
+}
@@ -0,0 +1,49 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// count the memory allocated to measure the peak during the generation
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+        PEAK.fetch_max(allocated, Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::SeqCst);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[test]
+fn large_catalog() {
+    let translations: String = (0..50_000)
+        .map(|i| {
+            format!(
+                "[band_{i}]\n    en = Band {i}: %s\n    fr = Groupe {i} : %s\n    de = Gruppe {i}: %s\n",
+            )
+        })
+        .collect();
+    let out_dir = tempfile::tempdir().unwrap();
+    std::env::set_var("OUT_DIR", out_dir.as_ref().as_os_str());
+
+    let before = ALLOCATED.load(Ordering::SeqCst);
+    PEAK.store(before, Ordering::SeqCst);
+    twine::build_translations_from_str(&[&translations], "i18n.rs").unwrap();
+    let peak = PEAK.load(Ordering::SeqCst) - before;
+
+    let size = std::fs::metadata(out_dir.as_ref().join("i18n.rs"))
+        .unwrap()
+        .len() as usize;
+    println!("generated: {} bytes, peak memory: {} bytes", size, peak);
+    // the generated code is written as it is generated instead of being buffered
+    assert!(peak < size);
+}
@@ -0,0 +1,74 @@
+mod common;
+
+#[cfg(feature = "locale-numbers")]
+#[test]
+fn locale_numbers() {
+    let translations = r#"
+        [format_amount]
+            en = %.2f EUR
+            de = %.2f EUR
+            de-ch = CHF %.2f
+            eo = %.2f EUR
+        [format_hexadecimal]
+            en = %x
+            de = %x
+        "#;
+    let generated = common::build(twine::Builder::new().locale_numbers(true), translations);
+
+    assert!(generated.contains(
+        r#"$crate::Lang::En(_) => format!("{:.2e} EUR" $(, $crate::LocaleNumber(&$fmt_args, ",", "."))*),"#
+    ));
+    assert!(generated.contains(
+        r#"$crate::Lang::De(_) => format!("{:.2e} EUR" $(, $crate::LocaleNumber(&$fmt_args, ".", ","))*),"#
+    ));
+    assert!(generated.contains(
+        r#"$crate::Lang::De("ch") => format!("CHF {:.2e}" $(, $crate::LocaleNumber(&$fmt_args, "’", "."))*),"#
+    ));
+    // the languages missing in the table and the other placeholders are not changed
+    assert!(generated.contains(r#"$crate::Lang::Eo(_) => format!("{:.2} EUR" $(, $fmt_args)*),"#));
+    assert!(generated.contains(r#"$crate::Lang::De(_) => format!("{:x}" $(, $fmt_args)*),"#));
+    assert!(generated.contains("pub struct LocaleNumber<T>"));
+
+    // disabled by default
+    let generated = common::build(twine::Builder::new(), translations);
+
+    assert!(!generated.contains("LocaleNumber"));
+}
+
+#[cfg(feature = "locale-numbers")]
+#[test]
+fn locale_count() {
+    let translations = r#"
+        [n_files]
+            en.one = # file
+            en.other = # files
+            de.one = # Datei
+            de.other = # Dateien
+            eo.one = # dosiero
+            eo.other = # dosieroj
+            fr.one = %d fichier
+            fr.other = %d fichiers
+        "#;
+    let generated = common::build(twine::Builder::new().locale_count(true), translations);
+
+    assert!(generated.contains(
+        r#"($crate::Lang::En(_), _) => format!("{0:e} files", $crate::LocaleNumber(&count, ",", ".") $(, $fmt_args)*),"#
+    ));
+    assert!(generated.contains(
+        r#"($crate::Lang::De(_), _) => format!("{0:e} Dateien", $crate::LocaleNumber(&count, ".", ",") $(, $fmt_args)*),"#
+    ));
+    // the languages missing in the table and the other placeholders are not changed
+    assert!(generated.contains(
+        r#"($crate::Lang::Eo(_), _) => format!("{0} dosieroj", count $(, $fmt_args)*),"#
+    ));
+    assert!(generated.contains(
+        r#"($crate::Lang::Fr(_), _) => format!("{:} fichiers", count $(, $fmt_args)*),"#
+    ));
+    assert!(generated.contains("pub struct LocaleNumber<T>"));
+
+    // disabled by default, even with `locale_numbers`
+    let generated = common::build(twine::Builder::new().locale_numbers(true), translations);
+
+    assert!(generated
+        .contains(r#"($crate::Lang::En(_), _) => format!("{0} files", count $(, $fmt_args)*),"#));
+}
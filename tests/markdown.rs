@@ -0,0 +1,51 @@
+mod common;
+
+use common::{build, fixture};
+
+use twine::Catalog;
+
+#[test]
+fn is_markdown() {
+    let code = build(twine::Builder::new(), &fixture("markdown.ini"));
+
+    assert!(code.contains("pub fn is_markdown(key: Key) -> bool {"));
+    assert!(code.contains("matches!(key, Key::HelpIntro | Key::LegalNotice)"));
+    // the markdown is not rendered
+    assert!(code.contains("Press **Save** to keep the changes."));
+}
+
+#[test]
+fn is_markdown_without_markdown_keys() {
+    let code = build(twine::Builder::new(), "[band_tool]\n    en = Tool\n");
+
+    assert!(code.contains("pub fn is_markdown(key: Key) -> bool {"));
+    assert!(code.contains("let _ = key;"));
+}
+
+#[test]
+fn catalog_is_markdown() {
+    let ini = std::fs::read_to_string("tests/fixtures/markdown.ini").unwrap();
+    let catalog: Catalog = ini.parse().unwrap();
+
+    assert!(catalog.is_markdown("help_intro"));
+    assert!(catalog.is_markdown("legal_notice"));
+    assert!(!catalog.is_markdown("help_title"));
+    assert!(!catalog.is_markdown("band_tool"));
+    assert!(!catalog.is_markdown("unknown"));
+    assert_eq!(catalog.languages("help_intro"), ["en", "fr"]);
+
+    let mut binary = Vec::new();
+    catalog.serialize_binary(&mut binary).unwrap();
+    let reloaded = Catalog::load_binary(&mut binary.as_slice()).unwrap();
+    assert!(reloaded.is_markdown("help_intro"));
+    assert!(!reloaded.is_markdown("band_tool"));
+}
+
+#[test]
+fn invalid_format() {
+    let err = "[help_intro]\n    format = html\n"
+        .parse::<Catalog>()
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert_eq!(err.to_string(), "invalid format at line 2: html");
+}
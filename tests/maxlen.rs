@@ -0,0 +1,44 @@
+#[test]
+fn maxlen_warning() {
+    let mut cargo_output = Vec::new();
+    twine::Builder::new()
+        .cargo_output(&mut cargo_output)
+        .build_translations_to_writer(
+            &mut [std::fs::File::open("tests/fixtures/maxlen.ini").unwrap()],
+            &mut std::io::sink(),
+        )
+        .unwrap();
+    let cargo_output = String::from_utf8(cargo_output).unwrap();
+
+    assert_eq!(
+        cargo_output,
+        "\
+        cargo:warning=translation \"fr\" of key \"button_save\" is too long: \
+        29 characters (max 12)\n\
+        ",
+    );
+}
+
+#[test]
+fn maxlen_strict() {
+    let err = twine::Builder::new()
+        .strict(true)
+        .cargo_output(std::io::sink())
+        .build_translations(&["tests/fixtures/maxlen.ini"], "i18n.rs")
+        .unwrap_err();
+
+    match err {
+        twine::TwineError::MaxLengthExceeded {
+            key,
+            lang,
+            max,
+            actual,
+        } => {
+            assert_eq!(key, "button_save");
+            assert_eq!(lang, "fr");
+            assert_eq!(max, 12);
+            assert_eq!(actual, 29);
+        }
+        err => panic!("unexpected error: {}", err),
+    }
+}
@@ -0,0 +1,8 @@
+fn main() {
+    twine::Builder::new()
+        .named_args(true)
+        .typed_functions(true)
+        .local_macros("ui::i18n")
+        .build_translations(&["../test-crate/translations.ini"], "i18n.rs")
+        .unwrap();
+}
@@ -0,0 +1,45 @@
+// the macros are generated in a module without `#[macro_export]`: the lint
+// `macro_expanded_macro_exports_accessed_by_absolute_paths` does not need to be disabled
+pub mod ui {
+    pub mod i18n {
+        include!(concat!(env!("OUT_DIR"), "/i18n.rs"));
+    }
+}
+
+pub mod views {
+    use crate::ui::i18n::Lang;
+
+    pub fn band(lang: Lang) -> String {
+        crate::ui::i18n::t!(band_tool => lang)
+    }
+
+    pub fn greeting(lang: Lang) -> String {
+        crate::ui::i18n::t!(greeting, name = "Sam", count = 3 => lang)
+    }
+
+    pub fn files(lang: Lang, count: i64) -> String {
+        crate::ui::i18n::plural!(n_files, count => lang)
+    }
+
+    pub fn current_band() -> String {
+        crate::ui::i18n::tl!(band_tool)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ui::i18n::{set_lang, Lang};
+    use crate::views;
+
+    #[test]
+    fn scoped_path() {
+        assert_eq!(views::band(Lang::Fr("")), "Outil");
+        assert_eq!(
+            views::greeting(Lang::En("")),
+            "Hello Sam, you have 3 messages!"
+        );
+        assert_eq!(views::files(Lang::En(""), 2), "2 files");
+        set_lang(Lang::Fr(""));
+        assert_eq!(views::current_band(), "Outil");
+    }
+}
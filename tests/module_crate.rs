@@ -0,0 +1,27 @@
+#[test]
+fn module_crate() {
+    let manifest_path = "tests/module-crate/Cargo.toml";
+    for args in [
+        &[
+            "clippy",
+            "--manifest-path",
+            manifest_path,
+            "--all-targets",
+            "--",
+            "-D",
+            "warnings",
+        ][..],
+        &["test", "--manifest-path", manifest_path][..],
+    ] {
+        let output = std::process::Command::new("cargo")
+            .args(args)
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        println!("stdout:\n{}\n", stdout);
+        eprintln!("stderr:\n{}\n", stderr);
+        assert!(output.status.success());
+    }
+}
@@ -0,0 +1,43 @@
+use std::fs;
+
+#[test]
+fn named_str() {
+    let out_dir = tempfile::tempdir().unwrap();
+    std::env::set_var("OUT_DIR", out_dir.as_ref().as_os_str());
+
+    let mut cargo_output = Vec::new();
+    twine::Builder::new()
+        .cargo_output(&mut cargo_output)
+        .build_translations_from_named_str(
+            &[
+                ("translations/bands.ini", include_str!("fixtures/bands.ini")),
+                (
+                    "translations/formats.ini",
+                    include_str!("fixtures/formats.ini"),
+                ),
+            ],
+            "named.rs",
+        )
+        .unwrap();
+    let cargo_output = String::from_utf8(cargo_output).unwrap();
+    let named = fs::read_to_string(out_dir.as_ref().join("named.rs")).unwrap();
+
+    assert_eq!(
+        cargo_output,
+        "\
+        cargo:rerun-if-changed=translations/bands.ini\n\
+        cargo:rerun-if-changed=translations/formats.ini\n",
+    );
+
+    // the name does not change the generated code
+    twine::build_translations_from_str(
+        &[
+            include_str!("fixtures/bands.ini"),
+            include_str!("fixtures/formats.ini"),
+        ],
+        "unnamed.rs",
+    )
+    .unwrap();
+    let unnamed = fs::read_to_string(out_dir.as_ref().join("unnamed.rs")).unwrap();
+    assert_eq!(named, unnamed);
+}
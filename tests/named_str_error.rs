@@ -0,0 +1,14 @@
+#[test]
+fn named_str_error() {
+    let err = twine::Builder::new()
+        .cargo_output(std::io::sink())
+        .build_translations_from_named_str(
+            &[("translations/bad.ini", "[band_tool]\n    maxlen = many\n")],
+            "i18n.rs",
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "could not read Twine INI file translations/bad.ini: invalid maxlen at line 2: many",
+    );
+}
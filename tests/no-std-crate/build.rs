@@ -0,0 +1,9 @@
+fn main() {
+    twine::Builder::new()
+        .lang_code(true)
+        .named_args(true)
+        .typed_functions(true)
+        .no_std(true)
+        .build_translations(&["../test-crate/translations.ini"], "i18n.rs")
+        .unwrap();
+}
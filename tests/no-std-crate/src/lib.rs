@@ -0,0 +1,30 @@
+#![no_std]
+#![allow(macro_expanded_macro_exports_accessed_by_absolute_paths)]
+
+extern crate alloc;
+
+use alloc::string::String;
+use core::fmt::Write;
+
+include!(concat!(env!("OUT_DIR"), "/i18n.rs"));
+
+pub fn band(lang: Lang) -> String {
+    t!(band_tool => lang)
+}
+
+pub fn codes() -> alloc::vec::Vec<String> {
+    Lang::all_codes()
+}
+
+pub fn format(lang: Lang) -> String {
+    t!(format_string, "Hello", "World" => lang)
+}
+
+pub fn plural(lang: Lang, count: i64) -> String {
+    plural!(n_files, count => lang)
+}
+
+pub fn write_into(lang: Lang, buf: &mut String) -> core::fmt::Result {
+    t_into!(buf, band_tool => lang)?;
+    buf.write_char('\n')
+}
@@ -0,0 +1,20 @@
+#[test]
+fn no_std() {
+    let output = std::process::Command::new("cargo")
+        .args([
+            "clippy",
+            "--manifest-path",
+            "tests/no-std-crate/Cargo.toml",
+            "--",
+            "-D",
+            "warnings",
+        ])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    println!("stdout:\n{}\n", stdout);
+    eprintln!("stderr:\n{}\n", stderr);
+    assert!(output.status.success());
+}
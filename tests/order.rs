@@ -0,0 +1,114 @@
+mod common;
+
+use common::{build, fixture};
+
+// the position of every needle in the haystack, panics if one is missing
+fn positions(haystack: &str, needles: &[&str]) -> Vec<usize> {
+    needles
+        .iter()
+        .map(|needle| {
+            haystack
+                .find(needle)
+                .unwrap_or_else(|| panic!("{:?} not found", needle))
+        })
+        .collect()
+}
+
+fn is_sorted(positions: &[usize]) -> bool {
+    positions.windows(2).all(|x| x[0] < x[1])
+}
+
+fn generate() -> String {
+    build(
+        twine::Builder::new()
+            .typed_functions(true)
+            .cargo_output(std::io::sink()),
+        &fixture("order.ini"),
+    )
+}
+
+#[test]
+fn order() {
+    let generated = generate();
+
+    // lower order first, then alphabetically, the keys without order count as `0`
+    let (t, rest) = generated.split_once("macro_rules! t {").unwrap();
+    let (t, _) = rest.split_once("macro_rules!").unwrap_or((t, ""));
+    assert!(is_sorted(&positions(
+        t,
+        &[
+            "(app_title ",
+            "(band_tool ",
+            "(band_abba ",
+            "(band_the_doors "
+        ],
+    )));
+    let (_, plural) = generated.split_once("macro_rules! plural {").unwrap();
+    assert!(plural.contains("(n_files,"));
+
+    let (_, key) = generated.split_once("pub enum Key {").unwrap();
+    let (key, rest) = key.split_once('}').unwrap();
+    assert!(is_sorted(&positions(
+        key,
+        &[
+            "AppTitle,",
+            "NFiles,",
+            "BandTool,",
+            "BandAbba,",
+            "BandTheDoors,"
+        ],
+    )));
+    assert!(is_sorted(&positions(
+        rest,
+        &[
+            "Key::AppTitle =>",
+            "Key::NFiles =>",
+            "Key::BandTool =>",
+            "Key::BandAbba =>",
+            "Key::BandTheDoors =>",
+        ],
+    )));
+
+    assert!(is_sorted(&positions(
+        &generated,
+        &[
+            "pub fn app_title(",
+            "pub fn n_files(",
+            "pub fn band_tool(",
+            "pub fn band_abba(",
+            "pub fn band_the_doors(",
+        ],
+    )));
+
+    // the behavior does not change: `Key::ALL` is still sorted by variant
+    let (_, all) = generated.split_once("pub const ALL: &[Key] = &[").unwrap();
+    let (all, _) = all.split_once("];").unwrap();
+    assert!(is_sorted(&positions(
+        all,
+        &[
+            "Key::AppTitle,",
+            "Key::BandAbba,",
+            "Key::BandTheDoors,",
+            "Key::BandTool,",
+            "Key::NFiles,",
+        ],
+    )));
+
+    // the output is deterministic
+    assert_eq!(generated, generate());
+}
+
+#[test]
+fn invalid_order() {
+    let err = twine::Builder::new()
+        .cargo_output(std::io::sink())
+        .build_translations_to_writer(
+            &mut ["[band_tool]\n    order = first\n    en = Tool\n".as_bytes()],
+            &mut Vec::new(),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "could not read Twine INI file: invalid order at line 2: first",
+    );
+}
@@ -0,0 +1,214 @@
+use proptest::prelude::*;
+use proptest::sample::{select, subsequence};
+use twine::{Catalog, CatalogDiff};
+
+// fragments of the Twine INI format, mixed to reach the edge cases of the parser
+const FRAGMENTS: &[&str] = &[
+    "[",
+    "]",
+    r"\]",
+    "[band_tool]",
+    "[__languages__]",
+    "[__defaults__]",
+    "[__schema__]",
+    "=",
+    " = ",
+    "    ",
+    "\t",
+    "\n",
+    "\r\n",
+    "\r",
+    ";",
+    "#",
+    ",",
+    ".",
+    "-",
+    "en",
+    "fr-be",
+    "en-gb,au",
+    "en.one",
+    "fr.other",
+    "maxlen",
+    "order",
+    "deprecated",
+    "context",
+    "fuzzy",
+    "id",
+    "tm_id",
+    "format",
+    "markdown",
+    "-1",
+    "10",
+    "99999999999999999999",
+    "%d",
+    "%1$s",
+    "%99999999999999999999$d",
+    "%",
+    "{name}",
+    "{",
+    "}",
+    "@{band_tool}",
+    "é",
+    "😀",
+    "\u{0}",
+];
+
+const KEYS: &[&str] = &["band_tool", "n_files", "a]b", "clé", "app title", "x"];
+const LANGS: &[&str] = &["en", "fr", "fr-be", "de-ch", "en.one", "en.other"];
+const VALUES: &[&str] = &[
+    "Tool",
+    "%d files",
+    "{name}",
+    "@{band_tool}",
+    "a = b",
+    "😀",
+    "%",
+];
+
+// random lines made of fragments of the format
+fn fragments() -> impl Strategy<Value = Vec<u8>> {
+    prop::collection::vec(select(FRAGMENTS), 0..64).prop_map(|x| x.concat().into_bytes())
+}
+
+// distinct values in a random order
+fn shuffled(values: &'static [&'static str]) -> impl Strategy<Value = Vec<&'static str>> {
+    subsequence(values, 0..=values.len()).prop_shuffle()
+}
+
+// a translation with an optional comment before it
+fn translation(lang: &'static str) -> impl Strategy<Value = String> {
+    (any::<bool>(), prop::collection::vec(select(VALUES), 1..4)).prop_map(
+        move |(comment, values)| {
+            let comment = if comment { "    # comment\n" } else { "" };
+            format!("{}    {} = {}\n", comment, lang, values.join(" "))
+        },
+    )
+}
+
+// a section with random metadata and translations in distinct languages
+fn section(key: &'static str) -> impl Strategy<Value = String> {
+    (
+        any::<bool>(),
+        prop::option::of(0..100usize),
+        prop::option::of(-100..100i64),
+        prop::option::of(any::<u32>()),
+        any::<bool>(),
+        prop::option::of(select(KEYS)),
+        prop::option::of(select(LANGS)),
+        shuffled(LANGS)
+            .prop_flat_map(|langs| langs.into_iter().map(translation).collect::<Vec<_>>()),
+    )
+        .prop_map(
+            move |(comment, maxlen, order, id, markdown, deprecated, fuzzy, translations)| {
+                let mut ini = String::new();
+                if comment {
+                    ini.push_str("; comment\n");
+                }
+                ini.push_str(&format!("[{}]\n", key.replace(']', r"\]")));
+                if let Some(maxlen) = maxlen {
+                    ini.push_str(&format!("    maxlen = {}\n", maxlen));
+                }
+                if let Some(order) = order {
+                    ini.push_str(&format!("    order = {}\n", order));
+                }
+                if let Some(id) = id {
+                    ini.push_str(&format!("    tm_id = CROWDIN-{}\n", id));
+                }
+                if markdown {
+                    ini.push_str("    format = markdown\n");
+                }
+                if let Some(deprecated) = deprecated {
+                    ini.push_str(&format!("    deprecated = {}\n", deprecated));
+                }
+                if let Some(fuzzy) = fuzzy {
+                    ini.push_str(&format!("    fuzzy = {}\n", fuzzy));
+                }
+                ini.push_str(&translations.concat());
+                ini
+            },
+        )
+}
+
+// a random but well-formed catalog: distinct keys with metadata, comments and translations in
+// distinct languages
+fn catalog() -> impl Strategy<Value = String> {
+    shuffled(KEYS)
+        .prop_flat_map(|keys| keys.into_iter().map(section).collect::<Vec<_>>())
+        .prop_map(|sections| sections.concat())
+}
+
+// neither the parser nor the generation panic: they return an error or a catalog that can be
+// written and code
+fn check(input: &[u8]) {
+    if let Ok(catalog) = Catalog::from_reader(&mut &input[..]) {
+        catalog.write_ini(&mut Vec::new()).unwrap();
+    }
+    let _ = twine::Builder::new()
+        .cargo_output(std::io::sink())
+        .build_translations_to_writer(&mut [input], &mut Vec::new());
+}
+
+// a well-formed catalog is written and read back without change
+fn check_round_trip(input: &str) {
+    let catalog: Catalog = input
+        .parse()
+        .unwrap_or_else(|err| panic!("{}: {:?}", err, input));
+    let mut ini = Vec::new();
+    catalog.write_ini(&mut ini).unwrap();
+    let ini = String::from_utf8(ini).unwrap();
+    let reloaded: Catalog = ini
+        .parse()
+        .unwrap_or_else(|err| panic!("{}: {:?}", err, ini));
+    assert_eq!(
+        catalog.diff(&reloaded),
+        CatalogDiff::default(),
+        "{:?}",
+        input
+    );
+    let mut rewritten = Vec::new();
+    reloaded.write_ini(&mut rewritten).unwrap();
+    assert_eq!(ini, String::from_utf8(rewritten).unwrap(), "{:?}", input);
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(1000))]
+
+    #[test]
+    fn random_bytes_never_panic(input in prop::collection::vec(any::<u8>(), 0..256)) {
+        check(&input);
+    }
+
+    #[test]
+    fn random_fragments_never_panic(input in fragments()) {
+        check(&input);
+    }
+
+    #[test]
+    fn random_catalogs_round_trip(input in catalog()) {
+        check_round_trip(&input);
+        check(input.as_bytes());
+    }
+}
+
+#[test]
+fn key_value_outside_section() {
+    let err = Catalog::from_reader(&mut "en = Tool\n".as_bytes()).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert_eq!(err.to_string(), "key-value outside section at line 1");
+}
+
+#[test]
+fn oversized_placeholder_position() {
+    let err = twine::Builder::new()
+        .cargo_output(std::io::sink())
+        .build_translations_to_writer(
+            &mut [&b"[a]\n    en = %99999999999999999999$d\n"[..]],
+            &mut Vec::new(),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "could not read Twine INI file: invalid placeholder position at line 2: \
+         %99999999999999999999$d"
+    );
+}
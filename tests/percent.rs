@@ -0,0 +1,46 @@
+use std::fs;
+
+#[test]
+fn percent() {
+    let translations = r#"
+        [escaped]
+            en = 50%%
+        [trailing]
+            en = 50%
+        [before_space]
+            en = 50 % off
+        [unsupported]
+            en = %z and %5 and %
+        [mixed]
+            en = %d%% of %s %
+        [percentage_trailing]
+            en = %.0f%
+        [percentage_escaped]
+            en = %.1f %%
+        [percentage_integer]
+            en = %d%%
+        "#;
+    let out_dir = tempfile::tempdir().unwrap();
+    std::env::set_var("OUT_DIR", out_dir.as_ref().as_os_str());
+
+    twine::build_translations_from_str(&[translations], "i18n.rs").unwrap();
+    let generated = fs::read_to_string(out_dir.as_ref().join("i18n.rs")).unwrap();
+    println!("{}", generated);
+
+    assert!(generated.contains(r#"_ => format!("50%" $(, $fmt_args)*),"#));
+    assert!(generated.contains(r#"_ => format!("50 % off" $(, $fmt_args)*),"#));
+    assert!(generated.contains(r#"_ => format!("%z and %5 and %" $(, $fmt_args)*),"#));
+    assert!(generated.contains(r#"_ => format!("{:}% of {:} %" $(, $fmt_args)*),"#));
+    assert!(generated.contains(r#"(mixed, $_arg0:expr => $lang:expr $(; $_fallback:literal)?)"#));
+    // the `%` following a placeholder is a literal `%`, not the start of another placeholder
+    assert!(generated.contains(r#"_ => format!("{:.0}%" $(, $fmt_args)*),"#));
+    assert!(generated.contains(r#"_ => format!("{:.1} %" $(, $fmt_args)*),"#));
+    assert!(generated.contains(r#"_ => format!("{:}%" $(, $fmt_args)*),"#));
+    for key in &[
+        "percentage_trailing",
+        "percentage_escaped",
+        "percentage_integer",
+    ] {
+        assert!(generated.contains(&format!("t!({}) expects 1 argument, got 0", key)));
+    }
+}
@@ -0,0 +1,44 @@
+use std::fs;
+
+#[test]
+fn pseudolocale() {
+    let translations = r#"
+        [band_tool]
+            en = Tool
+            fr = Outil
+        [format_string]
+            en = %s, %1$@ {name}!
+            fr = %s, %1$@ {name} !
+        [format_unicode]
+            en-gb = é "quoted" 100%%
+            fr = é « entre guillemets » 100 %%
+        [n_files]
+            en.one = # file
+            en.other = %d files
+            fr.one = # fichier
+            fr.other = %d fichiers
+        "#;
+    let out_dir = tempfile::tempdir().unwrap();
+    std::env::set_var("OUT_DIR", out_dir.as_ref().as_os_str());
+
+    twine::Builder::new()
+        .pseudolocale(true)
+        .named_args(true)
+        .build_translations_from_str(&[translations], "i18n.rs")
+        .unwrap();
+    let generated = fs::read_to_string(out_dir.as_ref().join("i18n.rs")).unwrap();
+    println!("{}", generated);
+
+    assert!(
+        generated.contains(r#"$crate::Lang::Pseudo(_) => format!("[Ţööļ!!]" $(, $fmt_args)*),"#)
+    );
+    assert!(generated
+        .contains(r#"$crate::Lang::Pseudo(_) => format!("[{:}, {0:} {name}!]" $(, $fmt_args)*),"#));
+    assert!(generated.contains(
+        r#"$crate::Lang::Pseudo(_) => format!("[é \"qûöţéð\" 100%!!!]" $(, $fmt_args)*),"#
+    ));
+    assert!(generated.contains(
+        r#"($crate::Lang::Pseudo(_), $crate::PluralCategory::One) => format!("[{0} fîļé!!]", count"#
+    ));
+    assert!(generated.contains("Pseudo(&'static str),"));
+}
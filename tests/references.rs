@@ -0,0 +1,66 @@
+mod common;
+
+use common::{build, fixture};
+
+#[test]
+fn references() {
+    let generated = build(twine::Builder::new(), &fixture("references.ini"));
+
+    assert!(!generated.contains("$t("));
+    assert!(generated.contains(
+        r#"$crate::Lang::En("gb") => format!("Welcome to Twine, mate!" $(, $fmt_args)*),"#
+    ));
+    assert!(generated
+        .contains(r#"$crate::Lang::Nl(_) => format!("Welkom bij Twijn!" $(, $fmt_args)*),"#));
+    // the references are resolved recursively with the region of the translation
+    assert!(generated.contains(
+        r#"$crate::Lang::En(_) => format!("Welcome to Twine! Hello {:}." $(, $fmt_args)*),"#
+    ));
+    assert!(generated.contains(
+        r#"$crate::Lang::Fr(_) => format!("Bienvenue dans Twine ! Bonjour {:}." $(, $fmt_args)*),"#
+    ));
+    assert!(generated.contains(
+        r#"($crate::Lang::Nl(_), _) => format!("{:} bestanden in Twijn", count $(, $fmt_args)*),"#
+    ));
+}
+
+#[test]
+fn unresolved_reference() {
+    let err = twine::Builder::new()
+        .build_translations_from_str(
+            &["[welcome]\n    en = Welcome to $t(app_name)!\n    fr = Bienvenue !\n[app_name]\n    fr = Twine\n"],
+            "i18n.rs",
+        )
+        .unwrap_err();
+
+    match err {
+        twine::TwineError::UnresolvedReference {
+            key,
+            lang,
+            reference,
+        } => {
+            assert_eq!(key, "welcome");
+            assert_eq!(lang, "en");
+            assert_eq!(reference, "app_name");
+        }
+        err => panic!("unexpected error: {}", err),
+    }
+}
+
+#[test]
+fn reference_cycle() {
+    let err = twine::Builder::new()
+        .build_translations_from_str(
+            &["[a]\n    en = $t(b)\n[b]\n    en = $t(c)\n[c]\n    en = $t(a)\n"],
+            "i18n.rs",
+        )
+        .unwrap_err();
+
+    match err {
+        twine::TwineError::ReferenceCycle { lang, keys } => {
+            assert_eq!(lang, "en");
+            assert_eq!(keys, vec!["a", "b", "c", "a"]);
+        }
+        err => panic!("unexpected error: {}", err),
+    }
+}
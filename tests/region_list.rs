@@ -0,0 +1,36 @@
+use std::fs;
+use twine::Catalog;
+
+#[test]
+fn region_list() {
+    let catalog =
+        Catalog::from_reader(&mut fs::File::open("tests/fixtures/regions.ini").unwrap()).unwrap();
+    assert_eq!(
+        catalog.languages("color"),
+        vec!["en", "en-gb", "en-au", "en-nz", "fr"],
+    );
+    for lang in &["en-gb", "en-au", "en-nz"] {
+        assert_eq!(catalog.get("color", lang), Some("colour"));
+    }
+    assert_eq!(catalog.get("n_colors", "en-au.other"), Some("%d colours"));
+
+    let out_dir = tempfile::tempdir().unwrap();
+    std::env::set_var("OUT_DIR", out_dir.as_ref().as_os_str());
+
+    twine::build_translations(&["tests/fixtures/regions.ini"], "i18n.rs").unwrap();
+    let generated = fs::read_to_string(out_dir.as_ref().join("i18n.rs")).unwrap();
+    println!("{}", generated);
+
+    for region in &["gb", "au", "nz"] {
+        assert!(generated.contains(&format!(
+            r#"$crate::Lang::En({:?}) => format!("colour" $(, $fmt_args)*),"#,
+            region,
+        )));
+    }
+    for region in &["gb", "au"] {
+        assert!(generated.contains(&format!(
+            r#"($crate::Lang::En({:?}), $crate::PluralCategory::One) => format!("{{:}} colour", count $(, $fmt_args)*),"#,
+            region,
+        )));
+    }
+}
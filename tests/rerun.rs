@@ -0,0 +1,22 @@
+#[test]
+fn rerun() {
+    let out_dir = tempfile::tempdir().unwrap();
+    std::env::set_var("OUT_DIR", out_dir.as_ref().as_os_str());
+
+    let mut cargo_output = Vec::new();
+    twine::build_translations_with_cargo_output(
+        &["tests/fixtures/bands.ini", "tests/fixtures/formats.ini"],
+        "i18n.rs",
+        &mut cargo_output,
+    )
+    .unwrap();
+    let cargo_output = String::from_utf8(cargo_output).unwrap();
+
+    assert_eq!(
+        cargo_output,
+        "\
+        cargo:rerun-if-changed=tests/fixtures/bands.ini\n\
+        cargo:rerun-if-changed=tests/fixtures/formats.ini\n\
+        ",
+    );
+}
@@ -0,0 +1,97 @@
+mod common;
+
+use common::{build, fixture};
+
+#[test]
+fn schema() {
+    let mut cargo_output = Vec::new();
+    let generated = build(
+        twine::Builder::new().cargo_output(&mut cargo_output),
+        &fixture("schema.ini"),
+    );
+    let cargo_output = String::from_utf8(cargo_output).unwrap();
+
+    // the explicit positions are the same as the implicit ones
+    assert_eq!(
+        cargo_output,
+        "\
+        cargo:warning=translation \"de\" of key \"order_confirmed\" uses the placeholders \
+        [\"%1$s\"] instead of [\"%1$s\", \"%2$d\"]\n\
+        cargo:warning=translation \"fr\" of key \"order_cancelled\" uses the placeholders \
+        [\"%1$s\"] instead of []\n\
+        cargo:warning=key \"order_shipped\" of the section [__schema__] does not exist\n",
+    );
+
+    // the section is not a key
+    assert!(!generated.contains("__schema__"));
+    assert!(!generated.contains("Schema"));
+}
+
+#[test]
+fn schema_strict() {
+    let err = twine::Builder::new()
+        .strict(true)
+        .cargo_output(std::io::sink())
+        .build_translations(&["tests/fixtures/schema.ini"], "i18n.rs")
+        .unwrap_err();
+
+    match err {
+        twine::TwineError::PlaceholderMismatch {
+            key,
+            lang,
+            expected,
+            actual,
+        } => {
+            assert_eq!(key, "order_confirmed");
+            assert_eq!(lang, "de");
+            assert_eq!(expected, vec!["%1$s", "%2$d"]);
+            assert_eq!(actual, vec!["%1$s"]);
+        }
+        err => panic!("unexpected error: {}", err),
+    }
+}
+
+#[test]
+fn schema_catalog() {
+    let catalog: twine::Catalog = std::fs::read_to_string("tests/fixtures/schema.ini")
+        .unwrap()
+        .parse()
+        .unwrap();
+
+    // the keys of the schema are neither keys nor languages
+    assert_eq!(
+        catalog.keys(),
+        vec!["greeting", "order_cancelled", "order_confirmed"]
+    );
+    assert!(catalog.validate(Default::default()).iter().all(|x| x.kind
+        != twine::DiagnosticKind::MissingLanguage
+        || x.lang.as_deref() == Some("de")));
+}
+
+#[test]
+fn schema_named_as_metadata() {
+    // the schema of a key named `format` is not the metadata `format`
+    let ini = "[__schema__]\n    format = %s\n[format]\n    en = %s\n    fr = %d\n";
+    let err = common::try_build(
+        twine::Builder::new()
+            .strict(true)
+            .cargo_output(std::io::sink()),
+        ini,
+    )
+    .unwrap_err();
+
+    match err {
+        twine::TwineError::PlaceholderMismatch {
+            key,
+            lang,
+            expected,
+            actual,
+        } => {
+            assert_eq!(key, "format");
+            assert_eq!(lang, "fr");
+            assert_eq!(expected, vec!["%1$s"]);
+            assert_eq!(actual, vec!["%1$d"]);
+        }
+        err => panic!("unexpected error: {}", err),
+    }
+}
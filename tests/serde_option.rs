@@ -0,0 +1,34 @@
+mod common;
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_option() {
+    let translations = r#"
+        [band_tool]
+            en = Tool
+            fr = Outil
+        "#;
+    // enabled by default with the feature
+    let generated = common::build(twine::Builder::new(), translations);
+
+    assert!(generated.contains("impl<'de> serde::Deserialize<'de> for Lang {"));
+    assert!(generated.contains("impl serde::Serialize for Lang {"));
+
+    let generated = common::build(twine::Builder::new().serde(false), translations);
+
+    assert!(!generated.contains("serde::"));
+}
+
+// the option exists without the feature so a build script can call it whatever the features
+#[cfg(not(feature = "serde"))]
+#[test]
+fn serde_option_without_feature() {
+    let mut code = Vec::new();
+    twine::Builder::new()
+        .serde(true)
+        .cargo_output(std::io::sink())
+        .build_translations_to_writer(&mut ["[band_tool]\n    en = Tool\n".as_bytes()], &mut code)
+        .unwrap();
+
+    assert!(!String::from_utf8(code).unwrap().contains("serde::"));
+}
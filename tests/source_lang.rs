@@ -0,0 +1,101 @@
+const TRANSLATIONS: &str = r#"
+    [band_tool]
+        en = Tool
+        fr = Outil
+    [band_the_doors]
+        en = The Doors
+    [band_the_jackson_5]
+        fr = Les 5 fils de Jack
+    [format_string]
+        en = %s, %@!
+        en-gb = %1$s, %2$s!
+        fr = %s !
+    [greeting]
+        en = Hello {name}!
+        fr = Bonjour {name} !
+    "#;
+
+#[test]
+fn source_lang_warnings() {
+    let mut cargo_output = Vec::new();
+    twine::Builder::new()
+        .source_lang("en")
+        .cargo_output(&mut cargo_output)
+        .build_translations_to_writer(&mut [TRANSLATIONS.as_bytes()], &mut std::io::sink())
+        .unwrap();
+    let cargo_output = String::from_utf8(cargo_output).unwrap();
+
+    assert_eq!(
+        cargo_output,
+        "\
+        cargo:warning=key \"band_the_doors\" is not translated in \"fr\"\n\
+        cargo:warning=key \"band_the_jackson_5\" is not translated in \"en\"\n\
+        cargo:warning=translation \"fr\" of key \"format_string\" uses the placeholders \
+        [\"%1$s\"] instead of [\"%1$s\", \"%2$s\"]\n\
+        ",
+    );
+}
+
+#[test]
+fn source_lang_orphan_strict() {
+    let err = twine::Builder::new()
+        .strict(true)
+        .source_lang("en")
+        .build_translations_from_str(
+            &["[band_the_jackson_5]\n    fr = Les 5 fils de Jack\n"],
+            "i18n.rs",
+        )
+        .unwrap_err();
+
+    match err {
+        twine::TwineError::MissingTranslation { key, lang } => {
+            assert_eq!(key, "band_the_jackson_5");
+            assert_eq!(lang, "en");
+        }
+        err => panic!("unexpected error: {}", err),
+    }
+}
+
+#[test]
+fn source_lang_missing_strict() {
+    let err = twine::Builder::new()
+        .strict(true)
+        .source_lang("en")
+        .build_translations_from_str(&[TRANSLATIONS], "i18n.rs")
+        .unwrap_err();
+
+    match err {
+        twine::TwineError::MissingTranslation { key, lang } => {
+            assert_eq!(key, "band_the_doors");
+            assert_eq!(lang, "fr");
+        }
+        err => panic!("unexpected error: {}", err),
+    }
+}
+
+#[test]
+fn source_lang_placeholders_strict() {
+    let err = twine::Builder::new()
+        .strict(true)
+        .source_lang("en")
+        .build_translations_from_str(
+            &["[format_string]\n    en = %s, %@!\n    fr = %2$s, %1$s {name} !\n"],
+            "i18n.rs",
+        )
+        .unwrap_err();
+
+    match err {
+        twine::TwineError::PlaceholderMismatch {
+            key,
+            lang,
+            expected,
+            actual,
+        } => {
+            assert_eq!(key, "format_string");
+            assert_eq!(lang, "fr");
+            assert_eq!(expected, vec!["%1$s", "%2$s"]);
+            assert_eq!(actual, vec!["%1$s", "%2$s", "{name}"]);
+        }
+        err => panic!("unexpected error: {}", err),
+    }
+}
@@ -0,0 +1,25 @@
+use std::fs;
+
+#[test]
+fn source_marker() {
+    let out_dir = tempfile::tempdir().unwrap();
+    std::env::set_var("OUT_DIR", out_dir.as_ref().as_os_str());
+
+    // the translations `@source` are not missing and use the placeholders of the source language
+    twine::Builder::new()
+        .strict(true)
+        .source_lang("en")
+        .build_translations(&["tests/fixtures/source_marker.ini"], "i18n.rs")
+        .unwrap();
+    let generated = fs::read_to_string(out_dir.as_ref().join("i18n.rs")).unwrap();
+    println!("{}", generated);
+
+    assert!(!generated.contains("@source"));
+    assert!(generated.contains(r#"$crate::Lang::Nl(_) => format!("Tool" $(, $fmt_args)*),"#));
+    assert!(generated.contains(r#"$crate::Lang::Fr(_) => format!("{:}, {:}!" $(, $fmt_args)*),"#));
+    assert!(generated.contains(
+        r#"($crate::Lang::Fr(_), $crate::PluralCategory::One) => format!("{:} file", count $(, $fmt_args)*),"#
+    ));
+    assert!(generated
+        .contains(r#"($crate::Lang::Fr(_), _) => format!("{:} files", count $(, $fmt_args)*),"#));
+}
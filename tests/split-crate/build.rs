@@ -0,0 +1,11 @@
+fn main() {
+    twine::Builder::new()
+        .build_translations_split(
+            &[
+                ("../fixtures/bands.ini", "bands.rs"),
+                ("../fixtures/formats.ini", "formats.rs"),
+            ],
+            "lang.rs",
+        )
+        .unwrap();
+}
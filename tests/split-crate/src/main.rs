@@ -0,0 +1,21 @@
+include!(concat!(env!("OUT_DIR"), "/lang.rs"));
+
+mod bands {
+    include!(concat!(env!("OUT_DIR"), "/bands.rs"));
+
+    pub fn band_tool(lang: crate::Lang) -> String {
+        t!(band_tool => lang)
+    }
+}
+
+mod formats {
+    include!(concat!(env!("OUT_DIR"), "/formats.rs"));
+}
+
+fn main() {
+    // the macros are used in their module or by path
+    for lang in Lang::all_languages() {
+        println!("{}", bands::band_tool(**lang));
+        println!("{}", formats::t!(format_string, "Hello", "World" => lang));
+    }
+}
@@ -0,0 +1,54 @@
+use std::fs;
+
+#[test]
+fn split() {
+    let out_dir = tempfile::tempdir().unwrap();
+    std::env::set_var("OUT_DIR", out_dir.as_ref().as_os_str());
+
+    let mut cargo_output = Vec::new();
+    twine::Builder::new()
+        .cargo_output(&mut cargo_output)
+        .build_translations_split(
+            &[
+                ("tests/fixtures/bands.ini", "bands.rs"),
+                ("tests/fixtures/formats.ini", "formats.rs"),
+            ],
+            "lang.rs",
+        )
+        .unwrap();
+    let shared = fs::read_to_string(out_dir.as_ref().join("lang.rs")).unwrap();
+    let bands = fs::read_to_string(out_dir.as_ref().join("bands.rs")).unwrap();
+    let formats = fs::read_to_string(out_dir.as_ref().join("formats.rs")).unwrap();
+    println!("{}\n========\n{}\n========\n{}", shared, bands, formats);
+
+    assert_eq!(
+        String::from_utf8(cargo_output).unwrap(),
+        "\
+        cargo:rerun-if-changed=tests/fixtures/bands.ini\n\
+        cargo:rerun-if-changed=tests/fixtures/formats.ini\n\
+        ",
+    );
+
+    // the languages of all the modules are in the shared file
+    assert!(shared.contains("pub enum Lang {"));
+    assert!(shared.contains("Fr(&'static str),"));
+    assert!(shared.contains(r#"&Lang::En(""),"#));
+    assert!(!shared.contains("macro_rules!"));
+    assert!(!shared.contains("pub enum Key"));
+
+    for module in [&bands, &formats] {
+        assert!(module.contains("macro_rules! t {"));
+        assert!(module.contains("pub(crate) use t;"));
+        assert!(!module.contains("#[macro_export]"));
+        assert!(!module.contains("pub enum Lang"));
+        assert!(!module.contains("TWINE_GEN_VERSION"));
+    }
+    assert!(
+        bands.contains("(band_tool $(, $fmt_args:expr)* => $lang:expr $(; $_fallback:literal)?)")
+    );
+    assert!(!bands.contains("(format_string"));
+    assert!(bands.contains(r#"(crate::Lang::Fr(""), "Outil"),"#));
+    assert!(formats
+        .contains("(format_string $(, $fmt_args:expr)* => $lang:expr $(; $_fallback:literal)?)"));
+    assert!(!formats.contains("(band_tool"));
+}
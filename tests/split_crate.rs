@@ -0,0 +1,33 @@
+// the generated modules compile and their macros are usable in their module or by path
+#[test]
+fn split_crate() {
+    let output = std::process::Command::new("cargo")
+        .args(["run", "--manifest-path", "tests/split-crate/Cargo.toml"])
+        .env_remove("OUT_DIR")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    println!("stdout:\n{}\n", stdout);
+    eprintln!("stderr:\n{}\n", stderr);
+    assert!(output.status.success());
+    assert_eq!(stdout.trim(), "Tool\nHello, World!\nOutil\nHello, World !",);
+
+    let output = std::process::Command::new("cargo")
+        .args([
+            "clippy",
+            "--manifest-path",
+            "tests/split-crate/Cargo.toml",
+            "--",
+            "-D",
+            "warnings",
+        ])
+        .env_remove("OUT_DIR")
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    eprintln!("stderr:\n{}\n", stderr);
+    assert!(output.status.success());
+}
@@ -0,0 +1,53 @@
+#[test]
+fn strict_placeholders() {
+    // the literal `%` are still allowed
+    twine::Builder::new()
+        .strict_placeholders(true)
+        .cargo_output(std::io::sink())
+        .build_translations_to_writer(
+            &mut [std::fs::File::open("tests/fixtures/placeholders_valid.ini").unwrap()],
+            &mut std::io::sink(),
+        )
+        .unwrap();
+
+    let err = twine::Builder::new()
+        .strict_placeholders(true)
+        .cargo_output(std::io::sink())
+        .build_translations(&["tests/fixtures/placeholders_invalid.ini"], "i18n.rs")
+        .unwrap_err();
+    match err {
+        twine::TwineError::InvalidPlaceholder {
+            key,
+            lang,
+            placeholder,
+        } => {
+            assert_eq!(key, "format_typo");
+            assert_eq!(lang, "en");
+            assert_eq!(placeholder, "%@s");
+        }
+        err => panic!("unexpected error: {}", err),
+    }
+
+    // lenient by default
+    twine::Builder::new()
+        .cargo_output(std::io::sink())
+        .build_translations_to_writer(
+            &mut [std::fs::File::open("tests/fixtures/placeholders_invalid.ini").unwrap()],
+            &mut std::io::sink(),
+        )
+        .unwrap();
+}
+
+#[test]
+fn invalid_placeholder_dot() {
+    let err = twine::Builder::new()
+        .strict_placeholders(true)
+        .cargo_output(std::io::sink())
+        .build_translations_from_str(&["[format_typo]\n    fr = %.f articles\n"], "i18n.rs")
+        .unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "translation \"fr\" of key \"format_typo\" contains the invalid placeholder \"%.f\"",
+    );
+}
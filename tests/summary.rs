@@ -0,0 +1,32 @@
+#[test]
+fn summary() {
+    let out_dir = tempfile::tempdir().unwrap();
+    std::env::set_var("OUT_DIR", out_dir.as_ref().as_os_str());
+
+    // every plural category is a string, the regions are languages
+    let mut cargo_output = Vec::new();
+    twine::Builder::new()
+        .summary(true)
+        .cargo_output(&mut cargo_output)
+        .build_translations(&["tests/fixtures/identical.ini"], "i18n.rs")
+        .unwrap();
+    let cargo_output = String::from_utf8(cargo_output).unwrap();
+
+    assert_eq!(
+        cargo_output,
+        "\
+        cargo:rerun-if-changed=tests/fixtures/identical.ini\n\
+        cargo:warning=twine: 5 keys, 4 languages, 15 strings generated\n",
+    );
+
+    // disabled by default
+    let mut cargo_output = Vec::new();
+    twine::Builder::new()
+        .cargo_output(&mut cargo_output)
+        .build_translations(&["tests/fixtures/identical.ini"], "i18n.rs")
+        .unwrap();
+
+    assert!(!String::from_utf8(cargo_output)
+        .unwrap()
+        .contains("strings generated"));
+}
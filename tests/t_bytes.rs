@@ -0,0 +1,27 @@
+mod common;
+
+use common::build;
+
+const INI: &str = "\
+[band_tool]
+    en = Tool
+    fr = Outil
+";
+
+#[test]
+fn t_bytes() {
+    let code = build(twine::Builder::new(), INI);
+
+    assert!(code.contains("#[macro_export]\nmacro_rules! t_bytes {"));
+    // the bytes of `t!()` or written through `t_into!()`
+    assert!(code.contains("$crate::t!($($tokens)*).into_bytes()"));
+    assert!(code.contains("$crate::t_into!(bytes, $($tokens)*)"));
+}
+
+#[test]
+fn t_bytes_local() {
+    let code = build(twine::Builder::new().local_macros("i18n"), INI);
+
+    assert!(code.contains("pub(crate) use t_bytes;"));
+    assert!(code.contains("$crate::i18n::t!($($tokens)*).into_bytes()"));
+}
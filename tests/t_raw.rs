@@ -0,0 +1,20 @@
+mod common;
+
+use common::build;
+
+const INI: &str = r#"
+[escaped]
+    en = Line\none, fran\u00E7ais,\t\"quoted\"
+    fr = %s \\ %1$d 50 %%
+"#;
+
+#[test]
+fn t_raw_decodes_escapes() {
+    let code = build(twine::Builder::new().cargo_output(std::io::sink()), INI);
+
+    // `t_raw!()` and `preview()`: the escape sequences are decoded, the placeholders are kept
+    assert!(code.contains(r#"Lang::En(_) => "Line\none, français,\t\"quoted\"","#));
+    assert!(code.contains(r#"Lang::Fr(_) => "%s \\ %1$d 50 %%","#));
+    assert!(code.contains(r#"(Lang::En(""), "Line\none, français,\t\"quoted\""),"#));
+    assert!(!code.contains(r"\u00E7"));
+}
@@ -0,0 +1,34 @@
+#![allow(macro_expanded_macro_exports_accessed_by_absolute_paths)]
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+// 1000 keys: `band_0` to `band_999`
+include!(concat!(env!("OUT_DIR"), "/keys.rs"));
+
+fn const_table_benchmark(c: &mut Criterion) {
+    let names = ["band_0", "band_500", "band_999"];
+    let keys: Vec<_> = names.iter().map(|x| Key::from_name(x).unwrap()).collect();
+    let langs = [Lang::En(""), Lang::Fr(""), Lang::Fr("be")];
+
+    c.bench_function("translate", |b| {
+        b.iter(|| {
+            for name in names.iter() {
+                for lang in langs.iter() {
+                    black_box(translate(black_box(name), black_box(*lang)));
+                }
+            }
+        })
+    });
+    c.bench_function("TABLE", |b| {
+        b.iter(|| {
+            for key in keys.iter() {
+                for lang in langs.iter() {
+                    black_box(TABLE[black_box(*key) as usize][lang_index(black_box(lang))]);
+                }
+            }
+        })
+    });
+}
+
+criterion_group!(benches, const_table_benchmark);
+criterion_main!(benches);
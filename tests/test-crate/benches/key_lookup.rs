@@ -0,0 +1,28 @@
+#![allow(macro_expanded_macro_exports_accessed_by_absolute_paths)]
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+// 1000 keys: `band_0` to `band_999`
+include!(concat!(env!("OUT_DIR"), "/keys.rs"));
+
+fn key_lookup_benchmark(c: &mut Criterion) {
+    let names = ["band_0", "band_500", "band_999", "unknown"];
+
+    c.bench_function("Key::from_name", |b| {
+        b.iter(|| {
+            for name in names.iter() {
+                black_box(Key::from_name(black_box(name)));
+            }
+        })
+    });
+    c.bench_function("KEYS", |b| {
+        b.iter(|| {
+            for name in names.iter() {
+                black_box(KEYS.get(black_box(*name)));
+            }
+        })
+    });
+}
+
+criterion_group!(benches, key_lookup_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,30 @@
+#![allow(macro_expanded_macro_exports_accessed_by_absolute_paths)]
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use serde::de::{value, Deserialize, IntoDeserializer};
+
+include!(concat!(env!("OUT_DIR"), "/i18n.rs"));
+
+fn lang_parse_benchmark(c: &mut Criterion) {
+    let values = ["en", "en_gb", "en_GB", "fr"];
+
+    c.bench_function("Lang::parse", |b| {
+        b.iter(|| {
+            for value in values.iter() {
+                black_box(Lang::parse(black_box(value)));
+            }
+        })
+    });
+    c.bench_function("Lang::deserialize", |b| {
+        b.iter(|| {
+            for value in values.iter() {
+                let deserializer: value::StrDeserializer<value::Error> =
+                    black_box(*value).into_deserializer();
+                black_box(Lang::deserialize(deserializer).ok());
+            }
+        })
+    });
+}
+
+criterion_group!(benches, lang_parse_benchmark);
+criterion_main!(benches);
@@ -1,3 +1,35 @@
 fn main() {
-    twine::build_translations(&["translations.ini"], "i18n.rs").unwrap();
+    twine::Builder::new()
+        .lang_code(true)
+        .named_args(true)
+        .typed_functions(true)
+        .ffi("i18n")
+        .const_table(true)
+        .build_translations(&["translations.ini"], "i18n.rs")
+        .unwrap();
+    twine::Builder::new()
+        .local_macros("local")
+        .named_args(true)
+        .uppercase_regions(true)
+        .locale_numbers(true)
+        .locale_count(true)
+        .build_translations(&["local.ini"], "local.rs")
+        .unwrap();
+    twine::Builder::new()
+        .local_macros("regions")
+        .ignore_regions(true)
+        .cargo_output(std::io::sink())
+        .build_translations(&["regions.ini"], "regions.rs")
+        .unwrap();
+    // used by the benchmark of the key lookup
+    let keys: Vec<_> = (0..1000)
+        .map(|i| format!("[band_{}]\n    en = Band {}\n    fr = Groupe {}\n", i, i, i))
+        .collect();
+    twine::Builder::new()
+        .const_table(true)
+        .build_translations_from_str(
+            &keys.iter().map(String::as_str).collect::<Vec<_>>(),
+            "keys.rs",
+        )
+        .unwrap();
 }
@@ -0,0 +1,93 @@
+include!(concat!(env!("OUT_DIR"), "/local.rs"));
+
+pub fn local_macros() {
+    assert_eq!(t!(band_tool => Lang::Nl("")), "Gereedschap");
+    assert_eq!(t_bytes!(band_tool => Lang::Nl("")), b"Gereedschap");
+    assert_eq!(plural!(n_bands, 1 => Lang::En("")), "1 band");
+    // the regions are displayed in uppercase
+    assert_eq!(Lang::En("gb").to_string(), "en_GB");
+    assert_eq!(Lang::En("gb").code(), "en_GB");
+    assert_eq!(Lang::parse("en_GB"), Some(Lang::En("gb")));
+    assert_eq!(Lang::parse("en_gb"), Some(Lang::En("gb")));
+    assert_eq!(
+        serde_json::to_string(&Lang::En("gb")).unwrap(),
+        "\"en_GB\"",
+    );
+    let lang: Lang = serde_json::from_str("\"en_GB\"").unwrap();
+    assert_eq!(lang, Lang::En("gb"));
+    // `nl` and `de` miss translations, `en_GB` is completed by `en`
+    assert_eq!(Lang::complete_languages(), [Lang::En(""), Lang::En("gb")]);
+    // the markdown translations are rendered by the UI
+    assert!(is_markdown(Key::HelpIntro));
+    assert!(!is_markdown(Key::BandTool));
+    // the numbers use the separators of the language
+    assert_eq!(t!(format_amount, 1234.5 => Lang::En("")), "1,234.50 EUR");
+    assert_eq!(t!(format_amount, 1234.5 => Lang::De("")), "1.234,50 EUR");
+    assert_eq!(t!(format_amount, -1234567.891 => Lang::De("")), "-1.234.567,89 EUR");
+    assert_eq!(t!(format_count, 1000000 => Lang::En("")), "1,000,000 bands");
+    assert_eq!(t!(format_count, 999 => Lang::De("")), "999 Bands");
+    // the code of the language requested is displayed
+    assert_eq!(t!(html_open => Lang::En("gb")), "<html lang=\"en_GB\">");
+    assert_eq!(t!(html_open => Lang::Nl("")), "<html lang=\"nl\">");
+    // the count and the named arguments of a plural key
+    let user = "Sam";
+    assert_eq!(plural!(cart_items, count = 1, name = user => Lang::En("")), "Sam has 1 item");
+    assert_eq!(plural!(cart_items, count = 3, name = user => Lang::En("")), "Sam has 3 items");
+    assert_eq!(
+        plural!(cart_items, count = 1, name = "Sam" => Lang::Nl("")),
+        "Sam heeft één artikel"
+    );
+    assert_eq!(
+        plural!(cart_items, count = 12, name = "Sam" => ?Some(Lang::Nl(""))),
+        "Sam heeft 12 artikelen"
+    );
+    // the count `#` uses the separator of the thousands of the language
+    assert_eq!(plural!(n_stars, 1 => Lang::En("")), "1 star");
+    assert_eq!(plural!(n_stars, 1234567 => Lang::En("")), "1,234,567 stars");
+    assert_eq!(plural!(n_stars, 1234567 => Lang::De("")), "1.234.567 Sterne");
+    assert_eq!(
+        plural!(cart_items, count = 1000, name = "Sam" => Lang::En("")),
+        "Sam has 1,000 items"
+    );
+    submodule::run();
+}
+
+// an error displayed in the language of the user, one key by variant
+enum MyError {
+    NotFound,
+    PermissionDenied,
+}
+
+struct Localized(MyError, Lang);
+
+impl std::fmt::Display for Localized {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let key = match self.0 {
+            MyError::NotFound => Key::ErrorNotFound,
+            MyError::PermissionDenied => Key::ErrorPermissionDenied,
+        };
+        f.write_str(&describe(key, &self.1))
+    }
+}
+
+pub fn describe_errors() {
+    assert_eq!(
+        Localized(MyError::NotFound, Lang::Nl("")).to_string(),
+        "Niet gevonden",
+    );
+    assert_eq!(
+        Localized(MyError::NotFound, Lang::En("gb")).to_string(),
+        "Not found",
+    );
+    // the translation used by `t!()` when the language is not translated
+    assert_eq!(
+        Localized(MyError::PermissionDenied, Lang::Nl("")).to_string(),
+        "Permission denied",
+    );
+}
+
+mod submodule {
+    pub fn run() {
+        assert_eq!(t!(band_tool => super::Lang::En("")), "Tool");
+    }
+}
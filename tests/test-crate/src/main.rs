@@ -1,10 +1,45 @@
 #![allow(macro_expanded_macro_exports_accessed_by_absolute_paths)]
 
+mod local;
 mod my_module;
+mod regions;
 
 include!(concat!(env!("OUT_DIR"), "/i18n.rs"));
 
 fn main() {
     my_module::basic();
     my_module::serde();
+    my_module::plural();
+    my_module::ordinal();
+    my_module::from_locale_env();
+    my_module::key_preview();
+    my_module::lang_code();
+    my_module::named_args();
+    my_module::t_into();
+    my_module::t_bytes();
+    my_module::const_table();
+    my_module::optional_lang();
+    my_module::translate();
+    my_module::adversarial_keys();
+    my_module::validate_overrides();
+    my_module::format_dynamic();
+    my_module::ffi();
+    my_module::typed_functions();
+    my_module::inline_fallback();
+    my_module::lang_parse();
+    my_module::t_raw();
+    my_module::t_static();
+    my_module::known_lang();
+    my_module::all_codes();
+    my_module::sort_languages();
+    my_module::nearest_lang();
+    my_module::lang_code_str();
+    my_module::gettext();
+    my_module::current_lang();
+    my_module::default_lang();
+    local::local_macros();
+    local::describe_errors();
+    regions::ignore_regions();
+    regions::escaped_braces();
+    my_module::local_macros_by_path();
 }
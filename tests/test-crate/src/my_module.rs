@@ -1,5 +1,13 @@
+use crate::const_lang;
+use crate::ordinal;
+use crate::plural;
 use crate::t;
-use crate::Lang;
+use crate::t_bytes;
+use crate::t_into;
+use crate::t_raw;
+use crate::t_static;
+use crate::{Key, Lang, LangCode};
+use std::convert::TryFrom;
 
 pub fn basic() {
     for lang in &[Lang::Fr(""), Lang::En(""), Lang::En("gb")] {
@@ -27,6 +35,12 @@ pub fn basic() {
     assert_eq!(lang.to_string(), "en");
     assert_eq!(lang.language(), "en");
     assert_eq!(lang.region(), "");
+
+    assert_eq!(Lang::En("gb").base(), Lang::En(""));
+    assert_eq!(Lang::Fr("").base(), Lang::Fr(""));
+    assert!(Lang::En("gb").same_language(&Lang::En("")));
+    assert!(Lang::En("gb").same_language(&Lang::En("us")));
+    assert!(!Lang::En("gb").same_language(&Lang::Fr("")));
 }
 
 pub fn serde() {
@@ -36,4 +50,638 @@ pub fn serde() {
     assert_eq!(serde_json::to_string(&lang).unwrap(), "\"en\"");
     let lang: Lang = serde_json::from_str("\"fr\"").unwrap();
     assert_eq!(serde_json::to_string(&lang).unwrap(), "\"fr\"");
+
+    let err = serde_json::from_str::<Lang>("\"en_gv\"").unwrap_err();
+    assert!(
+        err.to_string().contains("did you mean 'gb'?"),
+        "unexpected error: {}",
+        err,
+    );
+    let err = serde_json::from_str::<Lang>("\"fe\"").unwrap_err();
+    assert!(
+        err.to_string().contains("did you mean 'fr'?"),
+        "unexpected error: {}",
+        err,
+    );
+}
+
+pub fn plural() {
+    let lang = Lang::En("");
+    assert_eq!(plural!(n_files, 0 => lang), "0 files");
+    assert_eq!(plural!(n_files, 1 => lang), "1 file");
+    assert_eq!(plural!(n_files, 2 => lang), "2 files");
+    assert_eq!(plural!(n_files, 1_000_000 => lang), "1000000 files");
+    assert_eq!(plural!(n_files, -1 => lang), "-1 file");
+    assert_eq!(plural!(n_bands_in_city, 1, "Paris" => lang), "One band in Paris");
+    assert_eq!(plural!(n_bands_in_city, 3, "Paris" => lang), "3 bands in Paris");
+    // the category zero is used for 0 even if English does not have it
+    assert_eq!(plural!(n_items, 0 => lang), "No items");
+    assert_eq!(plural!(n_items, 1 => lang), "1 item");
+    assert_eq!(ordinal!(n_items, 0 => lang), "0 items");
+
+    let lang = Lang::Fr("");
+    assert_eq!(plural!(n_files, 0 => lang), "0 fichier");
+    assert_eq!(plural!(n_files, 1 => lang), "1 fichier");
+    assert_eq!(plural!(n_files, 2usize => lang), "2 fichiers");
+    assert_eq!(plural!(n_bands_in_city, 1, "Paris" => lang), "1 groupe à Paris");
+    assert_eq!(plural!(n_items, 0 => lang), "0 article");
+
+    // the count is evaluated only once
+    let mut count = 0;
+    let mut next = || {
+        count += 1;
+        count
+    };
+    assert_eq!(plural!(n_files, next() => Lang::En("gb")), "1 file");
+    assert_eq!(count, 1);
+}
+
+pub fn ordinal() {
+    let lang = Lang::En("");
+    let places: Vec<_> = [1, 2, 3, 4, 11, 12, 13, 21, 22, 23]
+        .iter()
+        .map(|n| ordinal!(place, *n => lang))
+        .collect();
+    assert_eq!(
+        places,
+        &["1st", "2nd", "3rd", "4th", "11th", "12th", "13th", "21st", "22nd", "23rd"],
+    );
+
+    let lang = Lang::Fr("");
+    assert_eq!(ordinal!(place, 1 => lang), "1er");
+    assert_eq!(ordinal!(place, 2 => lang), "2e");
+}
+
+pub fn from_locale_env() {
+    std::env::remove_var("LC_ALL");
+    std::env::remove_var("LC_MESSAGES");
+    std::env::remove_var("LANG");
+    assert_eq!(Lang::from_locale_env(), None);
+
+    std::env::set_var("LANG", "en_GB.UTF-8");
+    assert_eq!(Lang::from_locale_env(), Some(Lang::En("gb")));
+    std::env::set_var("LANG", "en_US.UTF-8");
+    assert_eq!(Lang::from_locale_env(), Some(Lang::En("")));
+    std::env::set_var("LANG", "fr_FR@euro");
+    assert_eq!(Lang::from_locale_env(), Some(Lang::Fr("")));
+    std::env::set_var("LANG", "de_DE.UTF-8");
+    assert_eq!(Lang::from_locale_env(), None);
+
+    std::env::set_var("LC_MESSAGES", "en_gb");
+    assert_eq!(Lang::from_locale_env(), Some(Lang::En("gb")));
+    std::env::set_var("LC_ALL", "");
+    assert_eq!(Lang::from_locale_env(), Some(Lang::En("gb")));
+    std::env::set_var("LC_ALL", "fr");
+    assert_eq!(Lang::from_locale_env(), Some(Lang::Fr("")));
+    std::env::set_var("LC_ALL", "C");
+    assert_eq!(Lang::from_locale_env(), None);
+}
+
+pub fn key_preview() {
+    assert_eq!(
+        crate::preview(Key::BandRageAgainstTheMachine),
+        &[
+            (Lang::En(""), "Rage Against the Machine"),
+            (Lang::En("gb"), "Wrath Against the Machine"),
+            (Lang::Fr(""), "Colère contre la machine"),
+        ],
+    );
+    assert_eq!(
+        crate::preview(Key::FormatString),
+        &[(Lang::En(""), "%s, %@!"), (Lang::Fr(""), "%s, %@ !")],
+    );
+    assert_eq!(
+        crate::preview(Key::NFiles),
+        &[(Lang::En(""), "%d files"), (Lang::Fr(""), "%d fichiers")],
+    );
+    // the escape sequences are decoded
+    assert_eq!(
+        crate::preview(Key::StuffWithEscapedSequencesAndDoubleQuotes),
+        &[(
+            Lang::En(""),
+            "Stuff with\nescaped\tsequences and \"double quotes\""
+        )],
+    );
+}
+
+pub fn lang_code() {
+    assert_eq!(LangCode::from(Lang::En("")), LangCode::En);
+    assert_eq!(LangCode::from(Lang::En("gb")), LangCode::EnGb);
+    assert_eq!(LangCode::from(Lang::En("us")), LangCode::En);
+    assert_eq!(LangCode::from(Lang::Fr("")), LangCode::Fr);
+    assert_eq!(LangCode::En as u16, 0);
+    assert_eq!(LangCode::EnGb as u16, 1);
+    assert_eq!(LangCode::Fr as u16, 2);
+    assert_eq!(std::mem::size_of::<LangCode>(), 2);
+
+    for lang in Lang::all_languages() {
+        let code = LangCode::from(**lang) as u16;
+        assert_eq!(Lang::from(LangCode::try_from(code).unwrap()), **lang);
+    }
+    assert_eq!(LangCode::try_from(3), Err(3));
+}
+
+pub fn named_args() {
+    assert_eq!(
+        t!(greeting, name = "Sam", count = 3 => Lang::En("")),
+        "Hello Sam, you have 3 messages!",
+    );
+    assert_eq!(
+        t!(greeting, count = 3, name = "Sam" => Lang::Fr("")),
+        "Bonjour Sam, vous avez 3 messages !",
+    );
+}
+
+pub fn optional_lang() {
+    let some = Some(Lang::Fr(""));
+    let none: Option<Lang> = None;
+
+    // `None` is the default language: the first one until `Lang::set_default()` is called
+    assert_eq!(t!(band_tool => ?some), "Outil");
+    assert_eq!(t!(band_tool => ?none), t!(band_tool => Lang::default()));
+    assert_eq!(t!(format_string, "Hello", "World" => ?some), "Hello, World !");
+    assert_eq!(t!(greeting, name = "Sam", count = 3 => ?some), "Bonjour Sam, vous avez 3 messages !");
+    assert_eq!(t!(new_key => ?none; "New feature"), "New feature");
+    assert_eq!(t_raw!(format_string => ?some), "%s, %@ !");
+    assert_eq!(plural!(n_files, 2 => ?some), "2 fichiers");
+
+    let mut buf = String::new();
+    t_into!(buf, band_tool => ?some).unwrap();
+    assert_eq!(buf, "Outil");
+}
+
+pub fn t_into() {
+    let mut buf = String::new();
+    for lang in [Lang::En("gb"), Lang::Fr("")].iter() {
+        t_into!(buf, band_rage_against_the_machine => *lang).unwrap();
+        buf.push('\n');
+        t_into!(&mut buf, format_string, "Hello", "World" => *lang).unwrap();
+        buf.push('\n');
+    }
+
+    assert_eq!(
+        buf,
+        "\
+        Wrath Against the Machine\n\
+        Hello, World!\n\
+        Colère contre la machine\n\
+        Hello, World !\n\
+        ",
+    );
+}
+
+pub fn t_bytes() {
+    for lang in [Lang::En("gb"), Lang::Fr("")].iter() {
+        assert_eq!(
+            t_bytes!(band_rage_against_the_machine => *lang),
+            t!(band_rage_against_the_machine => *lang).as_bytes(),
+        );
+        assert_eq!(
+            t_bytes!(format_string, "Hello", "World" => *lang),
+            t!(format_string, "Hello", "World" => *lang).into_bytes(),
+        );
+    }
+
+    // appended to the body of a response
+    let mut body = b"HTTP/1.1 200 OK\r\n\r\n".to_vec();
+    t_bytes!(&mut body, greeting, name = "Sam", count = 3 => Lang::Fr("")).unwrap();
+    assert_eq!(
+        body,
+        "HTTP/1.1 200 OK\r\n\r\nBonjour Sam, vous avez 3 messages !".as_bytes(),
+    );
+}
+
+pub fn const_table() {
+    use crate::{describe, lang_index, TABLE};
+
+    assert_eq!(TABLE[Key::BandTool as usize][lang_index(&Lang::Fr(""))], "Outil");
+    // the same translations as `describe()`, including the fallbacks
+    for key in Key::iter() {
+        for lang in Lang::all_languages()
+            .iter()
+            .map(|x| **x)
+            .chain([Lang::En("us"), Lang::Fr("be")])
+        {
+            assert_eq!(TABLE[key as usize][lang_index(&lang)], describe(key, &lang));
+        }
+    }
+}
+
+pub fn local_macros_by_path() {
+    assert_eq!(
+        crate::local::t!(band_tool => crate::local::Lang::Nl("")),
+        "Gereedschap",
+    );
+    assert_eq!(
+        crate::local::t!(band_tool => ?Some(crate::local::Lang::Nl(""))),
+        "Gereedschap",
+    );
+}
+
+pub fn translate() {
+    use crate::{translate, KEYS};
+
+    assert_eq!(Key::from_name("band_tool"), Some(Key::BandTool));
+    assert_eq!(Key::from_name("NameWith.DotInIt"), Some(Key::NameWithDotInIt));
+    assert_eq!(Key::from_name("café_button"), Some(Key::CaféButton));
+    assert_eq!(Key::from_name("unknown_key"), None);
+    assert_eq!(KEYS.get("band_tool"), Some(&Key::BandTool));
+    assert_eq!(KEYS.len(), 20);
+
+    // every key has a preview
+    assert_eq!(Key::iter().count(), 20);
+    assert_eq!(Key::iter().next(), Some(Key::AppRuinTheBand));
+    assert!(Key::iter().all(|key| !crate::preview(key).is_empty()));
+    assert_eq!(Key::iter().map(|key| crate::preview(key).len()).max(), Some(3));
+
+    assert_eq!(translate("band_tool", Lang::Fr("")), Some("Outil"));
+    assert_eq!(t!(café_button => Lang::Fr("")), "Café");
+    assert_eq!(
+        translate("band_rage_against_the_machine", Lang::En("gb")),
+        Some("Wrath Against the Machine")
+    );
+    assert_eq!(translate("band_tool", Lang::En("gb")), Some("Tool"));
+    assert_eq!(translate("format_string", Lang::Fr("")), Some("%s, %@ !"));
+    assert_eq!(translate("fallback_to_default_lang", Lang::Fr("")), None);
+    assert_eq!(translate("unknown_key", Lang::Fr("")), None);
+}
+
+pub fn adversarial_keys() {
+    use crate::{path_to_the_key, r#match, translate};
+
+    // the keywords are written as is in the macros, their functions are raw identifiers
+    assert_eq!(t!(match => Lang::Fr("")), "Allumette");
+    assert_eq!(r#match(&Lang::Fr("")), "Allumette");
+    assert_eq!(t!(path_to_the_key => Lang::Fr("")), "Clé hostile");
+    assert_eq!(path_to_the_key(&Lang::En("")), "Adversarial key");
+    assert_eq!(Key::from_name("path=>to, the = key"), Some(Key::PathToTheKey));
+    assert_eq!(translate("path=>to, the = key", Lang::En("")), Some("Adversarial key"));
+}
+
+pub fn ffi() {
+    use crate::{
+        i18n_key_count, i18n_key_index, i18n_lang_count, i18n_lang_index, i18n_translate,
+        FFI_KEYS, FFI_LANGS,
+    };
+    use std::ffi::CStr;
+    use std::os::raw::c_char;
+
+    // called like a C program would do
+    fn translate(key: &str, lang: &str) -> Option<String> {
+        unsafe {
+            let key = i18n_key_index(key.as_ptr(), key.len());
+            let lang = i18n_lang_index(lang.as_ptr(), lang.len());
+            assert!(key >= 0 && lang >= 0);
+            let mut len = 0;
+            let text = i18n_translate(key as u32, lang as u32, &mut len);
+            if text.is_null() {
+                return None;
+            }
+            let text = CStr::from_ptr(text as *const c_char).to_str().unwrap();
+            assert_eq!(text.len(), len);
+            Some(text.to_string())
+        }
+    }
+
+    assert_eq!(i18n_key_count() as usize, FFI_KEYS.len());
+    assert_eq!(i18n_key_count(), 20);
+    assert_eq!(i18n_lang_count() as usize, FFI_LANGS.len());
+    assert_eq!(
+        FFI_LANGS.to_vec(),
+        Lang::all_languages().iter().map(|x| x.code()).collect::<Vec<_>>()
+    );
+    assert_eq!(FFI_KEYS[0], "NameWith.DotInIt");
+
+    assert_eq!(translate("band_tool", "fr").as_deref(), Some("Outil"));
+    assert_eq!(translate("band_tool", "en_gb").as_deref(), Some("Tool"));
+    assert_eq!(
+        translate("band_rage_against_the_machine", "en-GB").as_deref(),
+        Some("Wrath Against the Machine")
+    );
+    assert_eq!(translate("format_string", "fr").as_deref(), Some("%s, %@ !"));
+    assert_eq!(translate("fallback_to_default_lang", "fr"), None);
+
+    unsafe {
+        assert_eq!(i18n_key_index(b"unknown_key".as_ptr(), 11), -1);
+        assert_eq!(i18n_lang_index(b"xx".as_ptr(), 2), -1);
+        assert!(i18n_translate(i18n_key_count(), 0, std::ptr::null_mut()).is_null());
+        assert!(!i18n_translate(0, 0, std::ptr::null_mut()).is_null());
+    }
+}
+
+pub fn validate_overrides() {
+    use crate::validate_overrides;
+    use std::collections::HashMap;
+
+    let mut overrides = HashMap::new();
+    overrides.insert("band_tool".to_string(), "Instrument".to_string());
+    overrides.insert("NameWith.DotInIt".to_string(), "Name".to_string());
+    assert_eq!(validate_overrides(&overrides), Ok(()));
+    assert_eq!(validate_overrides(&HashMap::new()), Ok(()));
+
+    overrides.insert("unknown_key".to_string(), "Unknown".to_string());
+    overrides.insert("band_tol".to_string(), "Typo".to_string());
+    assert_eq!(
+        validate_overrides(&overrides),
+        Err(vec!["band_tol".to_string(), "unknown_key".to_string()]),
+    );
+}
+
+pub fn format_dynamic() {
+    use crate::{format_dynamic, MissingArg};
+    use std::collections::HashMap;
+
+    let mut args = HashMap::new();
+    args.insert("name", "Sam");
+    args.insert("count", "3");
+    assert_eq!(
+        format_dynamic(Key::Greeting, &Lang::Fr(""), &args).as_deref(),
+        Ok("Bonjour Sam, vous avez 3 messages !"),
+    );
+    // the same text as `t!()`
+    assert_eq!(
+        format_dynamic(Key::Greeting, &Lang::En(""), &args).unwrap(),
+        t!(greeting, name = "Sam", count = 3 => Lang::En("")),
+    );
+    // the unused arguments are ignored
+    args.insert("unused", "x");
+    assert!(format_dynamic(Key::Greeting, &Lang::En("gb"), &args).is_ok());
+
+    let mut args = HashMap::new();
+    args.insert("count", "3");
+    assert_eq!(
+        format_dynamic(Key::Greeting, &Lang::En(""), &args),
+        Err(MissingArg(vec!["name".to_string()])),
+    );
+    let err = format_dynamic(Key::Greeting, &Lang::En(""), &HashMap::new()).unwrap_err();
+    assert_eq!(err.0, ["name", "count"]);
+    assert_eq!(err.to_string(), "missing named arguments: name, count");
+
+    // the keys without named arguments
+    assert_eq!(
+        format_dynamic(Key::BandTool, &Lang::Fr(""), &HashMap::new()).as_deref(),
+        Ok("Outil"),
+    );
+}
+
+pub fn typed_functions() {
+    use crate::{
+        band_tool, format_hexadecimal, format_percentage, format_string, n_bands_in_city,
+        n_files, name_with_dot_in_it,
+    };
+
+    let lang = Lang::Fr("");
+    assert_eq!(band_tool(&lang), "Outil");
+    assert_eq!(format_percentage(&lang, 73.02), "73 %");
+    assert_eq!(format_percentage(&Lang::En(""), 73.02), "73%");
+    assert_eq!(format_percentage(&Lang::En("gb"), 73.02), "% 73% % foo");
+    assert_eq!(format_string(&lang, "Hello", 'W'), "Hello, W !");
+    assert_eq!(format_hexadecimal(&lang, 0xBAD_CAFE), "0xBADCAFE");
+    assert_eq!(format_hexadecimal(&Lang::En(""), 0xBAD_CAFE_u64), "badcafe");
+    assert_eq!(n_files(&lang, 2), "2 fichiers");
+    assert_eq!(n_bands_in_city(&Lang::En(""), 1, "Paris"), "One band in Paris");
+    assert_eq!(name_with_dot_in_it(&lang), "Name with a dot");
+}
+
+pub fn inline_fallback() {
+    let lang = Lang::Fr("");
+    assert_eq!(t!(band_tool => lang; "Tool"), "Outil");
+    assert_eq!(t!(format_string, "Hello", "World" => lang; "{}, {}"), "Hello, World !");
+    assert_eq!(t!(band_not_yet_translated => lang; "Not yet"), "Not yet");
+    assert_eq!(t!(band_not_yet_translated, 5 => lang; "{} bands"), "5 bands");
+    assert_eq!(
+        t!(greeting_not_yet_translated, name = "Alice" => lang; "Hi {name}"),
+        "Hi Alice"
+    );
+
+    let mut buf = String::new();
+    t_into!(buf, band_tool => lang; "Tool").unwrap();
+    t_into!(buf, band_not_yet_translated => lang; " - Not yet").unwrap();
+    assert_eq!(buf, "Outil - Not yet");
+}
+
+pub fn lang_parse() {
+    assert_eq!(Lang::parse("en"), Some(Lang::En("")));
+    assert_eq!(Lang::parse("en_gb"), Some(Lang::En("gb")));
+    assert_eq!(Lang::parse("en_GB"), Some(Lang::En("gb")));
+    assert_eq!(Lang::parse("fr_be"), None);
+    assert_eq!(Lang::parse("fr_"), Some(Lang::Fr("")));
+    assert_eq!(Lang::parse("en_gv"), None);
+    assert_eq!(Lang::parse("fe"), None);
+    assert_eq!(Lang::parse(""), None);
+
+    // the part that is unknown is given
+    use std::convert::TryFrom;
+    assert_eq!(Lang::try_from("en_GB"), Ok(Lang::En("gb")));
+    assert_eq!(Lang::try_from("fr"), Ok(Lang::Fr("")));
+    assert_eq!(
+        Lang::try_from("de_ch"),
+        Err(crate::LangParseError::UnknownLanguage("de".to_string()))
+    );
+    assert_eq!(
+        Lang::try_from("en_us"),
+        Err(crate::LangParseError::UnknownRegion("us".to_string()))
+    );
+    // the region exists but not for this language
+    assert_eq!(
+        Lang::try_from("fr_gb"),
+        Err(crate::LangParseError::UnknownRegion("gb".to_string()))
+    );
+    assert_eq!(
+        Lang::try_from("en_us").unwrap_err().to_string(),
+        "unknown region: \"us\""
+    );
+
+    // same result as serde
+    for value in &["en", "en_gb", "en_GB", "fr"] {
+        let lang: Lang = serde_json::from_str(&format!("{:?}", value)).unwrap();
+        assert_eq!(Lang::parse(value), Some(lang));
+    }
+}
+
+pub fn t_raw() {
+    // the escape sequences are decoded like `t!` but the placeholders are kept
+    let raw: &'static str = t_raw!(format_string => Lang::En(""));
+    assert_eq!(raw, "%s, %@!");
+    assert_eq!(t_raw!(format_string => Lang::Fr("")), "%s, %@ !");
+    assert_eq!(t_raw!(format_percentage => Lang::Fr("")), "%.0f %");
+    assert_eq!(
+        t_raw!(band_rage_against_the_machine => Lang::En("gb")),
+        "Wrath Against the Machine"
+    );
+    assert_eq!(
+        t_raw!(stuff_with_escaped_sequences_and_double_quotes => Lang::Fr("")),
+        "Stuff with\nescaped\tsequences and \"double quotes\"",
+    );
+    assert_eq!(
+        t_raw!(stuff_with_escaped_sequences_and_double_quotes => Lang::En("")),
+        t!(stuff_with_escaped_sequences_and_double_quotes => Lang::En("")),
+    );
+    assert_eq!(
+        t_raw!(app_ruin_the_band => Lang::Fr("")),
+        "Ruiner le nom d'un groupe en le traduisant en français",
+    );
+    assert_eq!(t_raw!(new_key => Lang::En(""); "%s is new"), "%s is new");
+}
+
+pub fn t_static() {
+    // the translations without placeholders are literals usable in const contexts
+    const TOOL: &str = t_static!(band_tool => Lang::En(""));
+    static WRATH: &str = t_static!(band_rage_against_the_machine => Lang::En("gb"));
+    assert_eq!(TOOL, "Tool");
+    assert_eq!(WRATH, "Wrath Against the Machine");
+    assert_eq!(
+        t_static!(band_rage_against_the_machine => Lang::Fr("")),
+        "Colère contre la machine"
+    );
+    assert_eq!(
+        t_static!(stuff_with_escaped_sequences_and_double_quotes => Lang::Fr("")),
+        "Stuff with\nescaped\tsequences and \"double quotes\"",
+    );
+    assert_eq!(t_static!(new_key => Lang::En(""); "New"), "New");
+    match t!(band_tool => Lang::En("")).as_str() {
+        TOOL => {}
+        other => panic!("unexpected translation: {}", other),
+    }
+}
+
+pub fn known_lang() {
+    // the languages given to `const_lang!` are checked at compile time
+    const EN_GB: Lang = const_lang!(Lang::En("gb"));
+    assert_eq!(
+        t!(band_rage_against_the_machine => EN_GB),
+        "Wrath Against the Machine"
+    );
+    assert_eq!(t!(band_tool => const_lang!(Lang::Fr(""))), "Outil");
+    assert!(EN_GB.is_known());
+    assert!(!Lang::En("us").is_known());
+    // the unknown regions still fall back to their language at runtime
+    assert_eq!(
+        t!(band_rage_against_the_machine => Lang::En("us")),
+        "Rage Against the Machine"
+    );
+}
+
+pub fn sort_languages() {
+    let mut langs = vec![Lang::Fr(""), Lang::En("gb"), Lang::En("")];
+    // the languages given first, then the others alphabetically
+    langs.sort_by_key(|x| x.sort_key(&["fr"]));
+    assert_eq!(langs, vec![Lang::Fr(""), Lang::En(""), Lang::En("gb")]);
+    // a region can come before its language
+    langs.sort_by_key(|x| x.sort_key(&["en_gb", "en"]));
+    assert_eq!(langs, vec![Lang::En("gb"), Lang::En(""), Lang::Fr("")]);
+    // a language matches all its regions
+    langs.sort_by_key(|x| x.sort_key(&["en"]));
+    assert_eq!(langs, vec![Lang::En(""), Lang::En("gb"), Lang::Fr("")]);
+    assert_eq!(Lang::Fr("").sort_key(&[]), (0, "fr".to_string()));
+}
+
+pub fn nearest_lang() {
+    // exact match, the separator and the case do not matter
+    assert_eq!(Lang::nearest("en_gb"), Some(Lang::En("gb")));
+    assert_eq!(Lang::nearest("en-GB"), Some(Lang::En("gb")));
+    assert_eq!(Lang::nearest("fr"), Some(Lang::Fr("")));
+    // the region is not translated: the language without region
+    assert_eq!(Lang::nearest("en_us"), Some(Lang::En("")));
+    assert_eq!(Lang::nearest("FR-be"), Some(Lang::Fr("")));
+    // the language is not translated
+    assert_eq!(Lang::nearest("de_de"), None);
+    assert_eq!(Lang::nearest(""), None);
+}
+
+pub fn all_codes() {
+    assert_eq!(Lang::all_codes(), vec!["en", "en_gb", "fr"]);
+    assert_eq!(Lang::all_codes().len(), Lang::all_languages().len());
+}
+
+pub fn lang_code_str() {
+    assert_eq!(Lang::En("gb").code(), "en_gb");
+    assert_eq!(Lang::Fr("").code(), "fr");
+    assert_eq!(Lang::En("gb").code_static(), "en");
+    assert_eq!(Lang::Fr("").code_static(), "fr");
+}
+
+pub fn current_lang() {
+    use crate::{get_lang, set_lang, tl};
+
+    // every thread has its own current language
+    let (lang, band) = std::thread::spawn(|| (get_lang(), tl!(band_tool)))
+        .join()
+        .unwrap();
+    assert_eq!(lang, Lang::En(""));
+    assert_eq!(band, "Tool");
+
+    set_lang(Lang::Fr("be"));
+    assert_eq!(get_lang(), Lang::Fr("be"));
+    assert_eq!(tl!(band_tool), "Outil");
+    assert_eq!(tl!(format_string, "Hello", "World"), "Hello, World !");
+    assert_eq!(
+        tl!(greeting, name = "Alice", count = 3),
+        t!(greeting, name = "Alice", count = 3 => Lang::Fr("be"))
+    );
+
+    set_lang(Lang::En("gb"));
+    assert_eq!(
+        tl!(band_rage_against_the_machine),
+        "Wrath Against the Machine"
+    );
+}
+
+pub fn default_lang() {
+    use crate::{get_lang, set_lang, t_default};
+
+    // every thread has its own default language
+    let (lang, band) = std::thread::spawn(|| {
+        Lang::set_default(Lang::Fr(""));
+        (Lang::default(), t_default!(band_tool))
+    })
+    .join()
+    .unwrap();
+    assert_eq!(lang, Lang::Fr(""));
+    assert_eq!(band, "Outil");
+    assert_eq!(Lang::get_default(), Lang::En(""));
+    assert_eq!(Lang::default(), Lang::En(""));
+
+    // the default language and the current language are independent
+    set_lang(Lang::Fr(""));
+    Lang::set_default(Lang::En("gb"));
+    assert_eq!(get_lang(), Lang::Fr(""));
+    assert_eq!(Lang::default(), Lang::En("gb"));
+    assert_eq!(
+        t_default!(band_rage_against_the_machine),
+        "Wrath Against the Machine"
+    );
+    assert_eq!(t_default!(format_string, "Hello", "World"), "Hello, World!");
+}
+
+pub fn gettext() {
+    use crate::{gettext, ngettext, set_current_lang};
+
+    // nothing is translated until the current language is set
+    assert_eq!(gettext("band_tool"), "band_tool");
+    assert_eq!(ngettext("band_tool", "band_the_doors", 2), "band_the_doors");
+
+    set_current_lang(Lang::Fr("be"));
+    assert_eq!(gettext("band_tool"), "Outil");
+    assert_eq!(gettext("band_rage_against_the_machine"), "Colère contre la machine");
+    assert_eq!(gettext("unknown_key"), "unknown_key");
+    assert_eq!(ngettext("band_tool", "band_the_doors", 1), "Outil");
+    assert_eq!(ngettext("band_tool", "band_the_doors", 0), "Outil");
+    assert_eq!(ngettext("band_tool", "band_the_doors", 2), "Les portes");
+
+    set_current_lang(Lang::En("gb"));
+    assert_eq!(gettext("band_rage_against_the_machine"), "Wrath Against the Machine");
+    assert_eq!(gettext("n_files"), "%d files");
+    assert_eq!(ngettext("band_tool", "band_the_doors", 0), "The Doors");
+}
+
+#[cfg(feature = "wrong-arguments")]
+pub fn wrong_arguments() {
+    let _ = t!(format_percentage => Lang::En(""));
+    let _ = t!(format_string, "Hello", "World", "!" => Lang::En(""));
+}
+
+#[cfg(feature = "unknown-lang")]
+pub fn unknown_lang() {
+    let _ = t!(band_tool => const_lang!(Lang::En("us")));
 }
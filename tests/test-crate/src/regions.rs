@@ -0,0 +1,26 @@
+include!(concat!(env!("OUT_DIR"), "/regions.rs"));
+
+pub fn ignore_regions() {
+    // the regions are merged into their language, the last translation wins
+    assert_eq!(t!(colour => Lang::En), "Colour");
+    assert_eq!(t!(colour => Lang::Fr), "Couleur");
+    assert_eq!(plural!(n_bands, 2 => Lang::Fr), "2 groupes");
+    assert_eq!(Lang::all_languages(), &[&Lang::En, &Lang::Fr]);
+    assert_eq!(Lang::En.to_string(), "en");
+    assert_eq!(Lang::complete_languages(), [Lang::En, Lang::Fr]);
+    assert!(!is_markdown(Key::Colour));
+    // the region is dropped when parsing
+    assert_eq!(Lang::parse("en_GB"), Some(Lang::En));
+    assert_eq!(Lang::parse("fr"), Some(Lang::Fr));
+    assert_eq!(serde_json::to_string(&Lang::Fr).unwrap(), "\"fr\"");
+    let lang: Lang = serde_json::from_str("\"en_GB\"").unwrap();
+    assert_eq!(lang, Lang::En);
+}
+
+pub fn escaped_braces() {
+    // the braces are text when the named arguments are disabled, `{y}` is not the variable
+    let y = 1;
+    assert_eq!(y, 1);
+    assert_eq!(t!(braces, 2 => Lang::En), "{y} is not an argument, {2}");
+    assert_eq!(t!(braces, 2 => Lang::Fr), "{y} n'est pas un argument, {2}");
+}
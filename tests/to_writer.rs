@@ -0,0 +1,34 @@
+const INI: &str = "\
+[band_tool]
+    en = Tool
+    fr = Outil
+[n_files]
+    en.one = %d file
+    en.other = %d files
+";
+
+#[test]
+fn to_writer() {
+    // `OUT_DIR` is not used
+    std::env::remove_var("OUT_DIR");
+    let mut code = Vec::new();
+    twine::Builder::new()
+        .build_translations_to_writer(&mut [INI.as_bytes()], &mut code)
+        .unwrap();
+    let code = String::from_utf8(code).unwrap();
+    assert!(code.contains("macro_rules! t {"));
+    assert!(code.contains("macro_rules! plural {"));
+    assert!(code.contains("\"Outil\""));
+    assert!(code.contains("pub enum Lang {"));
+
+    // the same code as the file written by the other functions
+    let out_dir = tempfile::tempdir().unwrap();
+    std::env::set_var("OUT_DIR", out_dir.as_ref().as_os_str());
+    twine::build_translations_from_readers(&mut [INI.as_bytes()], "i18n.rs").unwrap();
+    let file = std::fs::read_to_string(out_dir.as_ref().join("i18n.rs")).unwrap();
+    assert_eq!(code, file);
+
+    let mut free_code = Vec::new();
+    twine::build_translations_to_writer(&mut [INI.as_bytes()], &mut free_code).unwrap();
+    assert_eq!(code.as_bytes(), free_code);
+}
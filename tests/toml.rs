@@ -0,0 +1,60 @@
+mod common;
+
+// the same translations as `tests/fixtures/translations.toml`
+#[cfg(feature = "toml")]
+const INI: &str = r#"
+[band_tool]
+    en = Tool
+    en-gb = Tool
+    fr = Outil
+[app_title]
+    maxlen = 20
+    order = -1
+    context = https://example.com/title.png
+    fuzzy = fr
+    en = Twine
+    fr = Twine
+[n_files]
+    en.one = %d file
+    en.other = %d files
+    fr.one = %d fichier
+    fr.other = %d fichiers
+[errors.not_found]
+    en = Not found,\ncheck the "path".
+    fr = C:\\Users\\%s n'existe pas
+[button_save]
+    en = Save\u0020
+    fr = \tEnregistrer\u0020
+    de = Speichern\u0020
+[__defaults__]
+    order = Order (untranslated)
+[order]
+    en = Order
+"#;
+
+#[cfg(feature = "toml")]
+#[test]
+fn toml() {
+    let out_dir = tempfile::tempdir().unwrap();
+    std::env::set_var("OUT_DIR", out_dir.as_ref().as_os_str());
+
+    let mut cargo_output = Vec::new();
+    twine::Builder::new()
+        .cargo_output(&mut cargo_output)
+        .build_translations_from_toml(&["tests/fixtures/translations.toml"], "toml.rs")
+        .unwrap();
+    let ini = common::build(twine::Builder::new().cargo_output(std::io::sink()), INI);
+    let toml = std::fs::read_to_string(out_dir.as_ref().join("toml.rs")).unwrap();
+
+    assert!(String::from_utf8(cargo_output)
+        .unwrap()
+        .starts_with("cargo:rerun-if-changed=tests/fixtures/translations.toml\n"));
+    assert_eq!(toml, ini);
+    // the multi-line strings and the escape sequences are decoded
+    assert!(toml.contains(r#""Not found,\ncheck the \"path\".""#));
+    assert!(toml.contains(r#""C:\\Users\\{:} n'existe pas""#));
+    assert!(toml.contains(r#""\tEnregistrer\u{0020}""#));
+    assert!(toml.contains("macro_rules! plural {"));
+    // the keys of `[__defaults__]` can have the name of a metadata
+    assert!(toml.contains(r#"$crate::Lang::Fr(_) => format!("Order (untranslated)""#));
+}
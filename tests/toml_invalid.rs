@@ -0,0 +1,32 @@
+#[cfg(feature = "toml")]
+#[test]
+fn invalid_toml() {
+    // the files are read before `OUT_DIR`
+    for (path, message) in [
+        (
+            "tests/fixtures/invalid.toml",
+            "arrays of tables are not supported: bands",
+        ),
+        (
+            "tests/fixtures/invalid_lang.toml",
+            "invalid plural category in [band_tool]: en.lots",
+        ),
+    ] {
+        let err = twine::Builder::new()
+            .cargo_output(std::io::sink())
+            .build_translations_from_toml(&[path], "i18n.rs")
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("could not read TOML file {}: {}", path, message),
+        );
+    }
+
+    // the syntax errors are located by the parser
+    let err = twine::Builder::new()
+        .cargo_output(std::io::sink())
+        .build_translations_from_toml(&["tests/fixtures/bands.ini"], "i18n.rs")
+        .unwrap_err();
+    assert!(matches!(err, twine::TwineError::ParseToml { .. }));
+    assert!(err.to_string().contains("line 2"), "{}", err);
+}
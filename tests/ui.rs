@@ -20,7 +20,7 @@ Stuff with\nescaped\tsequences and \"double quotes\"
 Ruin a band name by translating it in French
 Wrath Against the Machine
 Hello, World!
-% 73 % foo
+% 73% % foo
 badcafe
 Hello
 Name with a dot
@@ -31,7 +31,7 @@ Stuff with\nescaped\tsequences and \"double quotes\"
 #[test]
 fn ui() {
     let output = std::process::Command::new("cargo")
-        .args(&["run", "--manifest-path", "tests/test-crate/Cargo.toml"])
+        .args(["run", "--manifest-path", "tests/test-crate/Cargo.toml"])
         .output()
         .unwrap();
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -43,7 +43,7 @@ fn ui() {
     assert_eq!(stdout.trim(), EXPECTED_OUTPUT.trim());
 
     let output = std::process::Command::new("cargo")
-        .args(&[
+        .args([
             "clippy",
             "--manifest-path",
             "tests/test-crate/Cargo.toml",
@@ -61,3 +61,42 @@ fn ui() {
     eprintln!("stderr:\n{}\n", stderr);
     assert!(output.status.success());
 }
+
+#[test]
+fn wrong_arguments() {
+    let output = std::process::Command::new("cargo")
+        .args([
+            "build",
+            "--manifest-path",
+            "tests/test-crate/Cargo.toml",
+            "--features",
+            "wrong-arguments",
+        ])
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    eprintln!("stderr:\n{}\n", stderr);
+    assert!(!output.status.success());
+    assert!(stderr.contains("t!(format_percentage) expects 1 argument, got 0"));
+    assert!(stderr.contains("t!(format_string) expects 2 arguments, got more"));
+}
+
+#[test]
+fn unknown_lang() {
+    let output = std::process::Command::new("cargo")
+        .args([
+            "build",
+            "--manifest-path",
+            "tests/test-crate/Cargo.toml",
+            "--features",
+            "unknown-lang",
+        ])
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    eprintln!("stderr:\n{}\n", stderr);
+    assert!(!output.status.success());
+    assert!(stderr.contains("unknown language: Lang::En(\"us\")"));
+}
@@ -0,0 +1,34 @@
+mod common;
+
+use common::{build, fixture};
+
+#[test]
+fn unicode_keys() {
+    let generated = build(
+        twine::Builder::new().typed_functions(true),
+        &fixture("unicode_keys.ini"),
+    );
+
+    assert!(generated.contains("(café_button => $lang:expr"));
+    assert!(generated.contains("(ärger_über => $lang:expr"));
+    assert!(generated.contains("CaféButton,"));
+    assert!(generated.contains("ÄrgerÜber,"));
+    assert!(generated.contains("pub fn café_button("));
+    assert!(!generated.contains("caf_button"));
+}
+
+#[test]
+fn invalid_key() {
+    let err = twine::Builder::new()
+        .cargo_output(std::io::sink())
+        .build_translations_from_str(&["[1st_place]\n    en = First\n"], "i18n.rs")
+        .unwrap_err();
+
+    match err {
+        twine::TwineError::InvalidKey { key, normalized } => {
+            assert_eq!(key, "1st_place");
+            assert_eq!(normalized, "1st_place");
+        }
+        err => panic!("unexpected error: {}", err),
+    }
+}
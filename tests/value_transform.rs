@@ -0,0 +1,34 @@
+use std::fs;
+
+#[test]
+fn value_transform() {
+    let translations = r#"
+        [band_tool]
+            en = Tool
+            fr = Outil
+        [band_the_jackson_5]
+            en = The Jackson 5
+            fr = Les 5 fils de Jack, "été"
+        "#;
+    let out_dir = tempfile::tempdir().unwrap();
+    std::env::set_var("OUT_DIR", out_dir.as_ref().as_os_str());
+
+    twine::Builder::new()
+        .value_transform(Box::new(|_key, lang, value| {
+            if lang.starts_with("fr") {
+                value.to_uppercase()
+            } else {
+                value.to_string()
+            }
+        }))
+        .build_translations_from_str(&[translations], "i18n.rs")
+        .unwrap();
+    let generated = fs::read_to_string(out_dir.as_ref().join("i18n.rs")).unwrap();
+    println!("{}", generated);
+
+    assert!(generated.contains(r#"$crate::Lang::En(_) => format!("Tool" $(, $fmt_args)*),"#));
+    assert!(generated.contains(r#"$crate::Lang::Fr(_) => format!("OUTIL" $(, $fmt_args)*),"#));
+    assert!(generated.contains(
+        r#"$crate::Lang::Fr(_) => format!("LES 5 FILS DE JACK, \"ÉTÉ\"" $(, $fmt_args)*),"#
+    ));
+}
@@ -0,0 +1,20 @@
+use std::fs;
+
+#[test]
+fn version() {
+    let translations = r#"
+        [band_tool]
+            en = Tool
+        "#;
+    let out_dir = tempfile::tempdir().unwrap();
+    std::env::set_var("OUT_DIR", out_dir.as_ref().as_os_str());
+
+    twine::build_translations_from_str(&[translations], "i18n.rs").unwrap();
+    let generated = fs::read_to_string(out_dir.as_ref().join("i18n.rs")).unwrap();
+    println!("{}", generated);
+
+    assert!(generated.contains(&format!(
+        "pub const TWINE_GEN_VERSION: &str = {:?};",
+        env!("CARGO_PKG_VERSION"),
+    )));
+}
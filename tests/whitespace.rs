@@ -0,0 +1,37 @@
+#[test]
+fn check_whitespace() {
+    let out_dir = tempfile::tempdir().unwrap();
+    std::env::set_var("OUT_DIR", out_dir.as_ref().as_os_str());
+
+    let mut cargo_output = Vec::new();
+    // the differences are not errors in strict mode
+    twine::Builder::new()
+        .strict(true)
+        .check_whitespace(true)
+        .cargo_output(&mut cargo_output)
+        .build_translations(&["tests/fixtures/whitespace.ini"], "i18n.rs")
+        .unwrap();
+    let cargo_output = String::from_utf8(cargo_output).unwrap();
+
+    // the spaces around `Outil` are trimmed, ` :` is not at the end
+    assert_eq!(
+        cargo_output,
+        "\
+        cargo:rerun-if-changed=tests/fixtures/whitespace.ini\n\
+        cargo:warning=key \"button_save\" has whitespace at the start or the end that differs \
+        from \"en\" in [\"fr\"]\n\
+        cargo:warning=key \"n_files\" has whitespace at the start or the end that differs \
+        from \"en.one\" in [\"fr.one\"]\n",
+    );
+
+    // the check is disabled by default
+    let mut cargo_output = Vec::new();
+    twine::Builder::new()
+        .cargo_output(&mut cargo_output)
+        .build_translations(&["tests/fixtures/whitespace.ini"], "i18n.rs")
+        .unwrap();
+
+    assert!(!String::from_utf8(cargo_output)
+        .unwrap()
+        .contains("cargo:warning"));
+}
@@ -0,0 +1,20 @@
+use std::fs;
+
+#[test]
+fn with_lang() {
+    let out_dir = tempfile::tempdir().unwrap();
+    std::env::set_var("OUT_DIR", out_dir.as_ref().as_os_str());
+
+    let mut readers = [
+        (fs::File::open("tests/fixtures/en.ini").unwrap(), "en"),
+        (fs::File::open("tests/fixtures/fr.ini").unwrap(), "fr"),
+    ];
+    twine::build_translations_from_readers_with_lang(&mut readers, "i18n.rs").unwrap();
+    let generated = fs::read_to_string(out_dir.as_ref().join("i18n.rs")).unwrap();
+    println!("{}", generated);
+
+    assert!(generated.contains(r#"$crate::Lang::En(_) => format!("Tool" $(, $fmt_args)*),"#));
+    assert!(generated.contains(r#"$crate::Lang::Fr(_) => format!("Outil" $(, $fmt_args)*),"#));
+    assert!(generated.contains(r#"$crate::Lang::En(_) => format!("The Doors" $(, $fmt_args)*),"#));
+    assert!(generated.contains(r#"$crate::Lang::Fr(_) => format!("Les portes" $(, $fmt_args)*),"#));
+}
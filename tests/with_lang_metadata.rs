@@ -0,0 +1,15 @@
+#[test]
+fn with_lang_metadata() {
+    let out_dir = tempfile::tempdir().unwrap();
+    std::env::set_var("OUT_DIR", out_dir.as_ref().as_os_str());
+
+    // the whole line is the translation, even when it looks like a metadata
+    let mut readers = [("[sort_hint]\n    order = 5\n".as_bytes(), "en")];
+    twine::Builder::new()
+        .cargo_output(std::io::sink())
+        .build_translations_from_readers_with_lang(&mut readers, "i18n.rs")
+        .unwrap();
+    let generated = std::fs::read_to_string(out_dir.as_ref().join("i18n.rs")).unwrap();
+
+    assert!(generated.contains(r#"$crate::Lang::En(_) => format!("order = 5" $(, $fmt_args)*),"#));
+}